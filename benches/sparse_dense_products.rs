@@ -53,11 +53,96 @@ fn sparse_dense_vec_matprod_specialized(bench: &mut Bencher) {
     });
 }
 
+fn large_csr_mat() -> CsMat<f64> {
+    let n = 100_000;
+    let mut indptr = Vec::with_capacity(n + 1);
+    let mut indices = Vec::with_capacity(5 * n);
+    indptr.push(0);
+    for row in 0..n {
+        let lo = row.saturating_sub(2);
+        let hi = (row + 3).min(n);
+        indices.extend(lo..hi);
+        indptr.push(indices.len());
+    }
+    let data = vec![1.5; indices.len()];
+    CsMat::new((n, n), indptr, indices, data)
+}
+
+fn sparse_dense_matvec_serial(bench: &mut Bencher) {
+    let a = large_csr_mat();
+    let x = vec![2.; a.cols()];
+    let mut res = vec![0.; a.rows()];
+    bench.iter(|| {
+        sprs::prod::mul_acc_mat_vec_csr(a.view(), &x[..], &mut res);
+    });
+}
+
+/// Reference implementation of the CSR matrix-vector product,
+/// with bounds checking on the vector accesses
+fn sparse_dense_matvec_checked(bench: &mut Bencher) {
+    let a = large_csr_mat();
+    let x = vec![2.; a.cols()];
+    let mut res = vec![0.; a.rows()];
+    bench.iter(|| {
+        for (tv, row) in res.iter_mut().zip(a.outer_iterator()) {
+            for (col_ind, &value) in row.iter() {
+                *tv += x[col_ind] * value;
+            }
+        }
+    });
+}
+
+fn sparse_dense_matvec_csc(bench: &mut Bencher) {
+    let a = large_csr_mat().to_csc();
+    let x = vec![2.; a.cols()];
+    let mut res = vec![0.; a.rows()];
+    bench.iter(|| {
+        sprs::prod::mul_acc_mat_vec_csc(a.view(), &x[..], &mut res);
+    });
+}
+
+/// Reference implementation of the CSC matrix-vector product,
+/// with bounds checking on the result accesses
+fn sparse_dense_matvec_csc_checked(bench: &mut Bencher) {
+    let a = large_csr_mat().to_csc();
+    let x = vec![2.; a.cols()];
+    let mut res = vec![0.; a.rows()];
+    bench.iter(|| {
+        for (&xv, col) in x.iter().zip(a.outer_iterator()) {
+            for (row_ind, &value) in col.iter() {
+                res[row_ind] += xv * value;
+            }
+        }
+    });
+}
+
+#[cfg(feature = "multi_thread")]
+fn sparse_dense_matvec_par(bench: &mut Bencher) {
+    let a = large_csr_mat();
+    let x = vec![2.; a.cols()];
+    let mut res = vec![0.; a.rows()];
+    bench.iter(|| {
+        sprs::prod::mul_acc_mat_vec_csr_par(a.view(), &x[..], &mut res);
+    });
+}
+
 benchmark_group!(
     benches,
+    sparse_dense_matvec_serial,
+    sparse_dense_matvec_checked,
+    sparse_dense_matvec_csc,
+    sparse_dense_matvec_csc_checked,
     sparse_dense_dotprod_default,
     sparse_dense_dotprod_specialized,
     sparse_dense_vec_matprod_specialized,
     sparse_dense_vec_matprod_default
 );
+// benchmark_group! does not accept attributes on its entries, so the
+// benchmarks relying on optional features get their own group
+#[cfg(feature = "multi_thread")]
+benchmark_group!(benches_multi_thread, sparse_dense_matvec_par);
+
+#[cfg(feature = "multi_thread")]
+benchmark_main!(benches, benches_multi_thread);
+#[cfg(not(feature = "multi_thread"))]
 benchmark_main!(benches);
@@ -1,6 +1,6 @@
 use alga::general::{Additive, TwoSidedInverse};
 use bencher::{benchmark_group, benchmark_main, Bencher};
-use sprs::CsVec;
+use sprs::{CompressedStorage, CsMat, CsVec};
 
 fn csvec_neg(bench: &mut Bencher) {
     let vector =
@@ -14,5 +14,98 @@ fn csvec_additive_inverse(bench: &mut Bencher) {
     bench.iter(|| TwoSidedInverse::<Additive>::two_sided_inverse(&vector));
 }
 
-benchmark_group!(benches, csvec_neg, csvec_additive_inverse);
+fn csmat_append_outer_empty(bench: &mut Bencher) {
+    let row: Vec<f64> = (0..1000).map(|i| (i % 3) as f64).collect();
+    bench.iter(|| {
+        let mut mat = CsMat::empty(CompressedStorage::CSR, 1000);
+        for _ in 0..1000 {
+            mat = mat.append_outer(&row);
+        }
+        mat
+    });
+}
+
+fn csmat_append_outer_with_capacity(bench: &mut Bencher) {
+    let row: Vec<f64> = (0..1000).map(|i| (i % 3) as f64).collect();
+    let nnz = row.iter().filter(|&&x| x != 0.).count() * 1000;
+    bench.iter(|| {
+        let mut mat =
+            CsMat::with_capacity(CompressedStorage::CSR, 1000, 1000, nnz);
+        for _ in 0..1000 {
+            mat = mat.append_outer(&row);
+        }
+        mat
+    });
+}
+
+#[cfg(feature = "multi_thread")]
+fn csr_mul_csr_matrix() -> CsMat<f64> {
+    let n = 300;
+    let mut triplets = sprs::TriMat::new((n * n, n * n));
+    for i in 0..n * n {
+        triplets.add_triplet(i, i, 4.);
+        for &j in &[i + 1, i + n] {
+            if j < n * n {
+                triplets.add_triplet(i, j, -1.);
+                triplets.add_triplet(j, i, -1.);
+            }
+        }
+    }
+    triplets.to_csr()
+}
+
+#[cfg(feature = "multi_thread")]
+fn csr_mul_csr_serial(bench: &mut Bencher) {
+    let a = csr_mul_csr_matrix();
+    sprs::smmp::set_thread_threading_strategy(
+        sprs::smmp::ThreadingStrategy::Fixed(1),
+    );
+    bench.iter(|| &a * &a);
+}
+
+#[cfg(feature = "multi_thread")]
+fn csr_mul_csr_parallel(bench: &mut Bencher) {
+    let a = csr_mul_csr_matrix();
+    sprs::smmp::set_thread_threading_strategy(
+        sprs::smmp::ThreadingStrategy::Automatic,
+    );
+    bench.iter(|| &a * &a);
+}
+
+fn lu_banded(bench: &mut Bencher) {
+    // the factorization of a tridiagonal matrix should scale linearly
+    let n = 10_000;
+    let mut triplets = sprs::TriMat::new((n, n));
+    for i in 0..n {
+        triplets.add_triplet(i, i, 2.);
+        if i > 0 {
+            triplets.add_triplet(i, i - 1, -1.);
+            triplets.add_triplet(i - 1, i, -1.);
+        }
+    }
+    let a: CsMat<f64> = triplets.to_csc();
+    bench.iter(|| sprs::linalg::lu::lu(a.view()).unwrap());
+}
+
+benchmark_group!(
+    benches,
+    csvec_neg,
+    csvec_additive_inverse,
+    csmat_append_outer_empty,
+    csmat_append_outer_with_capacity,
+    lu_banded
+);
+
+// benchmark_group! does not accept attributes on its entries, so the
+// benchmarks relying on optional features get their own group
+#[cfg(feature = "multi_thread")]
+benchmark_group!(
+    benches_multi_thread,
+    csr_mul_csr_serial,
+    csr_mul_csr_parallel
+);
+
+#[cfg(feature = "multi_thread")]
+benchmark_main!(benches, benches_multi_thread);
+#[cfg(not(feature = "multi_thread"))]
 benchmark_main!(benches);
@@ -38,28 +38,29 @@ where
     let mut indptr = Vec::with_capacity(shape.0 + 1);
     let mut indices = Vec::with_capacity(exp_nnz);
     let mut data = Vec::with_capacity(exp_nnz);
-    // sample row indices
+    // sample the number of non-zeros in each row, a row cannot
+    // hold more than shape.1 non-zeros
+    let mut row_counts = vec![0; shape.0];
     for _ in 0..exp_nnz {
-        indices.push(I::from_usize(rng.gen_range(0, shape.0)));
+        loop {
+            let row = rng.gen_range(0, shape.0);
+            if row_counts[row] < shape.1 {
+                row_counts[row] += 1;
+                break;
+            }
+        }
         // Note: there won't be any correspondence between the data
         // sampled here and the row sampled before, but this does not matter
         // as we are sampling.
         data.push(dist.sample(rng));
     }
-    indices.sort_unstable();
     indptr.push(I::from_usize(0));
     let mut count = 0;
-    for &row in &indices {
-        while indptr.len() != row.index() + 1 {
-            indptr.push(I::from_usize(count));
-        }
-        count += 1;
-    }
-    while indptr.len() != shape.0 + 1 {
+    for row_count in row_counts {
+        count += row_count;
         indptr.push(I::from_usize(count));
     }
     assert_eq!(indptr.last().unwrap().index(), exp_nnz);
-    indices.clear();
     for row in 0..shape.0 {
         let start = indptr[row].index();
         let end = indptr[row + 1].index();
@@ -84,6 +85,24 @@ where
     CsMatI::new(shape, indptr, indices, data)
 }
 
+/// Generate a random sparse matrix in the CSC format, matching the given
+/// density and sampling the values of its non-zero elements from the
+/// provided distribution.
+pub fn rand_csc<R, N, D, I>(
+    rng: &mut R,
+    dist: D,
+    shape: (usize, usize),
+    density: f64,
+) -> CsMatI<N, I>
+where
+    R: Rng + ?Sized,
+    D: Distribution<N>,
+    N: Copy,
+    I: SpIndex,
+{
+    rand_csr(rng, dist, (shape.1, shape.0), density).transpose_into()
+}
+
 /// Convenient wrapper for the common case of sampling a matrix with standard
 /// normal distribution of the nnz values, using a lightweight rng.
 pub fn rand_csr_std(shape: (usize, usize), density: f64) -> CsMat<f64> {
@@ -119,6 +138,37 @@ mod tests {
         assert!(mat.density() < 0.32);
     }
 
+    #[test]
+    fn random_csr_is_reproducible() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mat1: CsMat<f64> =
+            super::rand_csr(&mut rng, Standard, (50, 40), 0.1);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mat2: CsMat<f64> =
+            super::rand_csr(&mut rng, Standard, (50, 40), 0.1);
+        assert_eq!(mat1, mat2);
+    }
+
+    #[test]
+    fn random_csr_full() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1234);
+        let mat: CsMat<f64> = super::rand_csr(&mut rng, Standard, (20, 3), 1.);
+        assert_eq!(mat.nnz(), 60);
+        assert_eq!(mat.density(), 1.);
+    }
+
+    #[test]
+    fn random_csc() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1234);
+        let mat: CsMat<f64> =
+            super::rand_csc(&mut rng, Standard, (100, 70), 0.3);
+        assert!(mat.is_csc());
+        assert_eq!(mat.shape(), (100, 70));
+        assert!(mat.density() > 0.25);
+        assert!(mat.density() < 0.35);
+        assert!(mat.check_compressed_structure().is_ok());
+    }
+
     #[test]
     fn random_csr_std() {
         let mat = super::rand_csr_std((100, 1000), 0.2);
@@ -9,6 +9,11 @@ pub enum SprsError {
     UnsortedIndptr,
     SingularMatrix,
     IllegalArguments(&'static str),
+    /// An iterative solver did not reach the requested tolerance,
+    /// the norm of the last residual is reported
+    NotConverged {
+        residual: f64,
+    },
 }
 
 use self::SprsError::*;
@@ -20,6 +25,7 @@ impl SprsError {
             UnsortedIndptr => "indptr is not sorted",
             SingularMatrix => "matrix is singular",
             IllegalArguments(s) => s,
+            NotConverged { .. } => "iterative solver did not converge",
         }
     }
 }
@@ -32,6 +38,108 @@ impl Error for SprsError {
 
 impl fmt::Display for SprsError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        self.descr().fmt(f)
+        match *self {
+            NotConverged { residual } => write!(
+                f,
+                "iterative solver did not converge (residual norm: {})",
+                residual
+            ),
+            _ => self.descr().fmt(f),
+        }
+    }
+}
+
+/// Describes why the compressed structure of a sparse matrix is invalid,
+/// as reported by `CsMatBase::check_structure`.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum StructureError {
+    /// An indptr or an index value cannot be represented as an usize
+    IndexConversion,
+    /// indptr does not have `outer_dims() + 1` elements
+    IndptrLength { expected: usize, found: usize },
+    /// indptr does not start with 0
+    IndptrStart,
+    /// indptr is not monotonically non-decreasing
+    UnsortedIndptr,
+    /// indptr values are too large to describe an allocatable matrix
+    IndptrOverflow,
+    /// indices and data have different lengths
+    IndicesDataLength { indices: usize, data: usize },
+    /// the last value of indptr does not match the number of indices
+    IndptrEnd { nnz: usize, indices: usize },
+    /// the inner indices of the given outer slice are not strictly
+    /// increasing
+    UnsortedIndices { outer: usize },
+    /// an inner index of the given outer slice is not lower than the
+    /// inner dimension
+    OutOfBoundsIndex { outer: usize, index: usize },
+}
+
+impl fmt::Display for StructureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::StructureError::*;
+        match *self {
+            IndexConversion => {
+                write!(f, "an index value is out of range of usize")
+            }
+            IndptrLength { expected, found } => {
+                write!(f, "indptr has length {}, expected {}", found, expected)
+            }
+            IndptrStart => write!(f, "indptr does not start with 0"),
+            UnsortedIndptr => write!(f, "indptr is not sorted"),
+            IndptrOverflow => {
+                write!(f, "an indptr value is larger than allowed")
+            }
+            IndicesDataLength { indices, data } => write!(
+                f,
+                "indices and data lengths differ ({} and {})",
+                indices, data
+            ),
+            IndptrEnd { nnz, indices } => write!(
+                f,
+                "indptr describes {} non-zeros, but {} indices are stored",
+                nnz, indices
+            ),
+            UnsortedIndices { outer } => {
+                write!(f, "indices of outer slice {} are not sorted", outer)
+            }
+            OutOfBoundsIndex { outer, index } => write!(
+                f,
+                "index {} of outer slice {} is out of bounds",
+                index, outer
+            ),
+        }
+    }
+}
+
+impl Error for StructureError {}
+
+impl From<StructureError> for SprsError {
+    fn from(err: StructureError) -> SprsError {
+        match err {
+            StructureError::UnsortedIndptr => UnsortedIndptr,
+            StructureError::UnsortedIndices { .. } => NonSortedIndices,
+            StructureError::IndexConversion => {
+                IllegalArguments("Index value out of range of usize")
+            }
+            StructureError::IndptrLength { .. } => {
+                IllegalArguments("Indptr length does not match dimension")
+            }
+            StructureError::IndptrStart => {
+                IllegalArguments("Indptr does not start with 0")
+            }
+            StructureError::IndptrOverflow => {
+                IllegalArguments("An indptr value is larger than allowed")
+            }
+            StructureError::IndicesDataLength { .. } => {
+                IllegalArguments("Indices and data lengths do not match")
+            }
+            StructureError::IndptrEnd { .. } => {
+                IllegalArguments("Indices length and indptr's nnz do not match")
+            }
+            StructureError::OutOfBoundsIndex { .. } => {
+                IllegalArguments("Out of bounds index")
+            }
+        }
     }
 }
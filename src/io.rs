@@ -4,20 +4,26 @@ use std::error::Error;
 use std::fmt;
 use std::fs::File;
 use std::io;
-use std::io::{Seek, SeekFrom, Write};
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::Path;
 
+use num_complex::Complex;
 use num_traits::cast::NumCast;
 
 use crate::indexing::SpIndex;
 use crate::num_kinds::{NumKind, PrimitiveKind};
-use crate::sparse::{SparseMat, TriMatI};
+use crate::sparse::{
+    CompressedStorage, CsMatI, CsMatViewI, SparseMat, TriMatI,
+};
+use crate::Shape;
 
 #[derive(Debug)]
 pub enum IoError {
     Io(io::Error),
     BadMatrixMarketFile,
     UnsupportedMatrixMarketFormat,
+    BadCsvFile,
+    BadBinaryFile,
 }
 
 use self::IoError::*;
@@ -32,6 +38,8 @@ impl fmt::Display for IoError {
             IoError::UnsupportedMatrixMarketFormat => {
                 write!(f, "Bad matrix market file.")
             }
+            IoError::BadCsvFile => write!(f, "Bad csv file."),
+            IoError::BadBinaryFile => write!(f, "Bad binary file."),
         }
     }
 }
@@ -53,6 +61,8 @@ impl PartialEq for IoError {
             IoError::UnsupportedMatrixMarketFormat => {
                 matches!(*rhs, IoError::UnsupportedMatrixMarketFormat)
             }
+            IoError::BadCsvFile => matches!(*rhs, IoError::BadCsvFile),
+            IoError::BadBinaryFile => matches!(*rhs, IoError::BadBinaryFile),
             _ => false,
         }
     }
@@ -103,8 +113,10 @@ fn parse_header(header: &str) -> Result<(SymmetryMode, DataType), IoError> {
 /// Read a sparse matrix file in the Matrix Market format and return a
 /// corresponding triplet matrix.
 ///
-/// Presently, only general matrices are supported, but symmetric and hermitian
-/// matrices should be supported in the future.
+/// General, symmetric and skew-symmetric matrices with integer or real
+/// values are supported. The 1-based indices of the file are converted
+/// to 0-based indices, and lines starting with `%` after the header are
+/// skipped. Complex and hermitian matrices are not supported yet.
 pub fn read_matrix_market<N, I, P>(mm_file: P) -> Result<TriMatI<N, I>, IoError>
 where
     I: SpIndex,
@@ -120,8 +132,10 @@ where
 /// Read a sparse matrix in the Matrix Market format from an `io::BufRead` and return a
 /// corresponding triplet matrix.
 ///
-/// Presently, only general matrices are supported, but symmetric and hermitian
-/// matrices should be supported in the future.
+/// General, symmetric and skew-symmetric matrices with integer or real
+/// values are supported. The 1-based indices of the file are converted
+/// to 0-based indices, and lines starting with `%` after the header are
+/// skipped. Complex and hermitian matrices are not supported yet.
 pub fn read_matrix_market_from_bufread<N, I, R>(
     reader: &mut R,
 ) -> Result<TriMatI<N, I>, IoError>
@@ -149,7 +163,11 @@ where
     loop {
         line.clear();
         let len = reader.read_line(&mut line)?;
-        if len == 0 || line.starts_with('%') {
+        if len == 0 {
+            // the file ended before the shape line
+            return Err(BadMatrixMarketFile);
+        }
+        if line.starts_with('%') || line.split_whitespace().next().is_none() {
             continue;
         } else {
             break;
@@ -255,6 +273,81 @@ where
     ))
 }
 
+/// Read a sparse matrix of the given shape from delimited text, where each
+/// line holds one triplet `row,col,value` with 0-based indices.
+///
+/// The fields are separated by `delimiter`, and surrounding whitespace is
+/// ignored. If `has_header` is true, the first line is skipped. Empty lines
+/// are skipped. Duplicate coordinates are summed, and the matrix is
+/// compressed into the requested storage.
+///
+/// Returns `IoError::BadCsvFile` if a line does not hold exactly three
+/// fields, if a field cannot be parsed, or if an index is out of bounds.
+///
+/// # Example
+///
+/// ```rust
+/// use sprs::io::read_csv_triplets;
+/// use sprs::{CsMat, CSR};
+/// let csv = "row,col,value\n0,1,2.5\n1,0,1\n0,1,0.5\n";
+/// let mat: CsMat<f64> =
+///     read_csv_triplets(&mut csv.as_bytes(), (2, 2), CSR, ',', true)
+///         .unwrap();
+/// assert_eq!(mat.get(0, 1), Some(&3.));
+/// assert_eq!(mat.get(1, 0), Some(&1.));
+/// ```
+pub fn read_csv_triplets<N, I, R>(
+    reader: &mut R,
+    shape: Shape,
+    storage: CompressedStorage,
+    delimiter: char,
+    has_header: bool,
+) -> Result<CsMatI<N, I>, IoError>
+where
+    I: SpIndex,
+    N: NumCast + Clone + num_traits::Num,
+    R: io::BufRead,
+{
+    let (rows, cols) = shape;
+    let mut triplets = TriMatI::new(shape);
+    let mut line = String::new();
+    if has_header {
+        reader.read_line(&mut line)?;
+    }
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut fields = line.split(delimiter).map(str::trim);
+        let mut parse_index = |bound: usize| {
+            fields
+                .next()
+                .and_then(|s| s.parse::<usize>().ok())
+                .filter(|&ind| ind < bound)
+                .ok_or(BadCsvFile)
+        };
+        let row = parse_index(rows)?;
+        let col = parse_index(cols)?;
+        let val = fields
+            .next()
+            .and_then(|s| s.parse::<f64>().ok())
+            .and_then(NumCast::from)
+            .ok_or(BadCsvFile)?;
+        if fields.next().is_some() {
+            return Err(BadCsvFile);
+        }
+        triplets.add_triplet(row, col, val);
+    }
+    Ok(match storage {
+        CompressedStorage::CSR => triplets.to_csr(),
+        CompressedStorage::CSC => triplets.to_csc(),
+    })
+}
+
 /// Write a sparse matrix into the matrix market format.
 ///
 /// # Example
@@ -279,9 +372,37 @@ where
     M: IntoIterator<Item = (&'a N, (I, I))> + SparseMat,
     P: AsRef<Path>,
 {
-    let (rows, cols, nnz) = (mat.rows(), mat.cols(), mat.nnz());
     let f = File::create(path)?;
     let mut writer = io::BufWriter::new(f);
+    write_matrix_market_to_bufwrite(&mut writer, mat)
+}
+
+/// Write a sparse matrix into the matrix market format, using an
+/// `io::Write`.
+///
+/// The indices are written 1-based, as specified by the format.
+///
+/// # Example
+///
+/// ```rust
+/// use sprs::{CsMat};
+/// let eye : CsMat<f64> = CsMat::eye(2);
+/// let mut buf = Vec::new();
+/// sprs::io::write_matrix_market_to_bufwrite(&mut buf, &eye).unwrap();
+/// let text = String::from_utf8(buf).unwrap();
+/// assert!(text.ends_with("2 2 2\n1 1 1\n2 2 1\n"));
+/// ```
+pub fn write_matrix_market_to_bufwrite<'a, N, I, M, W>(
+    writer: &mut W,
+    mat: M,
+) -> Result<(), io::Error>
+where
+    I: 'a + SpIndex + fmt::Display,
+    N: 'a + PrimitiveKind + Copy + fmt::Display,
+    M: IntoIterator<Item = (&'a N, (I, I))> + SparseMat,
+    W: io::Write,
+{
+    let (rows, cols, nnz) = (mat.rows(), mat.cols(), mat.nnz());
 
     // header
     let data_type = match N::num_kind() {
@@ -419,13 +540,248 @@ where
     Ok(())
 }
 
+/// Scalar types that can be stored in the binary format of `write_bin`
+/// and `read_bin`.
+pub trait BinaryScalar: Copy {
+    /// Tag identifying the type in the binary header
+    const TAG: u8;
+    /// Number of bytes of the little-endian representation
+    const WIDTH: usize;
+    /// Append the little-endian representation to `buf`
+    fn write_le(&self, buf: &mut Vec<u8>);
+    /// Read from a little-endian representation of `WIDTH` bytes
+    fn read_le(bytes: &[u8]) -> Self;
+}
+
+macro_rules! binary_scalar_impl {
+    ($prim: ty, $tag: expr) => {
+        impl BinaryScalar for $prim {
+            const TAG: u8 = $tag;
+            const WIDTH: usize = std::mem::size_of::<$prim>();
+            fn write_le(&self, buf: &mut Vec<u8>) {
+                buf.extend_from_slice(&self.to_le_bytes());
+            }
+            fn read_le(bytes: &[u8]) -> Self {
+                let mut arr = [0; std::mem::size_of::<$prim>()];
+                arr.copy_from_slice(bytes);
+                <$prim>::from_le_bytes(arr)
+            }
+        }
+    };
+}
+
+binary_scalar_impl!(i8, 1);
+binary_scalar_impl!(u8, 2);
+binary_scalar_impl!(i16, 3);
+binary_scalar_impl!(u16, 4);
+binary_scalar_impl!(i32, 5);
+binary_scalar_impl!(u32, 6);
+binary_scalar_impl!(i64, 7);
+binary_scalar_impl!(u64, 8);
+binary_scalar_impl!(f32, 9);
+binary_scalar_impl!(f64, 10);
+
+macro_rules! binary_complex_impl {
+    ($prim: ty, $tag: expr) => {
+        impl BinaryScalar for Complex<$prim> {
+            const TAG: u8 = $tag;
+            const WIDTH: usize = 2 * <$prim as BinaryScalar>::WIDTH;
+            fn write_le(&self, buf: &mut Vec<u8>) {
+                self.re.write_le(buf);
+                self.im.write_le(buf);
+            }
+            fn read_le(bytes: &[u8]) -> Self {
+                let (re, im) = bytes.split_at(Self::WIDTH / 2);
+                Complex::new(<$prim>::read_le(re), <$prim>::read_le(im))
+            }
+        }
+    };
+}
+
+binary_complex_impl!(f32, 11);
+binary_complex_impl!(f64, 12);
+
+const BIN_MAGIC: &[u8; 8] = b"SPRSBIN\0";
+const BIN_VERSION: u8 = 1;
+
+fn write_bin_indices<I: SpIndex>(buf: &mut Vec<u8>, inds: &[I]) {
+    let width = std::mem::size_of::<I>();
+    for ind in inds {
+        buf.extend_from_slice(&(ind.index() as u64).to_le_bytes()[..width]);
+    }
+}
+
+/// Read exactly `len` bytes, without trusting `len` for allocation
+fn read_bin_bytes<R: io::Read>(
+    reader: &mut R,
+    len: usize,
+) -> Result<Vec<u8>, IoError> {
+    let mut buf = Vec::new();
+    reader.take(len as u64).read_to_end(&mut buf)?;
+    if buf.len() != len {
+        return Err(BadBinaryFile);
+    }
+    Ok(buf)
+}
+
+fn read_bin_indices<R: io::Read, I: SpIndex>(
+    reader: &mut R,
+    len: usize,
+    width: usize,
+) -> Result<Vec<I>, IoError> {
+    let nbytes = len.checked_mul(width).ok_or(BadBinaryFile)?;
+    let bytes = read_bin_bytes(reader, nbytes)?;
+    bytes
+        .chunks(width)
+        .map(|chunk| {
+            let mut arr = [0; 8];
+            arr[..width].copy_from_slice(chunk);
+            let ind = u64::from_le_bytes(arr);
+            std::convert::TryFrom::try_from(ind)
+                .ok()
+                .and_then(I::try_from_usize)
+                .ok_or(BadBinaryFile)
+        })
+        .collect()
+}
+
+/// Write a sparse matrix in a compact binary format.
+///
+/// The format starts with a header holding a magic number, a format
+/// version, the storage, the widths of the index types and a tag for
+/// the scalar type, and the shape and number of non-zeros of the matrix,
+/// stored as little-endian `u64`. It is followed by the raw `indptr`,
+/// `indices` and `data` arrays, in little-endian.
+///
+/// Such a file can be read back using `read_bin`, which is much faster
+/// than parsing a text format.
+///
+/// # Example
+///
+/// ```rust
+/// use sprs::io::{read_bin, write_bin};
+/// use sprs::CsMat;
+/// let mat = CsMat::new((2, 3), vec![0, 2, 3], vec![0, 2, 1],
+///                      vec![1., 2., 3.]);
+/// let mut buf = Vec::new();
+/// write_bin(&mut buf, mat.view()).unwrap();
+/// let read: CsMat<f64> = read_bin(&mut buf.as_slice()).unwrap();
+/// assert_eq!(read, mat);
+/// ```
+pub fn write_bin<N, I, Iptr, W>(
+    writer: &mut W,
+    mat: CsMatViewI<N, I, Iptr>,
+) -> Result<(), IoError>
+where
+    N: BinaryScalar,
+    I: SpIndex,
+    Iptr: SpIndex,
+    W: io::Write,
+{
+    // the indptr of a view can start at a non zero offset
+    let indptr = mat.indptr();
+    let offset = indptr[0].index();
+    let end = indptr[indptr.len() - 1].index();
+    let nnz = end - offset;
+    let mut buf = Vec::with_capacity(
+        37 + std::mem::size_of_val(indptr)
+            + nnz * (std::mem::size_of::<I>() + N::WIDTH),
+    );
+    buf.extend_from_slice(BIN_MAGIC);
+    buf.push(BIN_VERSION);
+    buf.push(match mat.storage() {
+        CompressedStorage::CSR => 0,
+        CompressedStorage::CSC => 1,
+    });
+    buf.push(std::mem::size_of::<I>() as u8);
+    buf.push(std::mem::size_of::<Iptr>() as u8);
+    buf.push(N::TAG);
+    for &dim in &[mat.rows(), mat.cols(), nnz] {
+        buf.extend_from_slice(&(dim as u64).to_le_bytes());
+    }
+    for ptr in indptr {
+        buf.extend_from_slice(
+            &((ptr.index() - offset) as u64).to_le_bytes()
+                [..std::mem::size_of::<Iptr>()],
+        );
+    }
+    write_bin_indices(&mut buf, &mat.indices()[offset..end]);
+    for val in &mat.data()[offset..end] {
+        val.write_le(&mut buf);
+    }
+    writer.write_all(&buf)?;
+    Ok(())
+}
+
+/// Read a sparse matrix written by `write_bin`.
+///
+/// The index types need not be the same as the ones used when writing,
+/// as long as they can represent the stored indices, but the scalar type
+/// must match.
+///
+/// Returns `IoError::BadBinaryFile` if the header is invalid, if the
+/// stream is truncated, or if the stored arrays do not describe a valid
+/// compressed matrix.
+pub fn read_bin<N, I, Iptr, R>(
+    reader: &mut R,
+) -> Result<CsMatI<N, I, Iptr>, IoError>
+where
+    N: BinaryScalar,
+    I: SpIndex,
+    Iptr: SpIndex,
+    R: io::Read,
+{
+    let header = read_bin_bytes(reader, 37)?;
+    if &header[..8] != BIN_MAGIC || header[8] != BIN_VERSION {
+        return Err(BadBinaryFile);
+    }
+    let storage = match header[9] {
+        0 => CompressedStorage::CSR,
+        1 => CompressedStorage::CSC,
+        _ => return Err(BadBinaryFile),
+    };
+    let (ind_width, ptr_width) = (header[10] as usize, header[11] as usize);
+    if ![1, 2, 4, 8].contains(&ind_width)
+        || ![1, 2, 4, 8].contains(&ptr_width)
+        || header[12] != N::TAG
+    {
+        return Err(BadBinaryFile);
+    }
+    let mut dims = header[13..].chunks(8).map(|chunk| {
+        let mut arr = [0; 8];
+        arr.copy_from_slice(chunk);
+        std::convert::TryFrom::try_from(u64::from_le_bytes(arr))
+            .map_err(|_| BadBinaryFile)
+    });
+    let rows: usize = dims.next().unwrap()?;
+    let cols: usize = dims.next().unwrap()?;
+    let nnz: usize = dims.next().unwrap()?;
+    let outer = match storage {
+        CompressedStorage::CSR => rows,
+        CompressedStorage::CSC => cols,
+    };
+    let outer_len = outer.checked_add(1).ok_or(BadBinaryFile)?;
+    let indptr = read_bin_indices(reader, outer_len, ptr_width)?;
+    let indices = read_bin_indices(reader, nnz, ind_width)?;
+    let data_len = nnz.checked_mul(N::WIDTH).ok_or(BadBinaryFile)?;
+    let data = read_bin_bytes(reader, data_len)?
+        .chunks(N::WIDTH)
+        .map(N::read_le)
+        .collect();
+    let mat = CsMatI::new_trusted(storage, (rows, cols), indptr, indices, data);
+    mat.check_structure().map_err(|_| BadBinaryFile)?;
+    Ok(mat)
+}
+
 #[cfg(test)]
 mod test {
     use super::{
-        read_matrix_market, read_matrix_market_from_bufread,
-        write_matrix_market, write_matrix_market_sym, IoError, SymmetryMode,
+        read_bin, read_csv_triplets, read_matrix_market,
+        read_matrix_market_from_bufread, write_bin, write_matrix_market,
+        write_matrix_market_sym, write_matrix_market_to_bufwrite, IoError,
+        SymmetryMode,
     };
-    use crate::CsMat;
+    use crate::{CsMat, CSC, CSR};
     use tempdir::TempDir;
     #[test]
     fn simple_matrix_market_read() {
@@ -461,6 +817,51 @@ mod test {
         );
     }
 
+    #[test]
+    fn matrix_market_read_from_str() {
+        let mm = "%%MatrixMarket matrix coordinate real general\n\
+                  % a comment line\n\
+                  %\n\
+                  \n\
+                  3 4 4\n\
+                  1 1 2.5\n\
+                  3 4 -1\n\
+                  2 2 1e1\n\
+                  1 3 4\n";
+        let mut reader = std::io::BufReader::new(mm.as_bytes());
+        let mat = read_matrix_market_from_bufread::<f64, usize, _>(&mut reader)
+            .unwrap();
+        assert_eq!(mat.shape(), (3, 4));
+        assert_eq!(mat.row_inds(), &[0, 2, 1, 0]);
+        assert_eq!(mat.col_inds(), &[0, 3, 1, 2]);
+        assert_eq!(mat.data(), &[2.5, -1., 10., 4.]);
+        let expected = CsMat::new(
+            (3, 4),
+            vec![0, 2, 3, 4],
+            vec![0, 2, 1, 3],
+            vec![2.5, 4., 10., -1.],
+        );
+        assert_eq!(mat.to_csr(), expected);
+    }
+
+    #[test]
+    fn matrix_market_read_fail_no_shape() {
+        let mm = "%%MatrixMarket matrix coordinate real general\n% comment\n";
+        let mut reader = std::io::BufReader::new(mm.as_bytes());
+        let res = read_matrix_market_from_bufread::<f64, usize, _>(&mut reader);
+        assert_eq!(res.unwrap_err(), IoError::BadMatrixMarketFile);
+    }
+
+    #[test]
+    fn matrix_market_read_fail_zero_index() {
+        let mm = "%%MatrixMarket matrix coordinate real general\n\
+                  2 2 1\n\
+                  0 1 1.\n";
+        let mut reader = std::io::BufReader::new(mm.as_bytes());
+        let res = read_matrix_market_from_bufread::<f64, usize, _>(&mut reader);
+        assert_eq!(res.unwrap_err(), IoError::BadMatrixMarketFile);
+    }
+
     #[test]
     fn int_matrix_market_read() {
         let path = "data/matrix_market/simple_int.mm";
@@ -509,6 +910,32 @@ mod test {
         assert_eq!(mat, mat3);
     }
 
+    #[test]
+    fn write_read_matrix_market_in_memory() {
+        let mat = CsMat::new_csc(
+            (2, 3),
+            vec![0, 1, 1, 3],
+            vec![1, 0, 1],
+            vec![1.5, -2., 3.],
+        );
+        let mut buf = Vec::new();
+        write_matrix_market_to_bufwrite(&mut buf, &mat).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        let expected = "%%MatrixMarket matrix coordinate real general\n\
+                        % written by sprs\n\
+                        2 3 3\n\
+                        2 1 1.5\n\
+                        1 3 -2\n\
+                        2 3 3\n";
+        assert_eq!(text, expected);
+
+        let mut reader = std::io::BufReader::new(text.as_bytes());
+        let mat2 =
+            read_matrix_market_from_bufread::<f64, usize, _>(&mut reader)
+                .unwrap();
+        assert_eq!(mat2.to_csc(), mat);
+    }
+
     #[test]
     fn read_write_read_matrix_market_via_csc() {
         let path = "data/matrix_market/simple.mm";
@@ -598,4 +1025,145 @@ mod test {
         let mat2 = read_matrix_market::<i32, usize, _>(&save_path).unwrap();
         assert_eq!(mat, mat2.to_csr());
     }
+
+    #[test]
+    fn csv_triplets_read() {
+        let csv = "row;col;value\n\
+                   0; 0; 2.5\n\
+                   2;3;-1\n\
+                   \n\
+                   1;1;1e1\n\
+                   2;3;4\n";
+        let mat: CsMat<f64> =
+            read_csv_triplets(&mut csv.as_bytes(), (3, 4), CSR, ';', true)
+                .unwrap();
+        // the duplicate entry at (2, 3) is summed
+        let expected = CsMat::new(
+            (3, 4),
+            vec![0, 1, 2, 3],
+            vec![0, 1, 3],
+            vec![2.5, 10., 3.],
+        );
+        assert_eq!(mat, expected);
+        assert!(mat.is_csr());
+        let mat_csc: CsMat<f64> = read_csv_triplets(
+            &mut csv
+                .lines()
+                .skip(1)
+                .collect::<Vec<_>>()
+                .join("\n")
+                .as_bytes(),
+            (3, 4),
+            CSC,
+            ';',
+            false,
+        )
+        .unwrap();
+        assert!(mat_csc.is_csc());
+        assert!(mat_csc.logically_eq(&expected));
+    }
+
+    #[test]
+    fn csv_triplets_read_fail() {
+        let read = |csv: &str| {
+            read_csv_triplets::<f64, usize, _>(
+                &mut csv.as_bytes(),
+                (2, 2),
+                CSR,
+                ',',
+                false,
+            )
+        };
+        assert!(read("0,1,1\n1,1,2\n").is_ok());
+        // out of bounds index
+        assert_eq!(read("0,2,1\n").unwrap_err(), IoError::BadCsvFile);
+        // unparsable value
+        assert_eq!(read("0,1,a\n").unwrap_err(), IoError::BadCsvFile);
+        // header without has_header
+        assert_eq!(read("r,c,v\n0,1,1\n").unwrap_err(), IoError::BadCsvFile);
+        // too many or too few fields
+        assert_eq!(read("0,1,1,1\n").unwrap_err(), IoError::BadCsvFile);
+        assert_eq!(read("0,1\n").unwrap_err(), IoError::BadCsvFile);
+    }
+
+    #[test]
+    fn bin_round_trip() {
+        let mat = crate::test_data::mat1();
+        let mut buf = Vec::new();
+        write_bin(&mut buf, mat.view()).unwrap();
+        assert_eq!(buf.len(), 37 + 6 * 8 + 7 * 8 + 7 * 8);
+        let read: CsMat<f64> = read_bin(&mut buf.as_slice()).unwrap();
+        assert_eq!(read, mat);
+        assert_eq!(read.indptr(), mat.indptr());
+
+        // smaller index types can be written and read
+        let mat_csc: crate::CsMatI<f64, u16, u32> =
+            crate::test_data::mat1_csc().to_other_types();
+        let mut buf = Vec::new();
+        write_bin(&mut buf, mat_csc.view()).unwrap();
+        let read: crate::CsMatI<f64, u16, u32> =
+            read_bin(&mut buf.as_slice()).unwrap();
+        assert!(read.is_csc());
+        assert_eq!(read, mat_csc);
+        let read: CsMat<f64> = read_bin(&mut buf.as_slice()).unwrap();
+        assert_eq!(read, crate::test_data::mat1_csc());
+
+        // a slice of a larger matrix
+        let slice = mat.slice_outer(2..5);
+        let mut buf = Vec::new();
+        write_bin(&mut buf, slice.view()).unwrap();
+        let read: CsMat<f64> = read_bin(&mut buf.as_slice()).unwrap();
+        assert_eq!(read.view(), slice.view());
+        let mut buf = Vec::new();
+        write_bin(&mut buf, mat.view().middle_outer_views(2, 3)).unwrap();
+        let read: CsMat<f64> = read_bin(&mut buf.as_slice()).unwrap();
+        assert_eq!(read.view(), slice.view());
+
+        let mat = crate::CsMat::new(
+            (1, 2),
+            vec![0, 1],
+            vec![1],
+            vec![num_complex::Complex64::new(1., -2.)],
+        );
+        let mut buf = Vec::new();
+        write_bin(&mut buf, mat.view()).unwrap();
+        assert_eq!(read_bin(&mut buf.as_slice()).unwrap(), mat);
+    }
+
+    #[test]
+    fn bin_read_fail() {
+        let mat = crate::test_data::mat1();
+        let mut buf = Vec::new();
+        write_bin(&mut buf, mat.view()).unwrap();
+        let read = |buf: &[u8]| read_bin::<f64, usize, usize, _>(&mut &*buf);
+        // bad magic
+        let mut bad = buf.clone();
+        bad[0] = b'X';
+        assert_eq!(read(&bad).unwrap_err(), IoError::BadBinaryFile);
+        // truncated stream
+        assert_eq!(
+            read(&buf[..buf.len() - 1]).unwrap_err(),
+            IoError::BadBinaryFile
+        );
+        // wrong scalar type
+        assert_eq!(
+            read_bin::<f32, usize, usize, _>(&mut buf.as_slice()).unwrap_err(),
+            IoError::BadBinaryFile
+        );
+        // an index too large for the requested type
+        let big =
+            crate::CsMat::new((1, 70000), vec![0, 1], vec![69999], vec![1.]);
+        let mut big_buf = Vec::new();
+        write_bin(&mut big_buf, big.view()).unwrap();
+        assert_eq!(
+            read_bin::<f64, u16, usize, _>(&mut big_buf.as_slice())
+                .unwrap_err(),
+            IoError::BadBinaryFile
+        );
+        // out of bounds inner index
+        let mut bad = buf.clone();
+        let indices_start = 37 + 6 * 8;
+        bad[indices_start] = 5;
+        assert_eq!(read(&bad).unwrap_err(), IoError::BadBinaryFile);
+    }
 }
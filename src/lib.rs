@@ -11,6 +11,7 @@ It features a sparse matrix type, [**`CsMat`**](struct.CsMatBase.html), and a sp
 - sparse matrix/sparse matrix addition, multiplication.
 - sparse vector/sparse vector addition, dot product.
 - sparse matrix/dense matrix addition, multiplication.
+- conversions to and from dense `ndarray` arrays.
 - sparse triangular solves.
 - powerful iteration over the sparse structure, enabling easy extension of the library.
 - matrix construction using the [triplet format](struct.TriMatBase.html),
@@ -72,6 +73,22 @@ let b = &eye * &a;
 assert_eq!(a, b.to_csc());
 ```
 
+Conversion from and to dense `ndarray` arrays, and products with
+dense vectors:
+
+```rust
+use ndarray::{arr1, arr2};
+use sprs::CsMat;
+let dense = arr2(&[[1., 0., 2.],
+                   [0., 0., 3.]]);
+// the explicit zeros are dropped
+let a = CsMat::csr_from_dense(dense.view(), 0.);
+assert_eq!(a.nnz(), 3);
+assert_eq!(a.to_dense(), dense);
+let y = &a * &arr1(&[1., 1., 1.]).view();
+assert_eq!(y, arr1(&[3., 3.]));
+```
+
 */
 
 pub mod array_backend;
@@ -88,16 +105,17 @@ pub type Ix2 = ndarray::Ix2;
 pub use crate::indexing::SpIndex;
 
 pub use crate::sparse::{
-    csmat::CsIter, csmat::OuterIterator, csmat::OuterIteratorMut,
-    csmat::OuterIteratorPerm, kronecker::kronecker_product, CsMat, CsMatBase,
-    CsMatI, CsMatVecView, CsMatView, CsMatViewI, CsMatViewMut, CsMatViewMutI,
+    csmat::CsIter, csmat::IntoOuterIterator, csmat::OuterIterator,
+    csmat::OuterIteratorMut, csmat::OuterIteratorPerm,
+    kronecker::kronecker_product, CsMat, CsMatBase, CsMatCowI, CsMatI,
+    CsMatVecView, CsMatView, CsMatViewI, CsMatViewMut, CsMatViewMutI,
     CsStructure, CsStructureI, CsStructureView, CsStructureViewI, CsVec,
     CsVecBase, CsVecI, CsVecView, CsVecViewI, CsVecViewMut, CsVecViewMutI,
     SparseMat, TriMat, TriMatBase, TriMatI, TriMatIter, TriMatView,
     TriMatViewI, TriMatViewMut, TriMatViewMutI,
 };
 
-pub use crate::sparse::symmetric::is_symmetric;
+pub use crate::sparse::symmetric::{is_symmetric, is_symmetric_approx};
 
 pub use crate::sparse::permutation::{
     perm_is_valid, transform_mat_papt, PermOwned, PermOwnedI, PermView,
@@ -107,6 +125,7 @@ pub use crate::sparse::permutation::{
 pub use crate::sparse::CompressedStorage::{self, CSC, CSR};
 
 pub use crate::sparse::binop;
+pub use crate::sparse::graph;
 pub use crate::sparse::linalg;
 pub use crate::sparse::prod;
 pub use crate::sparse::smmp;
@@ -122,7 +141,7 @@ pub mod vec {
     };
 }
 
-pub use crate::sparse::construct::{bmat, hstack, vstack};
+pub use crate::sparse::construct::{block_diag, bmat, hstack, vstack};
 
 pub use crate::sparse::to_dense::assign_to_dense;
 
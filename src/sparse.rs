@@ -1,5 +1,6 @@
 use crate::array_backend::Array2;
 use crate::indexing::SpIndex;
+use std::borrow::Cow;
 use std::ops::Deref;
 
 #[cfg(feature = "serde")]
@@ -75,11 +76,18 @@ pub use self::csmat::CompressedStorage;
 /// Alternately, a sparse matrix can be constructed from other sparse matrices
 /// using [`vstack`], [`hstack`] or [`bmat`].
 ///
+/// When the `serde` feature is enabled, deserializing a `CsMatBase` checks
+/// its structure, and fails instead of producing an invalid matrix.
+///
+/// Equality between two matrices compares their storage, so explicitly
+/// stored zeros matter. Use `logically_eq` to compare the non-zero values
+/// regardless of the storage.
+///
 /// [`vstack`]: fn.vstack.html
 /// [`hstack`]: fn.hstack.html
 /// [`bmat`]: fn.bmat.html
 #[derive(Eq, PartialEq, Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct CsMatBase<N, I, IptrStorage, IndStorage, DataStorage, Iptr = I>
 where
     I: SpIndex,
@@ -104,6 +112,10 @@ pub type CsMatViewMutI<'a, N, I, Iptr = I> =
     CsMatBase<N, I, &'a [Iptr], &'a [I], &'a mut [N], Iptr>;
 pub type CsMatVecView_<'a, N, I, Iptr = I> =
     CsMatBase<N, I, Array2<Iptr>, &'a [I], &'a [N], Iptr>;
+/// A matrix whose indices and data are either borrowed or owned, see
+/// `CsMatBase::slice_middle_cols`
+pub type CsMatCowI<'a, N, I, Iptr = I> =
+    CsMatBase<N, I, Vec<Iptr>, Cow<'a, [I]>, Cow<'a, [N]>, Iptr>;
 
 pub type CsMat<N> = CsMatI<N, usize>;
 pub type CsMatView<'a, N> = CsMatViewI<'a, N, usize>;
@@ -239,12 +251,12 @@ pub struct TriMatIter<RI, CI, DI> {
 
 mod prelude {
     pub use super::{
-        CsMat, CsMatBase, CsMatI, CsMatVecView, CsMatVecView_, CsMatView,
-        CsMatViewI, CsMatViewMut, CsMatViewMutI, CsStructure, CsStructureI,
-        CsStructureView, CsStructureViewI, CsVec, CsVecBase, CsVecI, CsVecView,
-        CsVecViewI, CsVecViewMut, CsVecViewMutI, SparseMat, TriMat, TriMatBase,
-        TriMatI, TriMatIter, TriMatView, TriMatViewI, TriMatViewMut,
-        TriMatViewMutI,
+        CsMat, CsMatBase, CsMatCowI, CsMatI, CsMatVecView, CsMatVecView_,
+        CsMatView, CsMatViewI, CsMatViewMut, CsMatViewMutI, CsStructure,
+        CsStructureI, CsStructureView, CsStructureViewI, CsVec, CsVecBase,
+        CsVecI, CsVecView, CsVecViewI, CsVecViewMut, CsVecViewMutI, SparseMat,
+        TriMat, TriMatBase, TriMatI, TriMatIter, TriMatView, TriMatViewI,
+        TriMatViewMut, TriMatViewMutI,
     };
 }
 
@@ -293,6 +305,7 @@ pub mod binop;
 pub mod compressed;
 pub mod construct;
 pub mod csmat;
+pub mod graph;
 pub mod kronecker;
 pub mod linalg;
 pub mod permutation;
@@ -43,7 +43,12 @@ where
     csmat_binop(lhs.view(), rhs.view(), |&x, &y| x - y)
 }
 
-/// Sparse matrix scalar multiplication, with same storage type
+/// Sparse matrix elementwise (Hadamard) multiplication, with same storage
+/// type
+///
+/// The result only has non-zeros where both `lhs` and `rhs` have non-zeros,
+/// its number of non-zeros is thus never larger than the number of non-zeros
+/// of either operand.
 pub fn mul_mat_same_storage<N, I, Iptr, Mat1, Mat2>(
     lhs: &Mat1,
     rhs: &Mat2,
@@ -59,6 +64,10 @@ where
 }
 
 /// Sparse matrix multiplication by a scalar
+///
+/// The structure of `mat` is kept, except when `val` is zero: the result
+/// is then a structurally empty matrix of the same shape and storage,
+/// instead of a matrix full of explicit zeros.
 pub fn scalar_mul_mat<N, I, Iptr, Mat>(mat: &Mat, val: N) -> CsMatI<N, I, Iptr>
 where
     N: Num + Copy,
@@ -67,6 +76,16 @@ where
     Mat: SpMatView<N, I, Iptr>,
 {
     let mat = mat.view();
+    if val.is_zero() {
+        let indptr = vec![Iptr::zero(); mat.outer_dims() + 1];
+        return CsMatI::new_trusted(
+            mat.storage(),
+            mat.shape(),
+            indptr,
+            Vec::new(),
+            Vec::new(),
+        );
+    }
     mat.map(|&x| x * val)
 }
 
@@ -298,6 +317,10 @@ pub fn csmat_binop_dense_raw<'a, N, I, Iptr, F>(
 /// to zero when e.g. only `lhs` has a non-zero at a given location).
 ///
 /// The function thus has a correct behavior iff `binop(0, 0) == 0`.
+///
+/// As for matrices, the locations where `binop` evaluates to zero are
+/// not stored in the result, so that e.g. cancelling terms in an addition
+/// do not leave explicit zeros.
 pub fn csvec_binop<N, I, F>(
     mut lhs: CsVecViewI<N, I>,
     mut rhs: CsVecViewI<N, I>,
@@ -321,7 +344,9 @@ where
             Right((ind, val)) => (ind, binop(&N::zero(), val)),
             Both((ind, lval, rval)) => (ind, binop(lval, rval)),
         };
-        res.append(ind, binop_val);
+        if !binop_val.is_zero() {
+            res.append(ind, binop_val);
+        }
     }
     Ok(res)
 }
@@ -394,6 +419,45 @@ mod test {
         assert_eq!(c, &a + &b);
     }
 
+    #[test]
+    fn add_patterns_and_cancellation() {
+        // | 1 . 2 |   | . 3 . |   | 1 3 2 |
+        // | . . 4 | + | . 5 -4| = | . 5 . |
+        // | . . . |   | 6 . . |   | 6 . . |
+        let a = CsMat::new(
+            (3, 3),
+            vec![0, 2, 3, 3],
+            vec![0, 2, 2],
+            vec![1., 2., 4.],
+        );
+        let b = CsMat::new(
+            (3, 3),
+            vec![0, 1, 3, 4],
+            vec![1, 1, 2, 0],
+            vec![3., 5., -4., 6.],
+        );
+        let expected = CsMat::new(
+            (3, 3),
+            vec![0, 3, 4, 5],
+            vec![0, 1, 2, 1, 0],
+            vec![1., 3., 2., 5., 6.],
+        );
+        let c = super::add_mat_same_storage(&a, &b);
+        assert_eq!(c, expected);
+        assert_eq!(c.to_dense(), a.to_dense() + b.to_dense());
+        // the cancelled entry at (1, 2) is not stored
+        assert_eq!(c.get(1, 2), None);
+        assert_eq!(c.nnz(), 5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn add_fail_dim_mismatch() {
+        let a = CsMat::<f64>::eye(3);
+        let b = CsMat::<f64>::eye(4);
+        let _ = super::add_mat_same_storage(&a, &b);
+    }
+
     #[test]
     fn test_sub1() {
         let a = mat1();
@@ -407,6 +471,24 @@ mod test {
         assert_eq!(c, c_true);
     }
 
+    #[test]
+    fn sub_self_is_empty() {
+        let a = mat1();
+        assert!(a.nnz() > 0);
+        let c = &a - &a;
+        assert_eq!(c.shape(), a.shape());
+        assert_eq!(c.nnz(), 0);
+        assert_eq!(c.indptr(), &[0; 6]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn sub_fail_storage_mismatch() {
+        let a = mat1();
+        let b = mat1().to_csc();
+        let _ = super::sub_mat_same_storage(&a, &b);
+    }
+
     #[test]
     fn test_mul1() {
         let a = mat1();
@@ -419,6 +501,37 @@ mod test {
         assert_eq!(c.data(), c_true.data());
     }
 
+    #[test]
+    fn mul_is_structural_intersection() {
+        let a = mat1();
+        let b = mat2();
+        let c = super::mul_mat_same_storage(&a, &b);
+        assert!(c.nnz() <= a.nnz().min(b.nnz()));
+        for (&val, (i, j)) in c.iter() {
+            assert_eq!(val, a.get(i, j).unwrap() * b.get(i, j).unwrap());
+        }
+
+        // disjoint patterns give an empty product
+        let eye = CsMat::<f64>::eye(3);
+        let off_diag = CsMat::new(
+            (3, 3),
+            vec![0, 1, 2, 3],
+            vec![1, 2, 0],
+            vec![1., 2., 3.],
+        );
+        let c = super::mul_mat_same_storage(&eye, &off_diag);
+        assert_eq!(c.shape(), (3, 3));
+        assert_eq!(c.nnz(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn mul_fail_dim_mismatch() {
+        let a = CsMat::<f64>::eye(3);
+        let b = CsMat::<f64>::eye(2);
+        let _ = super::mul_mat_same_storage(&a, &b);
+    }
+
     #[test]
     fn test_smul() {
         let a = mat1();
@@ -429,6 +542,32 @@ mod test {
         assert_eq!(c.data(), c_true.data());
     }
 
+    #[test]
+    fn scalar_mul_commutes() {
+        let a = mat1();
+        let c_true = mat1_times_2();
+        assert_eq!(&a * 2., c_true);
+        assert_eq!(2. * &a, c_true);
+        let a = a.to_csc();
+        assert_eq!(2. * &a, c_true.to_csc());
+    }
+
+    #[test]
+    fn scalar_mul_zero_is_empty() {
+        let a = mat1();
+        let c = &a * 0.;
+        assert_eq!(c.shape(), a.shape());
+        assert_eq!(c.storage(), a.storage());
+        assert_eq!(c.nnz(), 0);
+        let c = 0. * &a.to_csc();
+        assert_eq!(c.storage(), crate::CSC);
+        assert_eq!(c.indptr(), &[0; 6]);
+
+        let mut a = a;
+        a.scale(0.5);
+        assert_eq!(a.data(), &[1.5, 2., 1., 2.5, 2.5, 4., 3.5]);
+    }
+
     #[test]
     fn csvec_binops() {
         let vec1 = CsVec::new(8, vec![0, 2, 4, 6], vec![1.; 4]);
@@ -2,6 +2,7 @@
 
 use crate::indexing::SpIndex;
 use crate::sparse::prelude::*;
+use crate::sparse::CompressedStorage::CSR;
 use std::cmp;
 use std::default::Default;
 
@@ -32,9 +33,8 @@ where
     let outer_dim = mats.iter().map(|x| x.outer_dims()).sum::<usize>();
     let nnz = mats.iter().map(|x| x.nnz()).sum::<usize>();
 
-    let mut res = CsMatI::empty(storage_type, inner_dim);
-    res.reserve_outer_dim_exact(outer_dim);
-    res.reserve_nnz_exact(nnz);
+    let mut res =
+        CsMatI::with_capacity(storage_type, inner_dim, outer_dim, nnz);
     for mat in mats {
         for vec in mat.outer_iterator() {
             res = res.append_outer_csvec(vec.view());
@@ -45,6 +45,13 @@ where
 }
 
 /// Construct a sparse matrix by vertically stacking other matrices
+///
+/// The result is a CSR matrix, inputs with another storage are converted.
+///
+/// # Panics
+///
+/// - if the list of matrices is empty
+/// - if the matrices do not have the same number of columns
 pub fn vstack<'a, N, I, Iptr, MatArray>(mats: &MatArray) -> CsMatI<N, I, Iptr>
 where
     N: 'a + Clone + Default,
@@ -63,6 +70,13 @@ where
 }
 
 /// Construct a sparse matrix by horizontally stacking other matrices
+///
+/// The result is a CSC matrix, inputs with another storage are converted.
+///
+/// # Panics
+///
+/// - if the list of matrices is empty
+/// - if the matrices do not have the same number of rows
 pub fn hstack<'a, N, I, Iptr, MatArray>(mats: &MatArray) -> CsMatI<N, I, Iptr>
 where
     N: 'a + Clone + Default,
@@ -161,6 +175,68 @@ where
     vstack(&borrows)
 }
 
+/// Construct a block diagonal matrix, with the given matrices as diagonal
+/// blocks and empty off-diagonal blocks
+///
+/// The shape of the result is the sum of the shapes of the blocks, which
+/// need not be square. The result is a CSR matrix, inputs with another
+/// storage are converted.
+///
+/// # Examples
+/// ```
+/// use sprs::CsMat;
+/// let a = CsMat::<f64>::eye(2);
+/// let b = CsMat::new((1, 2), vec![0, 2], vec![0, 1], vec![3., 4.]);
+/// let c = sprs::block_diag(&[a.view(), b.view()]);
+/// assert_eq!(c.shape(), (3, 4));
+/// assert_eq!(c.get(2, 3), Some(&4.));
+/// ```
+///
+/// # Panics
+///
+/// - if the list of matrices is empty
+pub fn block_diag<'a, N, I, Iptr, MatArray>(
+    mats: &MatArray,
+) -> CsMatI<N, I, Iptr>
+where
+    N: 'a + Clone + Default,
+    I: 'a + SpIndex,
+    Iptr: 'a + SpIndex,
+    MatArray: AsRef<[CsMatViewI<'a, N, I, Iptr>]>,
+{
+    let mats = mats.as_ref();
+    if mats.is_empty() {
+        panic!("Empty stacking list");
+    }
+    let rows = mats.iter().map(|x| x.rows()).sum::<usize>();
+    let cols = mats.iter().map(|x| x.cols()).sum::<usize>();
+    let nnz = mats.iter().map(|x| x.nnz()).sum::<usize>();
+
+    let mut indptr = Vec::with_capacity(rows + 1);
+    let mut indices = Vec::with_capacity(nnz);
+    let mut data = Vec::with_capacity(nnz);
+    indptr.push(Iptr::zero());
+    let mut col_offset = 0;
+    for mat in mats {
+        let converted;
+        let mat = if mat.is_csr() {
+            mat.view()
+        } else {
+            converted = mat.to_csr();
+            converted.view()
+        };
+        for row in mat.outer_iterator() {
+            for (col, val) in row.iter() {
+                indices.push(I::from_usize(col + col_offset));
+                data.push(val.clone());
+            }
+            indptr.push(Iptr::from_usize(indices.len()));
+        }
+        col_offset += mat.cols();
+    }
+    CsMatI::new_trusted(CSR, (rows, cols), indptr, indices, data)
+}
+
 #[cfg(test)]
 mod test {
     use crate::sparse::CsMat;
@@ -224,6 +300,46 @@ mod test {
         assert_eq!(res, expected);
     }
 
+    #[test]
+    fn stack_dense_reference() {
+        let a = mat1();
+        let dense = a.to_dense();
+        let res = super::vstack(&[a.view(), a.view()]);
+        assert!(res.is_csr());
+        assert_eq!(res.shape(), (10, 5));
+        let expected =
+            ndarray::stack(ndarray::Axis(0), &[dense.view(), dense.view()])
+                .unwrap();
+        assert_eq!(res.to_dense(), expected);
+
+        // hstack of CSR matrices
+        let b = mat3();
+        let res = super::hstack(&[b.view(), a.view()]);
+        assert!(res.is_csc());
+        let expected = ndarray::stack(
+            ndarray::Axis(1),
+            &[b.to_dense().view(), dense.view()],
+        )
+        .unwrap();
+        assert_eq!(res.to_dense(), expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn vstack_fail_dim_mismatch() {
+        let a = mat1();
+        let b = mat3();
+        let _ = super::vstack(&[a.view(), b.view()]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn hstack_fail_dim_mismatch() {
+        let a = mat1();
+        let b = mat3().transpose_into();
+        let _ = super::hstack(&[a.view(), b.view()]);
+    }
+
     #[test]
     fn vstack_with_conversion() {
         let a = mat1().to_csc();
@@ -319,4 +435,33 @@ mod test {
         );
         assert_eq!(f, expected);
     }
+
+    #[test]
+    fn block_diag() {
+        let a = mat1();
+        let b = mat3().to_csc();
+        let c = super::block_diag(&[a.view(), b.view()]);
+        assert_eq!(c.shape(), (10, 9));
+        assert_eq!(c.nnz(), a.nnz() + b.nnz());
+        assert!(c.check_compressed_structure().is_ok());
+
+        let x1 = [1., -1., 2., 0.5, 3.];
+        let x2 = [2., 1., -3., 4.];
+        let x: Vec<f64> = x1.iter().chain(x2.iter()).cloned().collect();
+        let y = &c * &ndarray::arr1(&x);
+        let y1 = &a * &ndarray::arr1(&x1);
+        let y2 = &b * &ndarray::arr1(&x2);
+        let expected: Vec<f64> = y1.iter().chain(y2.iter()).cloned().collect();
+        assert_eq!(y.to_vec(), expected);
+
+        // same result as a bmat with empty off-diagonal blocks
+        let d = super::bmat(&[[Some(a.view()), None], [None, Some(b.view())]]);
+        assert_eq!(c, d);
+    }
+
+    #[test]
+    #[should_panic]
+    fn block_diag_fail_empty_stacking_list() {
+        let _: CsMat<f64> = super::block_diag(&[]);
+    }
 }
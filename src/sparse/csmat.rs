@@ -1,8 +1,10 @@
 use ndarray::ArrayView;
+use num_complex::Complex;
 use num_traits::{Float, Num, Signed, Zero};
+#[cfg(feature = "multi_thread")]
+use rayon::prelude::*;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::cmp;
 ///! A sparse matrix in the Compressed Sparse Row/Column format
 ///
 /// In the CSR format, a matrix is a structure containing three vectors:
@@ -12,10 +14,14 @@ use std::cmp;
 /// A(i, indices[indptr[i]..indptr[i+1]]) = data[indptr[i]..indptr[i+1]]
 /// In the CSC format, the relation is
 /// A(indices[indptr[i]..indptr[i+1]], i) = data[indptr[i]..indptr[i+1]]
+use std::borrow::Cow;
+use std::cmp;
+use std::convert::TryInto;
 use std::default::Default;
+use std::fmt;
 use std::iter::{Enumerate, Zip};
 use std::mem;
-use std::ops::{Add, Deref, DerefMut, Index, IndexMut, Mul, Range, Sub};
+use std::ops::{Add, Deref, DerefMut, Index, IndexMut, Mul, Neg, Range, Sub};
 use std::slice::{self, Iter, Windows};
 
 use crate::{Ix1, Ix2, Shape};
@@ -25,7 +31,7 @@ use ndarray::{self, Array, ArrayBase, ShapeBuilder};
 use crate::array_backend::Array2;
 use crate::indexing::SpIndex;
 
-use crate::errors::SprsError;
+use crate::errors::{SprsError, StructureError};
 use crate::sparse::binop;
 use crate::sparse::compressed::SpMatView;
 use crate::sparse::permutation::PermViewI;
@@ -35,6 +41,8 @@ use crate::sparse::smmp;
 use crate::sparse::to_dense::assign_to_dense;
 use crate::sparse::utils;
 use crate::sparse::vec;
+use crate::sparse::vec::NnzEither::{Both, Left, Right};
+use crate::sparse::vec::SparseIterTools;
 
 impl<N, I, IptrStorage, IndStorage, DataStorage, Iptr> Copy
     for CsMatBase<N, I, IptrStorage, IndStorage, DataStorage, Iptr>
@@ -90,6 +98,84 @@ pub fn inner_dimension(
     }
 }
 
+/// Consume an owned matrix, iterating over its outer dimension: this
+/// yields the rows of a CSR matrix, or the columns of a CSC matrix, as
+/// owned sparse vectors.
+///
+/// This differs from the iteration on views and references, which yield
+/// the non-zero elements.
+///
+/// # Example
+///
+/// ```rust
+/// use sprs::{CsMat, CsVec};
+/// let mat: CsMat<f64> = CsMat::eye(3);
+/// let mut rebuilt = CsMat::empty(sprs::CSR, 3);
+/// for row in mat.clone() {
+///     let row: CsVec<f64> = row.map(|x| 2. * x);
+///     rebuilt = rebuilt.append_outer_csvec(row.view());
+/// }
+/// assert_eq!(rebuilt, mat.map(|x| 2. * x));
+/// ```
+impl<N, I, Iptr> IntoIterator for CsMatI<N, I, Iptr>
+where
+    I: SpIndex,
+    Iptr: SpIndex,
+{
+    type Item = CsVecI<N, I>;
+    type IntoIter = IntoOuterIterator<N, I, Iptr>;
+    fn into_iter(self) -> Self::IntoIter {
+        let inner_len = self.inner_dims();
+        IntoOuterIterator {
+            inner_len,
+            indptr: self.indptr,
+            outer: 0,
+            indices: self.indices.into_iter(),
+            data: self.data.into_iter(),
+        }
+    }
+}
+
+/// Build a CSR matrix from an iterator of `(row, col, value)` triplets.
+///
+/// The shape of the matrix is inferred from the largest row and column
+/// indices, ie it is `(max_row + 1, max_col + 1)`, so trailing empty rows
+/// or columns cannot be represented this way (use `TriMat` with an explicit
+/// shape instead). Duplicate coordinates are summed, and the triplets can
+/// come in any order.
+///
+/// # Example
+///
+/// ```rust
+/// use sprs::CsMat;
+/// let triplets = vec![(1, 2, 1.), (0, 0, 2.), (1, 2, 3.)];
+/// let mat: CsMat<f64> = triplets.into_iter().collect();
+/// assert_eq!(mat.shape(), (2, 3));
+/// assert_eq!(mat.get(1, 2), Some(&4.));
+/// ```
+impl<N, I> std::iter::FromIterator<(usize, usize, N)> for CsMatI<N, I>
+where
+    N: Clone + Num,
+    I: SpIndex,
+{
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = (usize, usize, N)>,
+    {
+        let (mut rows, mut cols) = (0, 0);
+        let (mut row_inds, mut col_inds, mut data) =
+            (Vec::new(), Vec::new(), Vec::new());
+        for (row, col, val) in iter {
+            rows = rows.max(row + 1);
+            cols = cols.max(col + 1);
+            row_inds.push(I::from_usize(row));
+            col_inds.push(I::from_usize(col));
+            data.push(val);
+        }
+        TriMatI::from_triplets((rows, cols), row_inds, col_inds, data).to_csr()
+    }
+}
+
 pub use self::CompressedStorage::{CSC, CSR};
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -239,6 +325,46 @@ impl<'iter, N: 'iter, I: 'iter + SpIndex, Iptr: 'iter + SpIndex> Iterator
     }
 }
 
+/// Consuming iterator on the matrix' outer dimension, yielding owned
+/// sparse vectors. See the `IntoIterator` implementation of `CsMatI`.
+pub struct IntoOuterIterator<N, I, Iptr = I> {
+    inner_len: usize,
+    indptr: Vec<Iptr>,
+    outer: usize,
+    indices: std::vec::IntoIter<I>,
+    data: std::vec::IntoIter<N>,
+}
+
+impl<N, I: SpIndex, Iptr: SpIndex> Iterator for IntoOuterIterator<N, I, Iptr> {
+    type Item = CsVecI<N, I>;
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        if self.outer + 1 >= self.indptr.len() {
+            return None;
+        }
+        let nnz = self.indptr[self.outer + 1].index_unchecked()
+            - self.indptr[self.outer].index_unchecked();
+        self.outer += 1;
+        let indices = self.indices.by_ref().take(nnz).collect();
+        let data = self.data.by_ref().take(nnz).collect();
+        // CsMat invariants imply CsVec invariants
+        Some(CsVecBase {
+            dim: self.inner_len,
+            indices,
+            data,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.indptr.len() - 1 - self.outer;
+        (len, Some(len))
+    }
+}
+
+impl<N, I: SpIndex, Iptr: SpIndex> ExactSizeIterator
+    for IntoOuterIterator<N, I, Iptr>
+{
+}
+
 /// Reverse outer iteration on a compressed matrix yields
 /// a tuple consisting of the outer index and of a sparse vector
 /// containing the associated inner dimension
@@ -382,6 +508,43 @@ impl<N, I: SpIndex, Iptr: SpIndex>
             data,
         }
     }
+
+    /// Square CSR matrix with the given values on its diagonal.
+    ///
+    /// Zero values are not stored.
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// let mat = CsMat::from_diag(&[1., 0., 3.]);
+    /// assert_eq!(mat.nnz(), 2);
+    /// assert_eq!(mat.diag(), vec![1., 0., 3.]);
+    /// ```
+    pub fn from_diag(diag: &[N]) -> CsMatI<N, I, Iptr>
+    where
+        N: Num + Clone,
+    {
+        let n = diag.len();
+        let _ = (I::from_usize(n), Iptr::from_usize(n)); // Make sure n fits in type I & Iptr
+        let mut indptr = Vec::with_capacity(n + 1);
+        let mut indices = Vec::with_capacity(n);
+        let mut data = Vec::with_capacity(n);
+        indptr.push(Iptr::zero());
+        for (i, val) in diag.iter().enumerate() {
+            if !val.is_zero() {
+                indices.push(I::from_usize_unchecked(i));
+                data.push(val.clone());
+            }
+            indptr.push(Iptr::from_usize_unchecked(indices.len()));
+        }
+        CsMatI {
+            storage: CSR,
+            nrows: n,
+            ncols: n,
+            indptr,
+            indices,
+            data,
+        }
+    }
     /// Create an empty CsMat for building purposes
     pub fn empty(
         storage: CompressedStorage,
@@ -401,6 +564,31 @@ impl<N, I: SpIndex, Iptr: SpIndex>
         }
     }
 
+    /// Create an empty CsMat for building purposes, with enough storage
+    /// reserved to append `outer_capacity` outer vectors holding a total
+    /// of `nnz_capacity` non-zero elements without reallocating.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::{CsMat, CompressedStorage};
+    /// let mut mat = CsMat::with_capacity(CompressedStorage::CSR, 3, 2, 3);
+    /// mat = mat.append_outer(&[1., 0., 2.]).append_outer(&[0., 3., 0.]);
+    /// assert_eq!(mat.shape(), (2, 3));
+    /// assert_eq!(mat.nnz(), 3);
+    /// ```
+    pub fn with_capacity(
+        storage: CompressedStorage,
+        inner_size: usize,
+        outer_capacity: usize,
+        nnz_capacity: usize,
+    ) -> CsMatI<N, I, Iptr> {
+        let mut res = Self::empty(storage, inner_size);
+        res.reserve_outer_dim_exact(outer_capacity);
+        res.reserve_nnz_exact(nnz_capacity);
+        res
+    }
+
     /// Create a new CsMat representing the zero matrix.
     /// Hence it has no non-zero elements.
     pub fn zero(shape: Shape) -> CsMatI<N, I, Iptr> {
@@ -415,7 +603,8 @@ impl<N, I: SpIndex, Iptr: SpIndex>
         }
     }
 
-    /// Reserve the storage for the given additional number of nonzero data
+    /// Reserve the storage for the given additional number of outer
+    /// vectors
     pub fn reserve_outer_dim(&mut self, outer_dim_additional: usize) {
         self.indptr.reserve(outer_dim_additional);
     }
@@ -554,13 +743,34 @@ impl<N, I: SpIndex, Iptr: SpIndex>
             indices,
             data,
         };
-        m.sort_indices();
+        // sorting relies on indptr describing valid ranges of indices,
+        // otherwise the structure check below reports the error
+        let indptr_valid = m.indptr.len() == m.outer_dims() + 1
+            && m.indices.len() == m.data.len()
+            && m.indptr.iter().all(|x| x.try_index().is_some())
+            && m.indptr.windows(2).all(|x| x[0] <= x[1])
+            && m.indptr[m.outer_dims()].index_unchecked() <= m.indices.len();
+        if indptr_valid {
+            m.sort_indices();
+        }
         m.check_compressed_structure().and(Ok(m))
     }
 
     /// Create a CSR matrix from a dense matrix, ignoring elements lower than `epsilon`.
     ///
     /// If epsilon is negative, it will be clamped to zero.
+    ///
+    /// A flat row-major buffer can be converted by viewing it as an array:
+    ///
+    /// ```rust
+    /// use ndarray::ArrayView;
+    /// use sprs::CsMat;
+    /// let dense = [0., 1., 2., 0., 0., 3.];
+    /// let view = ArrayView::from_shape((2, 3), &dense).unwrap();
+    /// let mat = CsMat::csr_from_dense(view, 0.);
+    /// assert_eq!(mat.nnz(), 3);
+    /// assert_eq!(mat.to_dense().into_raw_vec(), dense.to_vec());
+    /// ```
     pub fn csr_from_dense(
         m: ArrayView<N, Ix2>,
         epsilon: N,
@@ -616,7 +826,13 @@ impl<N, I: SpIndex, Iptr: SpIndex>
         Self::csr_from_dense(m.reversed_axes(), epsilon).transpose_into()
     }
 
-    fn sort_indices(&mut self)
+    /// Sort the inner indices of each outer slice in increasing order,
+    /// moving the associated values along.
+    ///
+    /// The constructors taking owned data already sort the indices, so
+    /// this is only needed after modifying the indices of a matrix by
+    /// other means. Duplicate indices are not merged.
+    pub fn sort_indices(&mut self)
     where
         N: Copy,
     {
@@ -633,6 +849,43 @@ impl<N, I: SpIndex, Iptr: SpIndex>
         }
     }
 
+    /// Sort the inner indices of each outer slice, and merge the entries
+    /// sharing the same location by summing their values.
+    ///
+    /// The constructors checking the structure of a matrix reject
+    /// duplicate entries, so this is a no-op for such matrices. It is the
+    /// cleanup step for matrices whose compressed storage has been
+    /// assembled from overlapping contributions by lower level means.
+    /// Building a `TriMat` and converting it is usually a simpler way to
+    /// sum contributions.
+    pub fn dedup(&mut self)
+    where
+        N: Num + Copy,
+    {
+        self.sort_indices();
+        let mut nnz = 0;
+        let mut start = 0;
+        for outer in 0..self.outer_dims() {
+            let stop = self.indptr[outer + 1].index_unchecked();
+            let slice_start = nnz;
+            for k in start..stop {
+                let ind = self.indices[k];
+                let val = self.data[k];
+                if nnz > slice_start && self.indices[nnz - 1] == ind {
+                    self.data[nnz - 1] = self.data[nnz - 1] + val;
+                } else {
+                    self.indices[nnz] = ind;
+                    self.data[nnz] = val;
+                    nnz += 1;
+                }
+            }
+            start = stop;
+            self.indptr[outer + 1] = Iptr::from_usize(nnz);
+        }
+        self.indices.truncate(nnz);
+        self.data.truncate(nnz);
+    }
+
     /// Append an outer dim to an existing matrix, compressing it in the process
     pub fn append_outer(mut self, data: &[N]) -> Self
     where
@@ -655,15 +908,21 @@ impl<N, I: SpIndex, Iptr: SpIndex>
     }
 
     /// Append an outer dim to an existing matrix, provided by a sparse vector
+    ///
+    /// Contrary to `append_outer`, no dense workspace is traversed, which
+    /// is much cheaper when the appended vector is very sparse.
+    ///
+    /// # Panics
+    ///
+    /// If the dimension of the vector does not match the inner dimension
+    /// of the matrix.
     pub fn append_outer_csvec(mut self, vec: CsVecBase<&[I], &[N]>) -> Self
     where
         N: Clone,
     {
         assert_eq!(self.inner_dims(), vec.dim());
-        for (ind, val) in vec.indices().iter().zip(vec.data()) {
-            self.indices.push(*ind);
-            self.data.push(val.clone());
-        }
+        self.indices.extend_from_slice(vec.indices());
+        self.data.extend_from_slice(vec.data());
         match self.storage {
             CSR => self.nrows += 1,
             CSC => self.ncols += 1,
@@ -675,6 +934,53 @@ impl<N, I: SpIndex, Iptr: SpIndex>
         self
     }
 
+    /// Grow the matrix by appending `block` as a new diagonal block in its
+    /// bottom-right corner, with empty off-diagonal blocks.
+    ///
+    /// Both dimensions of the matrix grow by the dimensions of `block`,
+    /// the result being the same as `block_diag(&[self.view(), block])`.
+    /// This only requires appending the elements of `block` to the storage.
+    ///
+    /// # Panics
+    ///
+    /// If the storage of `block` differs from the storage of the matrix.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// let mut mat = CsMat::<f64>::eye(2);
+    /// let block = CsMat::new((1, 2), vec![0, 2], vec![0, 1], vec![3., 4.]);
+    /// mat.append_diag_block(block.view());
+    /// assert_eq!(mat.shape(), (3, 4));
+    /// assert_eq!(mat.get(2, 3), Some(&4.));
+    /// ```
+    pub fn append_diag_block(&mut self, block: CsMatViewI<N, I, Iptr>)
+    where
+        N: Clone,
+    {
+        if self.storage != block.storage() {
+            panic!("Storage mismatch");
+        }
+        let inner_offset = self.inner_dims();
+        let mut nnz = self.nnz();
+        self.indptr.reserve(block.outer_dims());
+        self.indices.reserve(block.nnz());
+        self.data.reserve(block.nnz());
+        for vec in block.outer_iterator() {
+            self.indices.extend(
+                vec.indices()
+                    .iter()
+                    .map(|inner| I::from_usize(inner.index() + inner_offset)),
+            );
+            self.data.extend_from_slice(vec.data());
+            nnz += vec.nnz();
+            self.indptr.push(Iptr::from_usize(nnz));
+        }
+        self.nrows += block.rows();
+        self.ncols += block.cols();
+    }
+
     /// Insert an element in the matrix. If the element is already present,
     /// its value is overwritten.
     ///
@@ -824,10 +1130,14 @@ impl<'a, N: 'a, I: 'a + SpIndex, Iptr: 'a + SpIndex>
         if i >= self.outer_dims() || iend > self.outer_dims() {
             panic!("Out of bounds index");
         }
+        let (nrows, ncols) = match self.storage {
+            CSR => (count, self.cols()),
+            CSC => (self.rows(), count),
+        };
         CsMatViewI {
             storage: self.storage,
-            nrows: count,
-            ncols: self.cols(),
+            nrows,
+            ncols,
             indptr: &self.indptr[i..=iend],
             indices: &self.indices[..],
             data: &self.data[..],
@@ -887,9 +1197,50 @@ where
         self.indptr.last().unwrap().index_unchecked()
     }
 
+    /// The number of non-zero elements stored in the outer dimension `i`
+    /// (eg the i-th row for a CSR matrix).
+    ///
+    /// # Panics
+    ///
+    /// If `i >= self.outer_dims()`
+    pub fn nnz_outer(&self, i: usize) -> usize {
+        assert!(i < self.outer_dims(), "outer index out of bounds");
+        let start = self.indptr[i].index_unchecked();
+        let stop = self.indptr[i + 1].index_unchecked();
+        stop - start
+    }
+
+    /// The number of non-zero elements stored in each outer dimension,
+    /// eg the number of non-zeros of each row for a CSR matrix.
+    ///
+    /// For an adjacency matrix, this is the out-degree of each vertex
+    /// (including self loops, see [`degrees`](#method.degrees) for a count
+    /// excluding the diagonal).
+    pub fn outer_nnz_counts(&self) -> Vec<usize> {
+        self.indptr
+            .windows(2)
+            .map(|w| w[1].index_unchecked() - w[0].index_unchecked())
+            .collect()
+    }
+
     /// The density of the sparse matrix, defined as the number of non-zero
     /// elements divided by the maximum number of elements
+    ///
+    /// The density of a matrix without any element, ie with a zero
+    /// dimension, is `0.`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// let eye = CsMat::<f64>::eye(4);
+    /// assert_eq!(eye.density(), 0.25);
+    /// assert_eq!(CsMat::<f64>::zero((0, 3)).density(), 0.);
+    /// ```
     pub fn density(&self) -> f64 {
+        if self.nrows == 0 || self.ncols == 0 {
+            return 0.;
+        }
         let rows = self.nrows as f64;
         let cols = self.ncols as f64;
         let nnz = self.nnz() as f64;
@@ -918,6 +1269,9 @@ where
     /// in the corresponding outer slice. It is therefore advisable not to rely
     /// on this for algorithms, and prefer outer_iterator() which accesses
     /// elements in storage order.
+    ///
+    /// Out of bounds locations also return None. Indexing with `mat[[i, j]]`
+    /// can be used instead to panic if the location is not a non-zero.
     pub fn get(&self, i: usize, j: usize) -> Option<&N> {
         match self.storage {
             CSR => self.get_outer_inner(i, j),
@@ -998,8 +1352,28 @@ where
         self.storage = self.storage.other_storage();
     }
 
-    /// Transpose a matrix in place
+    /// Consume the matrix and return its transpose
     /// No allocation required (this is simply a storage order change)
+    ///
+    /// The `indptr`, `indices` and `data` storages are moved into the
+    /// result, which means that the transpose of a CSR matrix is a CSC
+    /// matrix, and vice versa. A transpose with the original storage can
+    /// then be obtained by converting the result with `into_csr` or
+    /// `into_csc`, which does allocate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// // | 1 2 |
+    /// // | 0 3 |
+    /// let mat = CsMat::new((2, 2), vec![0, 2, 3], vec![0, 1, 1],
+    ///                      vec![1, 2, 3]);
+    /// let transposed = mat.transpose_into();
+    /// assert!(transposed.is_csc());
+    /// assert_eq!(transposed.get(1, 0), Some(&2));
+    /// assert_eq!(transposed.get(0, 1), None);
+    /// ```
     pub fn transpose_into(mut self) -> Self {
         self.transpose_mut();
         self
@@ -1115,6 +1489,49 @@ where
         }
     }
 
+    /// Clone the matrix with another scalar type, converting each stored
+    /// value with `Into`, eg to convert a `f32` matrix into a `f64` one.
+    ///
+    /// The structure of the matrix is copied unchanged.
+    pub fn cast<N2>(&self) -> CsMatI<N2, I, Iptr>
+    where
+        N: Clone + Into<N2>,
+    {
+        self.map(|x| x.clone().into())
+    }
+
+    /// Clone the matrix with another scalar type, converting each stored
+    /// value with `TryInto`, eg to narrow an `i64` matrix into an `i32` one.
+    ///
+    /// Returns `SprsError::IllegalArguments` if a value cannot be
+    /// represented by the requested type.
+    pub fn try_cast<N2>(&self) -> Result<CsMatI<N2, I, Iptr>, SprsError>
+    where
+        N: Clone + TryInto<N2>,
+    {
+        // only the range of a view that its indptr refers to is converted
+        let offset = self.indptr[0];
+        let start = offset.index_unchecked();
+        let stop = self.indptr[self.outer_dims()].index_unchecked();
+        let data = self.data[start..stop]
+            .iter()
+            .map(|x| x.clone().try_into())
+            .collect::<Result<Vec<N2>, _>>()
+            .map_err(|_| {
+                SprsError::IllegalArguments(
+                    "Value cannot be represented by the requested type",
+                )
+            })?;
+        Ok(CsMatI {
+            storage: self.storage,
+            nrows: self.nrows,
+            ncols: self.ncols,
+            indptr: self.indptr.iter().map(|&i| i - offset).collect(),
+            indices: self.indices[start..stop].to_vec(),
+            data,
+        })
+    }
+
     /// Return a view into the current matrix
     pub fn view(&self) -> CsMatViewI<N, I, Iptr> {
         CsMatViewI {
@@ -1127,64 +1544,338 @@ where
         }
     }
 
-    pub fn structure_view(&self) -> CsStructureViewI<I, Iptr> {
-        // Safety: std::slice::from_raw_parts requires its passed
-        // pointer to be valid for the whole length of the slice. We have a
-        // zero-sized type, so the length is zero, and since we cast
-        // a non-null pointer, the pointer is valid as all pointers to zero-sized
-        // types are valid if they are not null.
-        let zst_data = unsafe {
-            std::slice::from_raw_parts(
-                self.data.as_ptr() as *const (),
-                self.data.len(),
-            )
+    /// Get a matrix made of the outer dimensions in `range`, eg the rows
+    /// `range` of a CSR matrix.
+    ///
+    /// The indices and data of the result borrow the storage of `self`, only
+    /// the `indptr` array is allocated, to re-base it to start at zero.
+    /// A regular view can be obtained from the result using `view()`.
+    ///
+    /// # Panics
+    ///
+    /// If the range is out of bounds
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// let eye: CsMat<f64> = CsMat::eye(5);
+    /// let slice = eye.slice_outer(1..3);
+    /// assert_eq!(slice.shape(), (2, 5));
+    /// assert_eq!(slice.indptr(), &[0, 1, 2]);
+    /// assert_eq!(slice.indices(), &[1, 2]);
+    /// ```
+    pub fn slice_outer(
+        &self,
+        range: Range<usize>,
+    ) -> CsMatBase<N, I, Vec<Iptr>, &[I], &[N], Iptr> {
+        assert!(
+            range.start <= range.end && range.end <= self.outer_dims(),
+            "Out of bounds index"
+        );
+        let indptr = &self.indptr[range.start..=range.end];
+        let offset = indptr[0];
+        let start = offset.index_unchecked();
+        let stop = indptr[indptr.len() - 1].index_unchecked();
+        let count = range.end - range.start;
+        let (nrows, ncols) = match self.storage {
+            CSR => (count, self.cols()),
+            CSC => (self.rows(), count),
         };
-        CsStructureViewI {
+        CsMatBase {
             storage: self.storage,
-            nrows: self.nrows,
-            ncols: self.ncols,
-            indptr: &self.indptr[..],
-            indices: &self.indices[..],
-            data: zst_data,
+            nrows,
+            ncols,
+            indptr: indptr.iter().map(|&i| i - offset).collect(),
+            indices: &self.indices[start..stop],
+            data: &self.data[start..stop],
         }
     }
 
-    pub fn to_dense(&self) -> Array<N, Ix2>
+    /// Get a matrix made of the columns in `range`, with the same storage.
+    ///
+    /// For a CSC matrix the columns are outer dimensions, so as for
+    /// `slice_outer` the indices and data of the result borrow the storage
+    /// of `self`. For a CSR matrix, the inner indices of each row have to be
+    /// filtered, so the result owns a copy of the selected elements.
+    /// A regular view can be obtained from the result using `view()`.
+    ///
+    /// # Panics
+    ///
+    /// If the range is out of bounds
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// let eye: CsMat<f64> = CsMat::eye(4);
+    /// let slice = eye.slice_middle_cols(1..3);
+    /// assert_eq!(slice.shape(), (4, 2));
+    /// assert_eq!(slice.indptr(), &[0, 0, 1, 2, 2]);
+    /// assert_eq!(slice.indices(), &[0, 1]);
+    /// ```
+    pub fn slice_middle_cols(
+        &self,
+        range: Range<usize>,
+    ) -> CsMatCowI<'_, N, I, Iptr>
     where
-        N: Clone + Zero,
+        N: Clone,
     {
-        let mut res = Array::zeros((self.rows(), self.cols()));
-        assign_to_dense(res.view_mut(), self.view());
-        res
+        match self.storage {
+            CSR => {
+                let CsMatBase {
+                    storage,
+                    nrows,
+                    ncols,
+                    indptr,
+                    indices,
+                    data,
+                } = self.submatrix(0..self.rows(), range);
+                CsMatBase {
+                    storage,
+                    nrows,
+                    ncols,
+                    indptr,
+                    indices: Cow::Owned(indices),
+                    data: Cow::Owned(data),
+                }
+            }
+            CSC => {
+                let CsMatBase {
+                    storage,
+                    nrows,
+                    ncols,
+                    indptr,
+                    indices,
+                    data,
+                } = self.slice_outer(range);
+                CsMatBase {
+                    storage,
+                    nrows,
+                    ncols,
+                    indptr,
+                    indices: Cow::Borrowed(indices),
+                    data: Cow::Borrowed(data),
+                }
+            }
+        }
     }
 
-    /// Return an outer iterator for the matrix
+    /// Repeat the matrix `row_reps` times vertically and `col_reps` times
+    /// horizontally, giving a matrix of shape
+    /// `(row_reps * rows, col_reps * cols)` with the same storage.
     ///
-    /// This can be used for iterating over the rows (resp. cols) of
-    /// a CSR (resp. CSC) matrix.
+    /// This is the Kronecker product of a `row_reps x col_reps` matrix of
+    /// ones with `self`, computed directly in a single allocation.
+    ///
+    /// # Example
     ///
     /// ```rust
-    /// use sprs::{CsMat};
-    /// let eye = CsMat::eye(5);
-    /// for (row_ind, row_vec) in eye.outer_iterator().enumerate() {
-    ///     let (col_ind, &val): (_, &f64) = row_vec.iter().next().unwrap();
-    ///     assert_eq!(row_ind, col_ind);
-    ///     assert_eq!(val, 1.);
-    /// }
+    /// use sprs::CsMat;
+    /// let eye = CsMat::<f64>::eye(2);
+    /// let tiled = eye.tile(1, 2);
+    /// assert_eq!(tiled.shape(), (2, 4));
+    /// assert_eq!(tiled.indptr(), &[0, 2, 4]);
+    /// assert_eq!(tiled.indices(), &[0, 2, 1, 3]);
     /// ```
-    pub fn outer_iterator(&self) -> OuterIterator<N, I, Iptr> {
-        let inner_len = match self.storage {
-            CSR => self.ncols,
-            CSC => self.nrows,
+    pub fn tile(&self, row_reps: usize, col_reps: usize) -> CsMatI<N, I, Iptr>
+    where
+        N: Clone,
+    {
+        let (outer_reps, inner_reps) = match self.storage {
+            CSR => (row_reps, col_reps),
+            CSC => (col_reps, row_reps),
         };
-        OuterIterator {
-            inner_len,
+        let inner_dims = self.inner_dims();
+        let nnz = self.nnz() * outer_reps * inner_reps;
+        let mut indptr = Vec::with_capacity(self.outer_dims() * outer_reps + 1);
+        let mut indices = Vec::with_capacity(nnz);
+        let mut data = Vec::with_capacity(nnz);
+        indptr.push(Iptr::zero());
+        for _ in 0..outer_reps {
+            for vec in self.outer_iterator() {
+                for rep in 0..inner_reps {
+                    let offset = rep * inner_dims;
+                    indices.extend(
+                        vec.indices()
+                            .iter()
+                            .map(|inner| I::from_usize(inner.index() + offset)),
+                    );
+                    data.extend_from_slice(vec.data());
+                }
+                indptr.push(Iptr::from_usize(indices.len()));
+            }
+        }
+        CsMatI::new_trusted(
+            self.storage,
+            (self.rows() * row_reps, self.cols() * col_reps),
+            indptr,
+            indices,
+            data,
+        )
+    }
+
+    /// Copy the block made of the rows in `rows` and the columns in `cols`
+    /// into a new matrix with the same storage.
+    ///
+    /// # Panics
+    ///
+    /// If the ranges are out of bounds
+    pub fn submatrix(
+        &self,
+        rows: Range<usize>,
+        cols: Range<usize>,
+    ) -> CsMatI<N, I, Iptr>
+    where
+        N: Clone,
+    {
+        assert!(
+            rows.start <= rows.end && rows.end <= self.rows(),
+            "Out of bounds index"
+        );
+        assert!(
+            cols.start <= cols.end && cols.end <= self.cols(),
+            "Out of bounds index"
+        );
+        let (outer_range, inner_range) = match self.storage {
+            CSR => (rows.clone(), cols.clone()),
+            CSC => (cols.clone(), rows.clone()),
+        };
+        let mut indptr = Vec::with_capacity(outer_range.len() + 1);
+        let mut indices = Vec::new();
+        let mut data = Vec::new();
+        indptr.push(Iptr::zero());
+        for outer_ind in outer_range {
+            // outer_ind is in bounds thanks to the assertions above
+            let outer = self.outer_view(outer_ind).unwrap();
+            for (inner_ind, val) in outer.iter() {
+                if inner_range.contains(&inner_ind) {
+                    indices.push(I::from_usize(inner_ind - inner_range.start));
+                    data.push(val.clone());
+                }
+            }
+            indptr.push(Iptr::from_usize(indices.len()));
+        }
+        CsMatI::new_trusted(
+            self.storage,
+            (rows.len(), cols.len()),
+            indptr,
+            indices,
+            data,
+        )
+    }
+
+    pub fn structure_view(&self) -> CsStructureViewI<I, Iptr> {
+        // Safety: std::slice::from_raw_parts requires its passed
+        // pointer to be valid for the whole length of the slice. We have a
+        // zero-sized type, so the length is zero, and since we cast
+        // a non-null pointer, the pointer is valid as all pointers to zero-sized
+        // types are valid if they are not null.
+        let zst_data = unsafe {
+            std::slice::from_raw_parts(
+                self.data.as_ptr() as *const (),
+                self.data.len(),
+            )
+        };
+        CsStructureViewI {
+            storage: self.storage,
+            nrows: self.nrows,
+            ncols: self.ncols,
+            indptr: &self.indptr[..],
+            indices: &self.indices[..],
+            data: zst_data,
+        }
+    }
+
+    /// Convert the matrix to a dense array, whatever its storage.
+    ///
+    /// The returned array has a standard (row-major) layout, so a flat
+    /// row-major buffer can be obtained with `into_raw_vec`:
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// let mat = CsMat::new_csc((2, 2), vec![0, 1, 2], vec![1, 0], vec![1, 2]);
+    /// assert_eq!(mat.to_dense().into_raw_vec(), vec![0, 2, 1, 0]);
+    /// ```
+    pub fn to_dense(&self) -> Array<N, Ix2>
+    where
+        N: Clone + Zero,
+    {
+        let mut res = Array::zeros((self.rows(), self.cols()));
+        assign_to_dense(res.view_mut(), self.view());
+        res
+    }
+
+    /// Return an outer iterator for the matrix
+    ///
+    /// This can be used for iterating over the rows (resp. cols) of
+    /// a CSR (resp. CSC) matrix.
+    ///
+    /// ```rust
+    /// use sprs::{CsMat};
+    /// let eye = CsMat::eye(5);
+    /// for (row_ind, row_vec) in eye.outer_iterator().enumerate() {
+    ///     let (col_ind, &val): (_, &f64) = row_vec.iter().next().unwrap();
+    ///     assert_eq!(row_ind, col_ind);
+    ///     assert_eq!(val, 1.);
+    /// }
+    /// ```
+    pub fn outer_iterator(&self) -> OuterIterator<N, I, Iptr> {
+        let inner_len = match self.storage {
+            CSR => self.ncols,
+            CSC => self.nrows,
+        };
+        OuterIterator {
+            inner_len,
             indptr_iter: self.indptr.windows(2),
             indices: &self.indices[..],
             data: &self.data[..],
         }
     }
 
+    /// Return a parallel iterator over the outer dimensions of the matrix,
+    /// yielding the outer index along with a view of the outer vector.
+    ///
+    /// Each item borrows a disjoint part of the storage, so the items can
+    /// be processed concurrently. This requires the scalar type to be
+    /// `Sync`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rayon::prelude::*;
+    /// use sprs::CsMat;
+    /// let eye: CsMat<f64> = CsMat::eye(5);
+    /// let row_sums: Vec<(usize, f64)> = eye
+    ///     .par_outer_iterator()
+    ///     .map(|(row_ind, row_vec)| (row_ind, row_vec.data().iter().sum()))
+    ///     .collect();
+    /// assert_eq!(row_sums[3], (3, 1.));
+    /// ```
+    #[cfg(feature = "multi_thread")]
+    pub fn par_outer_iterator(
+        &self,
+    ) -> impl IndexedParallelIterator<Item = (usize, CsVecViewI<'_, N, I>)> + '_
+    where
+        N: Sync,
+    {
+        let dim = self.inner_dims();
+        let indptr = &self.indptr[..];
+        let indices = &self.indices[..];
+        let data = &self.data[..];
+        (0..self.outer_dims()).into_par_iter().map(move |i| {
+            let start = indptr[i].index_unchecked();
+            let stop = indptr[i + 1].index_unchecked();
+            // CsMat invariants imply CsVec invariants
+            let vec = CsVecBase {
+                dim,
+                indices: &indices[start..stop],
+                data: &data[start..stop],
+            };
+            (i, vec)
+        })
+    }
+
     /// Return an outer iterator over P*A*P^T, where it is necessary to use
     /// `CsVec::iter_perm(perm.inv())` to iterate over the inner dimension.
     /// Unstable, this is a convenience function for the crate `sprs-ldl`
@@ -1209,6 +1900,87 @@ where
         }
     }
 
+    /// Permute the outer dimension of the matrix: the outer dimension `i`
+    /// of the result is the outer dimension `perm.at(i)` of `self`.
+    ///
+    /// For a CSR matrix, this computes `P * A`, and for a CSC matrix
+    /// `A * P^T`, where `P` is the permutation matrix of `perm`.
+    ///
+    /// # Panics
+    ///
+    /// If the dimension of `perm` differs from `self.outer_dims()`
+    pub fn permute_outer(&self, perm: PermViewI<I>) -> CsMatI<N, I, Iptr>
+    where
+        N: Clone,
+    {
+        assert_eq!(perm.dim(), self.outer_dims(), "Dimension mismatch");
+        let mut indptr = Vec::with_capacity(self.indptr.len());
+        let mut indices = Vec::with_capacity(self.nnz());
+        let mut data = Vec::with_capacity(self.nnz());
+        indptr.push(Iptr::zero());
+        for outer_ind in 0..self.outer_dims() {
+            // perm.at() is within bounds since perm.dim() == outer_dims
+            let outer = self.outer_view(perm.at(outer_ind)).unwrap();
+            indices.extend_from_slice(outer.indices());
+            data.extend_from_slice(outer.data());
+            indptr.push(Iptr::from_usize(indices.len()));
+        }
+        CsMatI::new_trusted(self.storage, self.shape(), indptr, indices, data)
+    }
+
+    /// Permute the inner dimension of the matrix: the inner index `perm.at(j)`
+    /// of `self` becomes the inner index `j` of the result.
+    ///
+    /// For a CSR matrix, this computes `A * P^T`, and for a CSC matrix
+    /// `P * A`, where `P` is the permutation matrix of `perm`. The indices
+    /// of each outer dimension are sorted again after being permuted.
+    ///
+    /// # Panics
+    ///
+    /// If the dimension of `perm` differs from `self.inner_dims()`
+    pub fn permute_inner(&self, perm: PermViewI<I>) -> CsMatI<N, I, Iptr>
+    where
+        N: Clone,
+    {
+        assert_eq!(perm.dim(), self.inner_dims(), "Dimension mismatch");
+        let mut indptr = Vec::with_capacity(self.indptr.len());
+        let mut indices = Vec::with_capacity(self.nnz());
+        let mut data = Vec::with_capacity(self.nnz());
+        let mut tmp = Vec::with_capacity(self.max_outer_nnz());
+        indptr.push(Iptr::zero());
+        for outer in self.outer_iterator() {
+            for (ind, val) in outer.iter() {
+                tmp.push((perm.at_inv(ind), val.clone()));
+            }
+            tmp.sort_by_key(|&(ind, _)| ind);
+            for (ind, val) in tmp.drain(..) {
+                indices.push(I::from_usize(ind));
+                data.push(val);
+            }
+            indptr.push(Iptr::from_usize(indices.len()));
+        }
+        CsMatI::new_trusted(self.storage, self.shape(), indptr, indices, data)
+    }
+
+    /// Apply the same permutation to the rows and columns of a square
+    /// matrix, ie compute `P * A * P^T`, where `P` is the permutation matrix
+    /// of `perm`. The structure of a symmetric matrix is thus kept.
+    ///
+    /// The entry at `(perm.at(i), perm.at(j))` in `self` ends up at `(i, j)`
+    /// in the result. Both dimensions are permuted in a single pass over the
+    /// matrix, see [`transform_mat_papt`](fn.transform_mat_papt.html).
+    ///
+    /// # Panics
+    ///
+    /// - if the matrix is not square
+    /// - if the dimension of `perm` does not match the matrix
+    pub fn permute_symmetric(&self, perm: PermViewI<I>) -> CsMatI<N, I, Iptr>
+    where
+        N: Copy + std::fmt::Debug,
+    {
+        crate::sparse::permutation::transform_mat_papt(self.view(), perm)
+    }
+
     /// Get the max number of nnz for each outer dim
     pub fn max_outer_nnz(&self) -> usize {
         self.outer_iterator()
@@ -1240,1826 +2012,4681 @@ where
             .collect()
     }
 
-    /// Get a view into the i-th outer dimension (eg i-th row for a CSR matrix)
-    pub fn outer_view(&self, i: usize) -> Option<CsVecViewI<N, I>> {
-        if i >= self.outer_dims() {
-            return None;
-        }
-        let start = self.indptr[i].index_unchecked();
-        let stop = self.indptr[i + 1].index_unchecked();
-        // CsMat invariants imply CsVec invariants
-        Some(CsVecBase {
-            dim: self.inner_dims(),
-            indices: &self.indices[start..stop],
-            data: &self.data[start..stop],
-        })
+    /// Get the bandwidth of the matrix, ie the maximum of `|row - col|`
+    /// over its non-zero locations.
+    ///
+    /// A diagonal matrix has a bandwidth of `0`, and a tridiagonal matrix
+    /// a bandwidth of `1`. This is the quantity that the Cuthill-McKee
+    /// orderings in `linalg::ordering` try to reduce.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// let mat = CsMat::new((3, 3), vec![0, 2, 3, 4], vec![0, 2, 1, 2],
+    ///                      vec![1., 2., 3., 4.]);
+    /// assert_eq!(mat.bandwidth(), 2);
+    /// assert_eq!(CsMat::<f64>::eye(3).bandwidth(), 0);
+    /// ```
+    pub fn bandwidth(&self) -> usize {
+        self.outer_iterator()
+            .enumerate()
+            .filter_map(|(outer, vec)| {
+                // the indices are sorted, the farthest ones are at the ends
+                let first = vec.indices().first()?.index();
+                let last = vec.indices().last()?.index();
+                Some(
+                    outer.saturating_sub(first).max(last.saturating_sub(outer)),
+                )
+            })
+            .max()
+            .unwrap_or(0)
     }
 
-    /// Iteration on outer blocks of size block_size
-    pub fn outer_block_iter(
-        &self,
-        block_size: usize,
-    ) -> ChunkOuterBlocks<N, I, Iptr> {
-        let m = CsMatBase {
-            storage: self.storage,
-            nrows: self.rows(),
-            ncols: self.cols(),
-            indptr: &self.indptr[..],
-            indices: &self.indices[..],
-            data: &self.data[..],
-        };
-        ChunkOuterBlocks {
-            mat: m,
-            dims_in_bloc: block_size,
-            bloc_count: 0,
+    /// Get the profile of the matrix, ie the sum over its rows of the
+    /// distance between the diagonal and the first non-zero of the row,
+    /// `row - min_col`. Rows without non-zeros before the diagonal do not
+    /// contribute to the profile.
+    ///
+    /// This is the number of elements below the diagonal that a skyline
+    /// (or envelope) storage of the lower triangular part of the matrix has
+    /// to store. For a symmetric matrix, it is also the profile of the upper
+    /// triangular part.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// // | 1 0 0 |
+    /// // | 0 2 0 |
+    /// // | 3 0 4 |
+    /// let mat = CsMat::new((3, 3), vec![0, 1, 2, 4], vec![0, 1, 0, 2],
+    ///                      vec![1., 2., 3., 4.]);
+    /// assert_eq!(mat.profile(), 2);
+    /// ```
+    pub fn profile(&self) -> usize {
+        match self.storage {
+            CSR => self
+                .outer_iterator()
+                .enumerate()
+                .filter_map(|(row, vec)| {
+                    let min_col = vec.indices().first()?.index();
+                    Some(row.saturating_sub(min_col))
+                })
+                .sum(),
+            CSC => {
+                // the columns are visited in increasing order, so the first
+                // time a row is met gives its minimum column
+                let mut min_cols = vec![None; self.rows()];
+                for (col, vec) in self.outer_iterator().enumerate() {
+                    for row in vec.indices() {
+                        min_cols[row.index()].get_or_insert(col);
+                    }
+                }
+                min_cols
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(row, min_col)| {
+                        Some(row.saturating_sub((*min_col)?))
+                    })
+                    .sum()
+            }
         }
     }
 
-    /// Return a new sparse matrix with the same sparsity pattern, with all non-zero values mapped by the function `f`.
-    pub fn map<F, N2>(&self, f: F) -> CsMatI<N2, I, Iptr>
+    /// Return the main diagonal of the matrix as a dense vector of length
+    /// `min(rows, cols)`. Diagonal locations without a non-zero are
+    /// returned as zero.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// let mat = CsMat::new((2, 3), vec![0, 1, 2], vec![0, 2], vec![1, 2]);
+    /// assert_eq!(mat.diag(), vec![1, 0]);
+    /// ```
+    pub fn diag(&self) -> Vec<N>
     where
-        F: FnMut(&N) -> N2,
+        N: Clone + Zero,
     {
-        let data: Vec<N2> = self.data.iter().map(f).collect();
-
-        CsMatI {
-            storage: self.storage,
-            nrows: self.nrows,
-            ncols: self.ncols,
-            indptr: self.indptr.to_vec(),
-            indices: self.indices.to_vec(),
-            data,
-        }
+        let len = self.rows().min(self.cols());
+        (0..len)
+            .map(|i| self.get(i, i).cloned().unwrap_or_else(N::zero))
+            .collect()
     }
 
-    /// Access an element given its outer_ind and inner_ind.
-    /// Will return None if there is no non-zero element at this location.
+    /// Sum of the diagonal elements of a square matrix.
     ///
-    /// This access is logarithmic in the number of non-zeros
-    /// in the corresponding outer slice. It is therefore advisable not to rely
-    /// on this for algorithms, and prefer outer_iterator() which accesses
-    /// elements in storage order.
-    pub fn get_outer_inner(
-        &self,
-        outer_ind: usize,
-        inner_ind: usize,
-    ) -> Option<&N> {
-        self.outer_view(outer_ind)
-            .and_then(|vec| vec.get_rbr(inner_ind))
-    }
-
-    /// Find the non-zero index of the element specified by row and col
+    /// # Panics
     ///
-    /// Searching this index is logarithmic in the number of non-zeros
-    /// in the corresponding outer slice.
-    /// Once it is available, the NnzIndex enables retrieving the data with
-    /// O(1) complexity.
-    pub fn nnz_index(&self, row: usize, col: usize) -> Option<NnzIndex> {
-        match self.storage() {
-            CSR => self.nnz_index_outer_inner(row, col),
-            CSC => self.nnz_index_outer_inner(col, row),
-        }
+    /// If the matrix is not square
+    pub fn trace(&self) -> N
+    where
+        N: Copy + Num,
+    {
+        assert_eq!(self.rows(), self.cols(), "Non square matrix");
+        (0..self.rows())
+            .filter_map(|i| self.get(i, i))
+            .fold(N::zero(), |acc, &x| acc + x)
     }
 
-    /// Find the non-zero index of the element specified by outer_ind and
-    /// inner_ind.
+    /// Add `alpha` to the diagonal of a square matrix, ie compute
+    /// `A + alpha I`, returning a new matrix with the same storage.
     ///
-    /// Searching this index is logarithmic in the number of non-zeros
-    /// in the corresponding outer slice.
-    pub fn nnz_index_outer_inner(
-        &self,
-        outer_ind: usize,
-        inner_ind: usize,
-    ) -> Option<NnzIndex> {
-        if outer_ind >= self.outer_dims() {
-            return None;
-        }
-        let offset = self.indptr[outer_ind].index_unchecked();
-        self.outer_view(outer_ind)
-            .and_then(|vec| vec.nnz_index(inner_ind))
-            .map(|vec::NnzIndex(ind)| NnzIndex(ind + offset))
-    }
-
-    /// Check the structure of CsMat components
-    /// This will ensure that:
-    /// * indptr is of length outer_dim() + 1
-    /// * indices and data have the same length, nnz == indptr\[outer_dims()\]
-    /// * indptr is sorted
-    /// * indptr values do not exceed usize::MAX / 2, as that would mean
-    ///   indices and indptr would take more space than the addressable memory
-    /// * indices is sorted for each outer slice
-    /// * indices are lower than inner_dims()
-    pub fn check_compressed_structure(&self) -> Result<(), SprsError> {
-        // Make sure both indptr and indices can be converted to usize
-        for i in self.indptr.iter() {
-            if i.try_index().is_none() {
-                return Err(SprsError::IllegalArguments(
-                    "Indptr value out of range of usize",
-                ));
+    /// The missing diagonal elements are inserted in their sorted position,
+    /// while the diagonal sums that cancel out are not stored. The other
+    /// elements are copied unchanged, including explicitly stored zeros.
+    ///
+    /// # Panics
+    ///
+    /// If the matrix is not square
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// // | 0 1 |
+    /// // | 1 2 |
+    /// let mat = CsMat::new((2, 2), vec![0, 1, 3], vec![1, 0, 1],
+    ///                      vec![1., 1., 2.]);
+    /// let shifted = mat.add_scalar_diag(0.5);
+    /// assert_eq!(shifted, CsMat::new((2, 2), vec![0, 2, 4],
+    ///                                vec![0, 1, 0, 1],
+    ///                                vec![0.5, 1., 1., 2.5]));
+    /// ```
+    pub fn add_scalar_diag(&self, alpha: N) -> CsMatI<N, I, Iptr>
+    where
+        N: Num + Copy,
+    {
+        assert_eq!(self.rows(), self.cols(), "Non square matrix");
+        let nnz = self.nnz() + self.outer_dims();
+        let mut indptr = Vec::with_capacity(self.outer_dims() + 1);
+        let mut indices = Vec::with_capacity(nnz);
+        let mut data = Vec::with_capacity(nnz);
+        indptr.push(Iptr::zero());
+        for (outer_ind, vec) in self.outer_iterator().enumerate() {
+            let mut diag_done = false;
+            for (inner_ind, &x) in vec.iter() {
+                if inner_ind == outer_ind {
+                    diag_done = true;
+                    let val = x + alpha;
+                    if !val.is_zero() {
+                        indices.push(I::from_usize(inner_ind));
+                        data.push(val);
+                    }
+                    continue;
+                }
+                if !diag_done && inner_ind > outer_ind {
+                    diag_done = true;
+                    if !alpha.is_zero() {
+                        indices.push(I::from_usize(outer_ind));
+                        data.push(alpha);
+                    }
+                }
+                indices.push(I::from_usize(inner_ind));
+                data.push(x);
             }
-        }
-        for i in self.indices.iter() {
-            if i.try_index().is_none() {
-                return Err(SprsError::IllegalArguments(
-                    "Indices value out of range of usize",
-                ));
+            if !diag_done && !alpha.is_zero() {
+                indices.push(I::from_usize(outer_ind));
+                data.push(alpha);
             }
+            indptr.push(Iptr::from_usize(indices.len()));
         }
+        CsMatI::new_trusted(self.storage, self.shape(), indptr, indices, data)
+    }
 
-        let outer = self.outer_dims();
+    /// Frobenius norm of the matrix, ie the square root of the sum of the
+    /// squares of its non-zero elements.
+    pub fn norm_frobenius(&self) -> N
+    where
+        N: Float,
+    {
+        self.outer_iterator()
+            .fold(N::zero(), |acc, vec| {
+                vec.data().iter().fold(acc, |acc, &x| acc + x * x)
+            })
+            .sqrt()
+    }
 
-        if self.indptr.len() != outer + 1 {
-            return Err(SprsError::IllegalArguments(
-                "Indptr length does not match dimension",
-            ));
-        }
-        if self.indices.len() != self.data.len() {
-            return Err(SprsError::IllegalArguments(
-                "Indices and data lengths do not match",
-            ));
-        }
-        let nnz = self.indices.len();
-        if nnz != self.nnz() {
-            return Err(SprsError::IllegalArguments(
-                "Indices length and inpdtr's nnz do not match",
-            ));
-        }
-        if let Some(&max_indptr) = self.indptr.iter().max() {
-            if max_indptr.index_unchecked() > nnz {
-                return Err(SprsError::IllegalArguments(
-                    "An indptr value is out of bounds",
-                ));
-            }
-            if max_indptr.index_unchecked() > usize::max_value() / 2 {
-                // We do not allow indptr values to be larger than half
-                // the maximum value of an usize, as that would clearly exhaust
-                // all available memory
-                // This means we could have an isize, but in practice it's
-                // easier to work with usize for indexing.
-                return Err(SprsError::IllegalArguments(
-                    "An indptr value is larger than allowed",
-                ));
-            }
-        } else {
-            unreachable!();
-        }
+    /// The matrix norm induced by the vector 1-norm, ie the maximum
+    /// absolute column sum.
+    ///
+    /// This is computed in storage order for both storages, but is
+    /// simpler for a CSC matrix: a CSR matrix requires an additional
+    /// buffer of length `cols()` to accumulate the column sums.
+    pub fn norm_1(&self) -> N
+    where
+        N: Float,
+    {
+        self.abs_sums(CSC).into_iter().fold(N::zero(), N::max)
+    }
 
-        if !self
-            .indptr
-            .deref()
-            .windows(2)
-            .all(|x| x[0].index_unchecked() <= x[1].index_unchecked())
-        {
-            return Err(SprsError::UnsortedIndptr);
-        }
+    /// The matrix norm induced by the vector infinity-norm, ie the maximum
+    /// absolute row sum.
+    ///
+    /// This is computed in storage order for both storages, but is
+    /// simpler for a CSR matrix: a CSC matrix requires an additional
+    /// buffer of length `rows()` to accumulate the row sums.
+    pub fn norm_inf(&self) -> N
+    where
+        N: Float,
+    {
+        self.abs_sums(CSR).into_iter().fold(N::zero(), N::max)
+    }
 
-        // check that the indices are sorted for each row
-        for vec in self.outer_iterator() {
-            vec.check_structure()?;
+    /// Sums of the absolute values of each row (`axis == CSR`) or each
+    /// column (`axis == CSC`)
+    fn abs_sums(&self, axis: CompressedStorage) -> Vec<N>
+    where
+        N: Float,
+    {
+        if self.storage == axis {
+            self.outer_iterator()
+                .map(|vec| {
+                    vec.data().iter().fold(N::zero(), |acc, &x| acc + x.abs())
+                })
+                .collect()
+        } else {
+            let mut sums = vec![N::zero(); self.inner_dims()];
+            for vec in self.outer_iterator() {
+                for (ind, &x) in vec.iter() {
+                    sums[ind] = sums[ind] + x.abs();
+                }
+            }
+            sums
         }
-
-        Ok(())
     }
 
-    /// Get an iterator that yields the non-zero locations and values stored in
-    /// this matrix, in the fastest iteration order.
-    pub fn iter(&self) -> CsIter<N, I, Iptr> {
-        CsIter {
-            storage: self.storage,
-            cur_outer: I::zero(),
-            indptr: &self.indptr[..],
-            inner_iter: self.indices.iter().zip(self.data.iter()).enumerate(),
-        }
+    /// Sum of all the stored values of the matrix
+    pub fn sum(&self) -> N
+    where
+        N: Num + Copy,
+    {
+        self.outer_iterator().fold(N::zero(), |acc, vec| {
+            vec.data().iter().fold(acc, |acc, &x| acc + x)
+        })
     }
-}
 
-/// # Methods to convert between storage orders
-impl<N, I, Iptr, IptrStorage, IndStorage, DataStorage>
-    CsMatBase<N, I, IptrStorage, IndStorage, DataStorage, Iptr>
-where
-    N: Default,
-    I: SpIndex,
-    Iptr: SpIndex,
-    IptrStorage: Deref<Target = [Iptr]>,
-    IndStorage: Deref<Target = [I]>,
-    DataStorage: Deref<Target = [N]>,
-{
-    /// Create a matrix mathematically equal to this one, but with the
-    /// opposed storage (a CSC matrix will be converted to CSR, and vice versa)
-    pub fn to_other_storage(&self) -> CsMatI<N, I, Iptr>
+    /// Sums of the stored values of each outer dimension, ie the row sums
+    /// of a CSR matrix, or the column sums of a CSC matrix.
+    ///
+    /// For a CSR matrix, this is the product of the matrix with a vector
+    /// of ones.
+    pub fn sum_outer(&self) -> Vec<N>
     where
-        N: Clone,
+        N: Num + Copy,
     {
-        let mut indptr = vec![Iptr::zero(); self.inner_dims() + 1];
-        let mut indices = vec![I::zero(); self.nnz()];
-        let mut data = vec![N::default(); self.nnz()];
-        raw::convert_mat_storage(
-            self.view(),
-            &mut indptr,
-            &mut indices,
-            &mut data,
-        );
-        CsMatI {
-            storage: self.storage().other_storage(),
-            nrows: self.nrows,
-            ncols: self.ncols,
-            indptr,
-            indices,
-            data,
-        }
+        self.fold_outer(N::zero(), |acc, _, &x| acc + x)
     }
 
-    /// Create a new CSC matrix equivalent to this one.
-    /// A new matrix will be created even if this matrix was already CSC.
-    pub fn to_csc(&self) -> CsMatI<N, I, Iptr>
+    /// Fold the stored values of each outer dimension, eg each row of a CSR
+    /// matrix, into an accumulator, returning one accumulator per outer
+    /// dimension.
+    ///
+    /// Each accumulator starts at `init`, and is updated by calling
+    /// `f(acc, inner_ind, &value)` on the stored values of the outer
+    /// dimension, in storage order. Outer dimensions without stored values
+    /// get `init`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// // | 1 0 2 |
+    /// // | 0 0 0 |
+    /// // | 0 3 0 |
+    /// let mat = CsMat::new((3, 3), vec![0, 2, 2, 3], vec![0, 2, 1],
+    ///                      vec![1, 2, 3]);
+    /// let counts = mat.fold_outer(0, |count, _, _| count + 1);
+    /// assert_eq!(counts, vec![2, 0, 1]);
+    /// let last_cols = mat.fold_outer(None, |_, col, _| Some(col));
+    /// assert_eq!(last_cols, vec![Some(2), None, Some(1)]);
+    /// ```
+    pub fn fold_outer<B, F>(&self, init: B, mut f: F) -> Vec<B>
     where
-        N: Clone,
+        B: Clone,
+        F: FnMut(B, usize, &N) -> B,
     {
-        match self.storage {
-            CSR => self.to_other_storage(),
-            CSC => self.to_owned(),
-        }
+        self.outer_iterator()
+            .map(|vec| {
+                vec.iter()
+                    .fold(init.clone(), |acc, (ind, val)| f(acc, ind, val))
+            })
+            .collect()
     }
 
-    /// Create a new CSR matrix equivalent to this one.
-    /// A new matrix will be created even if this matrix was already CSR.
-    pub fn to_csr(&self) -> CsMatI<N, I, Iptr>
+    /// Sums of the stored values of each inner dimension, ie the column
+    /// sums of a CSR matrix, or the row sums of a CSC matrix.
+    fn sum_inner(&self) -> Vec<N>
     where
-        N: Clone,
+        N: Num + Copy,
     {
-        match self.storage {
-            CSR => self.to_owned(),
-            CSC => self.to_other_storage(),
+        let mut sums = vec![N::zero(); self.inner_dims()];
+        for vec in self.outer_iterator() {
+            for (ind, &x) in vec.iter() {
+                sums[ind] = sums[ind] + x;
+            }
         }
+        sums
     }
-}
-
-impl<N, I, Iptr> CsMatI<N, I, Iptr>
-where
-    N: Default,
 
-    I: SpIndex,
-    Iptr: SpIndex,
-{
-    /// Create a new CSC matrix equivalent to this one.
-    /// If this matrix is CSR, it is converted to CSC
-    /// If this matrix is CSC, it is returned by value
-    pub fn into_csc(self) -> CsMatI<N, I, Iptr>
+    /// Sums of the rows of the matrix, as a dense vector of length
+    /// `rows()`.
+    ///
+    /// This is cheap for a CSR matrix, and needs a scatter pass over the
+    /// non-zeros for a CSC matrix.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// // | 1 0 2 |
+    /// // | 0 3 0 |
+    /// let mat = CsMat::new((2, 3), vec![0, 2, 3], vec![0, 2, 1],
+    ///                      vec![1, 2, 3]);
+    /// assert_eq!(mat.row_sums(), vec![3, 3]);
+    /// assert_eq!(mat.col_sums(), vec![1, 3, 2]);
+    /// ```
+    pub fn row_sums(&self) -> Vec<N>
     where
-        N: Clone,
+        N: Num + Copy,
     {
         match self.storage {
-            CSR => self.to_other_storage(),
-            CSC => self,
+            CSR => self.sum_outer(),
+            CSC => self.sum_inner(),
         }
     }
 
-    /// Create a new CSR matrix equivalent to this one.
-    /// If this matrix is CSC, it is converted to CSR
-    /// If this matrix is CSR, it is returned by value
-    pub fn into_csr(self) -> CsMatI<N, I, Iptr>
+    /// Sums of the columns of the matrix, as a dense vector of length
+    /// `cols()`.
+    ///
+    /// This is cheap for a CSC matrix, and needs a scatter pass over the
+    /// non-zeros for a CSR matrix.
+    pub fn col_sums(&self) -> Vec<N>
     where
-        N: Clone,
+        N: Num + Copy,
     {
         match self.storage {
-            CSR => self,
-            CSC => self.to_other_storage(),
+            CSR => self.sum_inner(),
+            CSC => self.sum_outer(),
         }
     }
-}
 
-/// # Methods for sparse matrices holding mutable access to their values.
-impl<N, I, Iptr, IptrStorage, IndStorage, DataStorage>
-    CsMatBase<N, I, IptrStorage, IndStorage, DataStorage, Iptr>
-where
-    I: SpIndex,
-    Iptr: SpIndex,
-    IptrStorage: Deref<Target = [Iptr]>,
-    IndStorage: Deref<Target = [I]>,
-    DataStorage: DerefMut<Target = [N]>,
-{
-    /// Mutable access to the non zero values
+    /// The largest stored value of the matrix, with its `(row, col)`
+    /// location, or `None` if the matrix has no stored values.
     ///
-    /// This enables changing the values without changing the matrix's
-    /// structure. To also change the matrix's structure,
-    /// see [modify](fn.modify.html)
-    pub fn data_mut(&mut self) -> &mut [N] {
-        &mut self.data[..]
+    /// Only the stored values are considered: if they are all negative,
+    /// the result is lower than the implicit zeros of the matrix. On ties,
+    /// the first value in storage order is returned.
+    pub fn max(&self) -> Option<(usize, usize, N)>
+    where
+        N: PartialOrd + Copy,
+    {
+        self.extremum(|x, best| x > best)
     }
 
-    /// Sparse matrix self-multiplication by a scalar
-    pub fn scale(&mut self, val: N)
+    /// The smallest stored value of the matrix, with its `(row, col)`
+    /// location, or `None` if the matrix has no stored values.
+    ///
+    /// Only the stored values are considered: if they are all positive,
+    /// the result is greater than the implicit zeros of the matrix. On
+    /// ties, the first value in storage order is returned.
+    pub fn min(&self) -> Option<(usize, usize, N)>
     where
-        N: Num + Copy,
+        N: PartialOrd + Copy,
     {
-        for data in self.data_mut() {
-            *data = *data * val;
-        }
+        self.extremum(|x, best| x < best)
     }
 
-    /// Get a mutable view into the i-th outer dimension
-    /// (eg i-th row for a CSR matrix)
-    pub fn outer_view_mut(&mut self, i: usize) -> Option<CsVecViewMutI<N, I>> {
-        if i >= self.outer_dims() {
-            return None;
-        }
-        let start = self.indptr[i].index_unchecked();
-        let stop = self.indptr[i + 1].index_unchecked();
-        // CsMat invariants imply CsVec invariants
-        Some(CsVecBase {
-            dim: self.inner_dims(),
-            indices: &self.indices[start..stop],
-            data: &mut self.data[start..stop],
+    fn extremum<F>(&self, better: F) -> Option<(usize, usize, N)>
+    where
+        N: Copy,
+        F: Fn(&N, &N) -> bool,
+    {
+        self.iter().fold(None, |best, (&x, (row, col))| match best {
+            Some((_, _, best_x)) if !better(&x, &best_x) => best,
+            _ => Some((row.index(), col.index(), x)),
         })
     }
 
-    /// Get a mutable reference to the element located at row i and column j.
-    /// Will return None if there is no non-zero element at this location.
+    /// The largest stored value of each row, as a dense vector of length
+    /// `rows()`, holding `None` for the rows without stored values.
     ///
-    /// This access is logarithmic in the number of non-zeros
-    /// in the corresponding outer slice. It is therefore advisable not to rely
-    /// on this for algorithms, and prefer outer_iterator_mut() which accesses
-    /// elements in storage order.
-    /// TODO: outer_iterator_mut is not yet implemented
-    pub fn get_mut(&mut self, i: usize, j: usize) -> Option<&mut N> {
-        match self.storage {
-            CSR => self.get_outer_inner_mut(i, j),
-            CSC => self.get_outer_inner_mut(j, i),
-        }
+    /// As for `max`, only the stored values are considered, so the maximum
+    /// of a row with negative stored values is negative even if the row
+    /// has implicit zeros.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// // | 1 0 -2 |
+    /// // | 0 0  0 |
+    /// // | 0 3  0 |
+    /// let mat = CsMat::new((3, 3), vec![0, 2, 2, 3], vec![0, 2, 1],
+    ///                      vec![1, -2, 3]);
+    /// assert_eq!(mat.row_max(), vec![Some(1), None, Some(3)]);
+    /// assert_eq!(mat.row_min(), vec![Some(-2), None, Some(3)]);
+    /// assert_eq!(mat.row_argmax(), vec![Some(0), None, Some(1)]);
+    /// ```
+    pub fn row_max(&self) -> Vec<Option<N>>
+    where
+        N: PartialOrd + Copy,
+    {
+        self.row_extremum(|x, best| x > best)
+            .into_iter()
+            .map(|best| best.map(|(_, x)| x))
+            .collect()
     }
 
-    /// Get a mutable reference to an element given its outer_ind and inner_ind.
-    /// Will return None if there is no non-zero element at this location.
+    /// The smallest stored value of each row, as a dense vector of length
+    /// `rows()`, holding `None` for the rows without stored values.
     ///
-    /// This access is logarithmic in the number of non-zeros
-    /// in the corresponding outer slice. It is therefore advisable not to rely
-    /// on this for algorithms, and prefer outer_iterator_mut() which accesses
-    /// elements in storage order.
-    pub fn get_outer_inner_mut(
-        &mut self,
-        outer_ind: usize,
-        inner_ind: usize,
-    ) -> Option<&mut N> {
-        if let Some(NnzIndex(index)) =
-            self.nnz_index_outer_inner(outer_ind, inner_ind)
-        {
-            Some(&mut self.data[index])
-        } else {
-            None
-        }
+    /// As for `min`, only the stored values are considered.
+    pub fn row_min(&self) -> Vec<Option<N>>
+    where
+        N: PartialOrd + Copy,
+    {
+        self.row_extremum(|x, best| x < best)
+            .into_iter()
+            .map(|best| best.map(|(_, x)| x))
+            .collect()
     }
 
-    /// Set the value of the non-zero element located at (row, col)
-    ///
-    /// # Panics
+    /// The column of the largest stored value of each row, as a dense vector
+    /// of length `rows()`, holding `None` for the rows without stored values.
     ///
-    /// - on out-of-bounds access
-    /// - if no non-zero element exists at the given location
-    pub fn set(&mut self, row: usize, col: usize, val: N) {
-        let outer = outer_dimension(self.storage(), row, col);
-        let inner = inner_dimension(self.storage(), row, col);
-        let vec::NnzIndex(index) = self
-            .outer_view(outer)
-            .and_then(|vec| vec.nnz_index(inner))
-            .unwrap();
-        self.data[index] = val;
-    }
-
-    /// Apply a function to every non-zero element
-    pub fn map_inplace<F>(&mut self, mut f: F)
+    /// Only the stored values are considered. On ties, the smallest column
+    /// is returned.
+    pub fn row_argmax(&self) -> Vec<Option<usize>>
     where
-        F: FnMut(&N) -> N,
+        N: PartialOrd + Copy,
     {
-        for val in &mut self.data[..] {
-            *val = f(val);
-        }
+        self.row_extremum(|x, best| x > best)
+            .into_iter()
+            .map(|best| best.map(|(col, _)| col))
+            .collect()
     }
 
-    /// Return a mutable outer iterator for the matrix
-    ///
-    /// This iterator yields mutable sparse vector views for each outer
-    /// dimension. Only the non-zero values can be modified, the
-    /// structure is kept immutable.
-    pub fn outer_iterator_mut(&mut self) -> OuterIteratorMut<N, I, Iptr> {
-        let inner_len = match self.storage {
-            CSR => self.ncols,
-            CSC => self.nrows,
+    /// The best stored value of each row according to `better`, with its
+    /// column, keeping the smallest column on ties.
+    fn row_extremum<F>(&self, better: F) -> Vec<Option<(usize, N)>>
+    where
+        N: Copy,
+        F: Fn(&N, &N) -> bool,
+    {
+        let update = |best: Option<(usize, N)>, col, x: N| match best {
+            Some((_, best_x)) if !better(&x, &best_x) => best,
+            _ => Some((col, x)),
         };
-        OuterIteratorMut {
-            inner_len,
-            indptr_iter: self.indptr.windows(2),
-            indices: &self.indices[..],
-            data: &mut self.data[..],
+        match self.storage {
+            CSR => self.fold_outer(None, |best, col, &x| update(best, col, x)),
+            CSC => {
+                // the columns are visited in increasing order
+                let mut res = vec![None; self.rows()];
+                for (col, vec) in self.outer_iterator().enumerate() {
+                    for (row, &x) in vec.iter() {
+                        res[row] = update(res[row], col, x);
+                    }
+                }
+                res
+            }
         }
     }
-}
 
-impl<N, I, Iptr, IptrStorage, IndStorage, DataStorage>
-    CsMatBase<N, I, IptrStorage, IndStorage, DataStorage, Iptr>
-where
-    I: SpIndex,
-    Iptr: SpIndex,
-    IptrStorage: DerefMut<Target = [Iptr]>,
-    IndStorage: DerefMut<Target = [I]>,
-    DataStorage: DerefMut<Target = [N]>,
-{
-    /// Modify the matrix's structure without changing its nonzero count.
+    /// Add the dense row vector `v` to each row of the matrix, returning
+    /// a new matrix with the same storage.
     ///
-    /// The coherence of the structure will be checked afterwards.
+    /// The result has a non-zero in every column where `v` is non-zero, so
+    /// its number of non-zeros can grow up to the number of stored values
+    /// of the matrix plus `rows()` times the number of non-zeros of `v`.
+    /// The sums that cancel out are not stored.
     ///
     /// # Panics
     ///
-    /// If the resulting matrix breaks the CsMat invariants (sorted indices,
-    /// no out of bounds indices).
+    /// If `v.len() != self.cols()`.
     ///
     /// # Example
     ///
     /// ```rust
     /// use sprs::CsMat;
-    /// // |   1   |
-    /// // | 1     |
-    /// // |   1 1 |
-    /// let mut mat = CsMat::new_csc((3, 3),
-    ///                                   vec![0, 1, 3, 4],
-    ///                                   vec![1, 0, 2, 2],
-    ///                                   vec![1.; 4]);
-    ///
-    /// // | 1 2   |
-    /// // | 1     |
-    /// // |   1   |
-    /// mat.modify(|indptr, indices, data| {
-    ///     indptr[1] = 2;
-    ///     indptr[2] = 4;
-    ///     indices[0] = 0;
-    ///     indices[1] = 1;
-    ///     indices[2] = 0;
-    ///     data[2] = 2.;
-    /// });
+    /// // | 1 0 2 |
+    /// // | 0 3 0 |
+    /// let mat = CsMat::new((2, 3), vec![0, 2, 3], vec![0, 2, 1],
+    ///                      vec![1, 2, 3]);
+    /// let res = mat.add_row_broadcast(&[0, 1, -2]);
+    /// // | 1 1 0 |
+    /// // | 0 4 -2 |
+    /// assert_eq!(res, CsMat::new((2, 3), vec![0, 2, 4], vec![0, 1, 1, 2],
+    ///                            vec![1, 1, 4, -2]));
     /// ```
-    pub fn modify<F>(&mut self, mut f: F)
+    pub fn add_row_broadcast(&self, v: &[N]) -> CsMatI<N, I, Iptr>
     where
-        F: FnMut(&mut [Iptr], &mut [I], &mut [N]),
+        N: Num + Copy,
     {
-        f(
-            &mut self.indptr[..],
-            &mut self.indices[..],
-            &mut self.data[..],
-        );
-        self.check_compressed_structure().unwrap();
-    }
-}
-
-/// Raw functions acting directly on the compressed structure.
-pub mod raw {
-    use crate::indexing::SpIndex;
-    use crate::sparse::prelude::*;
-    use std::mem::swap;
-
-    /*
-        /// Copy-convert a compressed matrix into the oppposite storage.
-        ///
-        /// The input compressed matrix does not need to have its indices sorted,
-        /// but the output compressed matrix will have its indices sorted.
-        ///
-        /// Can be used to implement CSC <-> CSR conversions, or to implement
-        /// same-storage (copy) transposition.
-        ///
-        /// # Panics
-        ///
-        /// Panics if indptr contains non-zero values
-        ///
-        /// Panics if the output slices don't match the input matrices'
-        /// corresponding slices.
-        pub fn convert_storage<N, I>(
-            in_storage: super::CompressedStorage,
-            shape: Shape,
-            in_indtpr: &[I],
-            in_indices: &[I],
-            in_data: &[N],
-            indptr: &mut [I],
-            indices: &mut [I],
-            data: &mut [N],
-        ) where
-            N: Clone,
-            I: SpIndex,
-        {
-            // we're building a csmat even though the indices are not sorted,
-            // but it's not a problem since we don't rely on this property.
-            // FIXME: this would be better with an explicit unsorted matrix type
-            let mat = CsMatBase {
-                storage: in_storage,
-                nrows: shape.0,
-                ncols: shape.1,
-                indptr: in_indtpr,
-                indices: in_indices,
-                data: in_data,
-            };
+        // only store the non-zero results
+        fn push<N: Num, I: SpIndex>(
+            indices: &mut Vec<I>,
+            data: &mut Vec<N>,
+            ind: usize,
+            val: N,
+        ) {
+            if !val.is_zero() {
+                indices.push(I::from_usize(ind));
+                data.push(val);
+            }
+        }
 
-            convert_mat_storage(mat, indptr, indices, data);
+        assert_eq!(v.len(), self.cols(), "Dimension mismatch");
+        let v_nnz: Vec<usize> =
+            (0..v.len()).filter(|&j| !v[j].is_zero()).collect();
+        let nnz = self.nnz() + self.rows() * v_nnz.len();
+        let mut indptr = Vec::with_capacity(self.outer_dims() + 1);
+        let mut indices = Vec::with_capacity(nnz);
+        let mut data = Vec::with_capacity(nnz);
+        indptr.push(Iptr::zero());
+        for (outer_ind, vec) in self.outer_iterator().enumerate() {
+            match self.storage {
+                CSR => {
+                    // merge the sorted columns of the row and of v
+                    let mut v_iter = v_nnz.iter().peekable();
+                    for (col, &x) in vec.iter() {
+                        while let Some(&&v_col) = v_iter.peek() {
+                            if v_col >= col {
+                                break;
+                            }
+                            push(&mut indices, &mut data, v_col, v[v_col]);
+                            v_iter.next();
+                        }
+                        if v_iter.peek() == Some(&&col) {
+                            v_iter.next();
+                        }
+                        push(&mut indices, &mut data, col, x + v[col]);
+                    }
+                    for &v_col in v_iter {
+                        push(&mut indices, &mut data, v_col, v[v_col]);
+                    }
+                }
+                CSC => {
+                    let v_col = v[outer_ind];
+                    if v_col.is_zero() {
+                        for (row, &x) in vec.iter() {
+                            push(&mut indices, &mut data, row, x);
+                        }
+                    } else {
+                        let mut dense = vec![v_col; self.rows()];
+                        for (row, &x) in vec.iter() {
+                            dense[row] = dense[row] + x;
+                        }
+                        for (row, &x) in dense.iter().enumerate() {
+                            push(&mut indices, &mut data, row, x);
+                        }
+                    }
+                }
+            }
+            indptr.push(Iptr::from_usize(indices.len()));
         }
-    */
+        CsMatI::new_trusted(self.storage, self.shape(), indptr, indices, data)
+    }
 
-    /// Copy-convert a csmat into the oppposite storage.
+    /// Compute the product of the transpose of this matrix with a dense
+    /// vector, `res = A^T x`, without building the transpose.
     ///
-    /// Can be used to implement CSC <-> CSR conversions, or to implement
-    /// same-storage (copy) transposition.
+    /// For a CSR matrix, each row scatters its contribution into `res`,
+    /// while for a CSC matrix each column is gathered into one element
+    /// of `res`. The previous content of `res` is overwritten.
     ///
     /// # Panics
     ///
-    /// Panics if indptr contains non-zero values
-    ///
-    /// Panics if the output slices don't match the input matrices'
-    /// corresponding slices.
-    pub fn convert_mat_storage<N: Clone, I: SpIndex, Iptr: SpIndex>(
-        mat: CsMatViewI<N, I, Iptr>,
-        indptr: &mut [Iptr],
-        indices: &mut [I],
-        data: &mut [N],
-    ) {
-        assert_eq!(indptr.len(), mat.inner_dims() + 1);
-        assert_eq!(indices.len(), mat.indices().len());
-        assert_eq!(data.len(), mat.data().len());
-
-        assert!(indptr.iter().all(|x| x.is_zero()));
-
-        for vec in mat.outer_iterator() {
-            for (inner_dim, _) in vec.iter() {
-                indptr[inner_dim] += Iptr::one();
-            }
-        }
-
-        let mut cumsum = Iptr::zero();
-        for iptr in indptr.iter_mut() {
-            let tmp = *iptr;
-            *iptr = cumsum;
-            cumsum += tmp;
+    /// - if `x.len() != self.rows()`
+    /// - if `res.len() != self.cols()`
+    pub fn mul_transpose_vec(&self, x: &[N], res: &mut [N])
+    where
+        N: Num + Copy,
+    {
+        assert_eq!(x.len(), self.rows(), "Dimension mismatch");
+        assert_eq!(res.len(), self.cols(), "Dimension mismatch");
+        for r in res.iter_mut() {
+            *r = N::zero();
         }
-        if let Some(last_iptr) = indptr.last() {
-            assert_eq!(last_iptr.index(), mat.nnz());
+        let mat_t = self.transpose_view();
+        match mat_t.storage() {
+            CSR => prod::mul_acc_mat_vec_csr(mat_t, x, res),
+            CSC => prod::mul_acc_mat_vec_csc(mat_t, x, res),
         }
+    }
 
-        for (outer_dim, vec) in mat.outer_iterator().enumerate() {
-            for (inner_dim, val) in vec.iter() {
-                let dest = indptr[inner_dim].index();
-                data[dest] = val.clone();
-                indices[dest] = I::from_usize_unchecked(outer_dim);
-                indptr[inner_dim] += Iptr::one();
-            }
+    /// Get a view into the i-th outer dimension (eg i-th row for a CSR matrix)
+    ///
+    /// The view borrows the matrix's storage, so this is O(1) and does not
+    /// copy any data. Returns `None` if `i >= self.outer_dims()`.
+    pub fn outer_view(&self, i: usize) -> Option<CsVecViewI<N, I>> {
+        if i >= self.outer_dims() {
+            return None;
         }
+        let start = self.indptr[i].index_unchecked();
+        let stop = self.indptr[i + 1].index_unchecked();
+        // CsMat invariants imply CsVec invariants
+        Some(CsVecBase {
+            dim: self.inner_dims(),
+            indices: &self.indices[start..stop],
+            data: &self.data[start..stop],
+        })
+    }
 
-        let mut last = Iptr::zero();
-        for iptr in indptr.iter_mut() {
-            swap(iptr, &mut last);
+    /// Iteration on outer blocks of size block_size
+    pub fn outer_block_iter(
+        &self,
+        block_size: usize,
+    ) -> ChunkOuterBlocks<N, I, Iptr> {
+        let m = CsMatBase {
+            storage: self.storage,
+            nrows: self.rows(),
+            ncols: self.cols(),
+            indptr: &self.indptr[..],
+            indices: &self.indices[..],
+            data: &self.data[..],
+        };
+        ChunkOuterBlocks {
+            mat: m,
+            dims_in_bloc: block_size,
+            bloc_count: 0,
         }
     }
-}
 
-impl<'a, N: 'a, I: 'a + SpIndex, Iptr: 'a + SpIndex>
-    CsMatBase<N, I, Vec<Iptr>, &'a [I], &'a [N], Iptr>
-{
-    /// Create a borrowed row or column CsMat matrix from raw data,
-    /// without checking their validity
+    /// Return a new sparse matrix with the same sparsity pattern, with all non-zero values mapped by the function `f`.
     ///
-    /// # Safety
-    /// This is unsafe because algorithms are free to assume
-    /// that properties guaranteed by check_compressed_structure are enforced.
-    /// For instance, non out-of-bounds indices can be relied upon to
-    /// perform unchecked slice access.
-    pub unsafe fn new_vecview_raw(
-        storage: CompressedStorage,
-        nrows: usize,
-        ncols: usize,
-        indptr: *const Iptr,
-        indices: *const I,
-        data: *const N,
-    ) -> CsMatVecView_<'a, N, I, Iptr> {
-        let indptr = slice::from_raw_parts(indptr, 2);
-        let nnz = indptr[1].index_unchecked();
-        CsMatVecView_ {
-            storage,
-            nrows,
-            ncols,
-            indptr: Array2 {
-                data: [indptr[0], indptr[1]],
-            },
-            indices: slice::from_raw_parts(indices, nnz),
-            data: slice::from_raw_parts(data, nnz),
-        }
-    }
-}
-
-impl<'a, 'b, N, I, Iptr, IpStorage, IStorage, DStorage, IpS2, IS2, DS2>
-    Add<&'b CsMatBase<N, I, IpS2, IS2, DS2, Iptr>>
-    for &'a CsMatBase<N, I, IpStorage, IStorage, DStorage, Iptr>
-where
-    N: 'a + Copy + Num + Default,
-    I: 'a + SpIndex,
-    Iptr: 'a + SpIndex,
-    IpStorage: 'a + Deref<Target = [Iptr]>,
-    IStorage: 'a + Deref<Target = [I]>,
-    DStorage: 'a + Deref<Target = [N]>,
-    IpS2: 'a + Deref<Target = [Iptr]>,
-    IS2: 'a + Deref<Target = [I]>,
-    DS2: 'a + Deref<Target = [N]>,
-{
-    type Output = CsMatI<N, I, Iptr>;
+    /// The function is only applied to the stored values: structural
+    /// zeros are left untouched, even if `f(0)` is not zero. Conversely,
+    /// stored values mapped to zero are kept as explicit zeros.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// let mat = CsMat::new((2, 2), vec![0, 1, 2], vec![1, 0], vec![2., -3.]);
+    /// let squared = mat.map(|&x| x * x);
+    /// assert_eq!(squared, CsMat::new((2, 2), vec![0, 1, 2], vec![1, 0],
+    ///                                vec![4., 9.]));
+    /// // the values can be converted to another type
+    /// let positive: CsMat<bool> = mat.map(|&x| x > 0.);
+    /// assert_eq!(positive.data(), &[true, false]);
+    /// ```
+    pub fn map<F, N2>(&self, f: F) -> CsMatI<N2, I, Iptr>
+    where
+        F: FnMut(&N) -> N2,
+    {
+        let data: Vec<N2> = self.data.iter().map(f).collect();
 
-    fn add(
-        self,
-        rhs: &'b CsMatBase<N, I, IpS2, IS2, DS2, Iptr>,
-    ) -> CsMatI<N, I, Iptr> {
-        if self.storage() != rhs.view().storage() {
-            return binop::add_mat_same_storage(
-                self,
-                &rhs.view().to_other_storage(),
-            );
+        CsMatI {
+            storage: self.storage,
+            nrows: self.nrows,
+            ncols: self.ncols,
+            indptr: self.indptr.to_vec(),
+            indices: self.indices.to_vec(),
+            data,
         }
-        binop::add_mat_same_storage(self, rhs)
     }
-}
-
-impl<'a, 'b, N, I, Iptr, IpStorage, IStorage, DStorage, Mat> Sub<&'b Mat>
-    for &'a CsMatBase<N, I, IpStorage, IStorage, DStorage, Iptr>
-where
-    N: 'a + Copy + Num + Default,
-    I: 'a + SpIndex,
-    Iptr: 'a + SpIndex,
-    IpStorage: 'a + Deref<Target = [Iptr]>,
-    IStorage: 'a + Deref<Target = [I]>,
-    DStorage: 'a + Deref<Target = [N]>,
-    Mat: SpMatView<N, I, Iptr>,
-{
-    type Output = CsMatI<N, I, Iptr>;
 
-    fn sub(self, rhs: &'b Mat) -> CsMatI<N, I, Iptr> {
-        if self.storage() != rhs.view().storage() {
-            return binop::sub_mat_same_storage(
-                self,
-                &rhs.view().to_other_storage(),
-            );
+    /// Return a new sparse matrix where each stored value is replaced by
+    /// `f(row, col, &value)`.
+    ///
+    /// As for `map`, the structural zeros are left untouched. However, the
+    /// values mapped to zero are dropped from the result to keep it sparse.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// let mat = CsMat::new((2, 2), vec![0, 2, 3], vec![0, 1, 1],
+    ///                      vec![1., 2., 3.]);
+    /// // weight the values by their distance to the diagonal
+    /// let weighted = mat.map_with_coords(|row, col, &x| {
+    ///     x * (col as f64 - row as f64).abs()
+    /// });
+    /// assert_eq!(weighted, CsMat::new((2, 2), vec![0, 1, 1], vec![1],
+    ///                                 vec![2.]));
+    /// ```
+    pub fn map_with_coords<F>(&self, mut f: F) -> CsMatI<N, I, Iptr>
+    where
+        N: Zero,
+        F: FnMut(usize, usize, &N) -> N,
+    {
+        let mut indptr = Vec::with_capacity(self.outer_dims() + 1);
+        let mut indices = Vec::with_capacity(self.nnz());
+        let mut data = Vec::with_capacity(self.nnz());
+        indptr.push(Iptr::zero());
+        for (outer_ind, vec) in self.outer_iterator().enumerate() {
+            for (inner_ind, val) in vec.iter() {
+                let (row, col) = match self.storage {
+                    CSR => (outer_ind, inner_ind),
+                    CSC => (inner_ind, outer_ind),
+                };
+                let val = f(row, col, val);
+                if !val.is_zero() {
+                    indices.push(I::from_usize(inner_ind));
+                    data.push(val);
+                }
+            }
+            indptr.push(Iptr::from_usize(indices.len()));
+        }
+        CsMatI {
+            storage: self.storage,
+            nrows: self.nrows,
+            ncols: self.ncols,
+            indptr,
+            indices,
+            data,
         }
-        binop::sub_mat_same_storage(self, rhs)
     }
-}
-
-macro_rules! sparse_scalar_mul {
-    ($scalar: ident) => {
-        impl<'a, I, Iptr, IpStorage, IStorage, DStorage> Mul<$scalar>
-            for &'a CsMatBase<$scalar, I, IpStorage, IStorage, DStorage, Iptr>
-        where
-            I: 'a + SpIndex,
-            Iptr: 'a + SpIndex,
-            IpStorage: 'a + Deref<Target = [Iptr]>,
-            IStorage: 'a + Deref<Target = [I]>,
-            DStorage: 'a + Deref<Target = [$scalar]>,
-        {
-            type Output = CsMatI<$scalar, I, Iptr>;
 
-            fn mul(self, rhs: $scalar) -> CsMatI<$scalar, I, Iptr> {
-                binop::scalar_mul_mat(self, rhs)
-            }
+    /// Return an empty matrix, without any non-zero element, with the same
+    /// shape and storage as this matrix.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// let mat = CsMat::new_csc((2, 3), vec![0, 1, 1, 2], vec![1, 0],
+    ///                          vec![1., 2.]);
+    /// let empty = mat.empty_like();
+    /// assert_eq!(empty.shape(), (2, 3));
+    /// assert!(empty.is_csc());
+    /// assert_eq!(empty.nnz(), 0);
+    /// ```
+    pub fn empty_like(&self) -> CsMatI<N, I, Iptr> {
+        CsMatI {
+            storage: self.storage,
+            nrows: self.nrows,
+            ncols: self.ncols,
+            indptr: vec![Iptr::zero(); self.outer_dims() + 1],
+            indices: Vec::new(),
+            data: Vec::new(),
         }
-    };
-}
-
-sparse_scalar_mul!(u32);
-sparse_scalar_mul!(i32);
-sparse_scalar_mul!(u64);
-sparse_scalar_mul!(i64);
-sparse_scalar_mul!(isize);
-sparse_scalar_mul!(usize);
-sparse_scalar_mul!(f32);
-sparse_scalar_mul!(f64);
-
-impl<'a, I, Iptr, IpStorage, IStorage, DStorage, T> std::ops::MulAssign<T>
-    for CsMatBase<T, I, IpStorage, IStorage, DStorage, Iptr>
-where
-    I: 'a + SpIndex,
-    Iptr: 'a + SpIndex,
-    IpStorage: 'a + Deref<Target = [Iptr]>,
-    IStorage: 'a + Deref<Target = [I]>,
-    DStorage: 'a + DerefMut<Target = [T]>,
-    T: std::ops::MulAssign<T> + Clone,
-{
-    fn mul_assign(&mut self, rhs: T) {
-        self.data_mut()
-            .iter_mut()
-            .for_each(|v| v.mul_assign(rhs.clone()));
     }
-}
 
-impl<'a, I, Iptr, IpStorage, IStorage, DStorage, T> std::ops::DivAssign<T>
-    for CsMatBase<T, I, IpStorage, IStorage, DStorage, Iptr>
-where
-    I: 'a + SpIndex,
-    Iptr: 'a + SpIndex,
-    IpStorage: 'a + Deref<Target = [Iptr]>,
-    IStorage: 'a + Deref<Target = [I]>,
-    DStorage: 'a + DerefMut<Target = [T]>,
-    T: std::ops::DivAssign<T> + Clone,
-{
-    fn div_assign(&mut self, rhs: T) {
-        self.data_mut()
-            .iter_mut()
-            .for_each(|v| v.div_assign(rhs.clone()));
+    /// Return a new sparse matrix with the same sparsity pattern, where
+    /// every stored value is replaced by `fill`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// let mat = CsMat::new((2, 2), vec![0, 1, 2], vec![1, 0], vec![2., -3.]);
+    /// let pattern: CsMat<u8> = mat.with_same_structure(1);
+    /// assert_eq!(pattern, CsMat::new((2, 2), vec![0, 1, 2], vec![1, 0],
+    ///                                vec![1, 1]));
+    /// ```
+    pub fn with_same_structure<N2>(&self, fill: N2) -> CsMatI<N2, I, Iptr>
+    where
+        N2: Clone,
+    {
+        self.map(|_| fill.clone())
     }
-}
 
-impl<'a, 'b, N, I, Iptr, IpS1, IS1, DS1, IpS2, IS2, DS2>
-    Mul<&'b CsMatBase<N, I, IpS2, IS2, DS2, Iptr>>
-    for &'a CsMatBase<N, I, IpS1, IS1, DS1, Iptr>
-where
-    N: 'a + Copy + Num + Default + std::ops::AddAssign + Send + Sync,
-    I: 'a + SpIndex,
-    Iptr: 'a + SpIndex,
-    IpS1: 'a + Deref<Target = [Iptr]>,
-    IS1: 'a + Deref<Target = [I]>,
-    DS1: 'a + Deref<Target = [N]>,
-    IpS2: 'b + Deref<Target = [Iptr]>,
-    IS2: 'b + Deref<Target = [I]>,
-    DS2: 'b + Deref<Target = [N]>,
-{
-    type Output = CsMatI<N, I, Iptr>;
+    /// Return a new sparse matrix with the same sparsity pattern, holding
+    /// the absolute values of the stored values.
+    pub fn abs(&self) -> CsMatI<N, I, Iptr>
+    where
+        N: Signed,
+    {
+        self.map(|x| x.abs())
+    }
 
-    fn mul(
-        self,
-        rhs: &'b CsMatBase<N, I, IpS2, IS2, DS2, Iptr>,
-    ) -> CsMatI<N, I, Iptr> {
-        match (self.storage(), rhs.storage()) {
-            (CSR, CSR) => smmp::mul_csr_csr(self.view(), rhs.view()),
-            (CSR, CSC) => {
-                let rhs_csr = rhs.to_other_storage();
-                smmp::mul_csr_csr(self.view(), rhs_csr.view())
-            }
-            (CSC, CSR) => {
-                let rhs_csc = rhs.to_other_storage();
-                smmp::mul_csr_csr(
-                    rhs_csc.transpose_view(),
-                    self.transpose_view(),
-                )
-                .transpose_into()
-            }
-            (CSC, CSC) => {
-                smmp::mul_csr_csr(rhs.transpose_view(), self.transpose_view())
-                    .transpose_into()
+    /// Return a new sparse matrix holding only the non-zero entries for
+    /// which the predicate `keep(row, col, &value)` returns `true`.
+    ///
+    /// The storage order is preserved, and the compressed structure is
+    /// compacted: discarded entries are not stored as explicit zeros.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// let mat = CsMat::new((2, 2), vec![0, 2, 3], vec![0, 1, 1],
+    ///                      vec![1., 1e-12, -2.]);
+    /// // drop the tiny values
+    /// let pruned = mat.prune(|_, _, &x: &f64| x.abs() > 1e-10);
+    /// assert_eq!(pruned.nnz(), 2);
+    /// assert_eq!(pruned.get(0, 1), None);
+    /// // keep the lower triangular part
+    /// let lower = mat.prune(|row, col, _| col <= row);
+    /// assert_eq!(lower.nnz(), 2);
+    /// ```
+    pub fn prune<F>(&self, mut keep: F) -> CsMatI<N, I, Iptr>
+    where
+        N: Clone,
+        F: FnMut(usize, usize, &N) -> bool,
+    {
+        let mut indptr = Vec::with_capacity(self.outer_dims() + 1);
+        let mut indices = Vec::new();
+        let mut data = Vec::new();
+        indptr.push(Iptr::zero());
+        for (outer_ind, vec) in self.outer_iterator().enumerate() {
+            for (inner_ind, val) in vec.iter() {
+                let (row, col) = match self.storage {
+                    CSR => (outer_ind, inner_ind),
+                    CSC => (inner_ind, outer_ind),
+                };
+                if keep(row, col, val) {
+                    indices.push(I::from_usize(inner_ind));
+                    data.push(val.clone());
+                }
             }
+            indptr.push(Iptr::from_usize(indices.len()));
+        }
+        CsMatI {
+            storage: self.storage,
+            nrows: self.nrows,
+            ncols: self.ncols,
+            indptr,
+            indices,
+            data,
         }
     }
-}
 
-impl<'a, 'b, N, I, Iptr, IpS, IS, DS, DS2> Add<&'b ArrayBase<DS2, Ix2>>
-    for &'a CsMatBase<N, I, IpS, IS, DS, Iptr>
-where
-    N: 'a + Copy + Num + Default,
-    I: 'a + SpIndex,
-    Iptr: 'a + SpIndex,
-    IpS: 'a + Deref<Target = [Iptr]>,
-    IS: 'a + Deref<Target = [I]>,
-    DS: 'a + Deref<Target = [N]>,
-    DS2: 'b + ndarray::Data<Elem = N>,
-{
-    type Output = Array<N, Ix2>;
+    /// Return a new sparse matrix holding only the non-zero entries whose
+    /// absolute value is at least `threshold`.
+    ///
+    /// This is a shorthand for `prune` with a threshold on the magnitude,
+    /// typically used to drop the tiny values created by cancellations.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// let mat = CsMat::new((2, 2), vec![0, 2, 3], vec![0, 1, 1],
+    ///                      vec![1., -1e-12, -2.]);
+    /// let filtered = mat.filter_abs(1e-10);
+    /// assert_eq!(filtered, CsMat::new((2, 2), vec![0, 1, 2], vec![0, 1],
+    ///                                 vec![1., -2.]));
+    /// ```
+    pub fn filter_abs(&self, threshold: N) -> CsMatI<N, I, Iptr>
+    where
+        N: Signed + PartialOrd + Clone,
+    {
+        self.prune(|_, _, val| val.abs() >= threshold)
+    }
 
-    fn add(self, rhs: &'b ArrayBase<DS2, Ix2>) -> Array<N, Ix2> {
-        match (self.storage(), rhs.is_standard_layout()) {
-            (CSR, true) => binop::add_dense_mat_same_ordering(
-                self,
-                rhs,
-                N::one(),
-                N::one(),
-            ),
-            (CSR, false) => {
-                let lhs = self.to_other_storage();
-                binop::add_dense_mat_same_ordering(
-                    &lhs,
-                    rhs,
-                    N::one(),
-                    N::one(),
-                )
-            }
-            (CSC, true) => {
-                let lhs = self.to_other_storage();
-                binop::add_dense_mat_same_ordering(
-                    &lhs,
-                    rhs,
-                    N::one(),
-                    N::one(),
-                )
+    /// Return a new sparse matrix where the stored values are clamped into
+    /// the range `[lo, hi]`.
+    ///
+    /// Values clamped to zero are dropped from the result to keep it sparse.
+    /// Only the stored values are clamped: the structural zeros are left
+    /// untouched, even if zero is not in the range.
+    ///
+    /// # Panics
+    ///
+    /// If `lo > hi`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// let mat = CsMat::new((2, 2), vec![0, 2, 3], vec![0, 1, 1],
+    ///                      vec![-3., 0.5, 2.]);
+    /// let clamped = mat.clamp(0., 1.);
+    /// assert_eq!(clamped, CsMat::new((2, 2), vec![0, 1, 2], vec![1, 1],
+    ///                                vec![0.5, 1.]));
+    /// ```
+    pub fn clamp(&self, lo: N, hi: N) -> CsMatI<N, I, Iptr>
+    where
+        N: Num + PartialOrd + Clone,
+    {
+        assert!(lo <= hi, "Empty clamping range");
+        let mut indptr = Vec::with_capacity(self.outer_dims() + 1);
+        let mut indices = Vec::new();
+        let mut data = Vec::new();
+        indptr.push(Iptr::zero());
+        for vec in self.outer_iterator() {
+            for (inner_ind, val) in vec.iter() {
+                let val = if *val < lo {
+                    lo.clone()
+                } else if *val > hi {
+                    hi.clone()
+                } else {
+                    val.clone()
+                };
+                if !val.is_zero() {
+                    indices.push(I::from_usize(inner_ind));
+                    data.push(val);
+                }
             }
-            (CSC, false) => binop::add_dense_mat_same_ordering(
-                self,
-                rhs,
-                N::one(),
-                N::one(),
-            ),
+            indptr.push(Iptr::from_usize(indices.len()));
+        }
+        CsMatI {
+            storage: self.storage,
+            nrows: self.nrows,
+            ncols: self.ncols,
+            indptr,
+            indices,
+            data,
         }
     }
-}
 
-impl<'a, 'b, N, I, Iptr, IpS, IS, DS, DS2> Mul<&'b ArrayBase<DS2, Ix2>>
-    for &'a CsMatBase<N, I, IpS, IS, DS, Iptr>
-where
-    N: 'a + Copy + Num + Default,
-    I: 'a + SpIndex,
-    Iptr: 'a + SpIndex,
-    IpS: 'a + Deref<Target = [Iptr]>,
-    IS: 'a + Deref<Target = [I]>,
-    DS: 'a + Deref<Target = [N]>,
-    DS2: 'b + ndarray::Data<Elem = N>,
-{
-    type Output = Array<N, Ix2>;
+    /// Access an element given its outer_ind and inner_ind.
+    /// Will return None if there is no non-zero element at this location.
+    ///
+    /// This access is logarithmic in the number of non-zeros
+    /// in the corresponding outer slice. It is therefore advisable not to rely
+    /// on this for algorithms, and prefer outer_iterator() which accesses
+    /// elements in storage order.
+    pub fn get_outer_inner(
+        &self,
+        outer_ind: usize,
+        inner_ind: usize,
+    ) -> Option<&N> {
+        self.outer_view(outer_ind)
+            .and_then(|vec| vec.get_rbr(inner_ind))
+    }
 
-    fn mul(self, rhs: &'b ArrayBase<DS2, Ix2>) -> Array<N, Ix2> {
-        let rows = self.rows();
-        let cols = rhs.shape()[1];
-        // when the number of colums is small, it is more efficient
-        // to perform the product by iterating over the columns of
-        // the rhs, otherwise iterating by rows can take advantage of
-        // vectorized axpy.
-        match (self.storage(), cols >= 8) {
-            (CSR, true) => {
-                let mut res = Array::zeros((rows, cols));
-                prod::csr_mulacc_dense_rowmaj(
-                    self.view(),
-                    rhs.view(),
-                    res.view_mut(),
-                );
-                res
-            }
-            (CSR, false) => {
-                let mut res = Array::zeros((rows, cols).f());
-                prod::csr_mulacc_dense_colmaj(
-                    self.view(),
-                    rhs.view(),
-                    res.view_mut(),
-                );
-                res
-            }
-            (CSC, true) => {
-                let mut res = Array::zeros((rows, cols));
-                prod::csc_mulacc_dense_rowmaj(
-                    self.view(),
-                    rhs.view(),
-                    res.view_mut(),
-                );
-                res
-            }
-            (CSC, false) => {
-                let mut res = Array::zeros((rows, cols).f());
-                prod::csc_mulacc_dense_colmaj(
-                    self.view(),
-                    rhs.view(),
-                    res.view_mut(),
-                );
-                res
-            }
+    /// Find the non-zero index of the element specified by row and col
+    ///
+    /// Searching this index is logarithmic in the number of non-zeros
+    /// in the corresponding outer slice.
+    /// Once it is available, the NnzIndex enables retrieving the data with
+    /// O(1) complexity.
+    pub fn nnz_index(&self, row: usize, col: usize) -> Option<NnzIndex> {
+        match self.storage() {
+            CSR => self.nnz_index_outer_inner(row, col),
+            CSC => self.nnz_index_outer_inner(col, row),
         }
     }
-}
 
-impl<'a, 'b, N, I, IpS, IS, DS, DS2> Dot<CsMatBase<N, I, IpS, IS, DS>>
-    for ArrayBase<DS2, Ix2>
-where
-    N: 'a + Copy + Num + Default + std::fmt::Debug,
-    I: 'a + SpIndex,
-    IpS: 'a + Deref<Target = [I]>,
-    IS: 'a + Deref<Target = [I]>,
-    DS: 'a + Deref<Target = [N]>,
-    DS2: 'b + ndarray::Data<Elem = N>,
-{
-    type Output = Array<N, Ix2>;
+    /// Find the non-zero index of the element specified by outer_ind and
+    /// inner_ind.
+    ///
+    /// Searching this index is logarithmic in the number of non-zeros
+    /// in the corresponding outer slice.
+    pub fn nnz_index_outer_inner(
+        &self,
+        outer_ind: usize,
+        inner_ind: usize,
+    ) -> Option<NnzIndex> {
+        if outer_ind >= self.outer_dims() {
+            return None;
+        }
+        let offset = self.indptr[outer_ind].index_unchecked();
+        self.outer_view(outer_ind)
+            .and_then(|vec| vec.nnz_index(inner_ind))
+            .map(|vec::NnzIndex(ind)| NnzIndex(ind + offset))
+    }
 
-    fn dot(&self, rhs: &CsMatBase<N, I, IpS, IS, DS>) -> Array<N, Ix2> {
-        let rhs_t = rhs.transpose_view();
-        let lhs_t = self.t();
+    /// Check the structure of CsMat components
+    /// This will ensure that:
+    /// * indptr is of length outer_dim() + 1
+    /// * indptr starts with 0 and is sorted
+    /// * indices and data have the same length, nnz == indptr\[outer_dims()\]
+    /// * indptr values do not exceed usize::MAX / 2, as that would mean
+    ///   indices and indptr would take more space than the addressable memory
+    /// * indices is sorted for each outer slice
+    /// * indices are lower than inner_dims()
+    ///
+    /// See `check_structure` for a more detailed error report.
+    pub fn check_compressed_structure(&self) -> Result<(), SprsError> {
+        self.check_structure().map_err(SprsError::from)
+    }
 
-        let rows = rhs_t.rows();
-        let cols = lhs_t.ncols();
-        // when the number of colums is small, it is more efficient
-        // to perform the product by iterating over the columns of
-        // the rhs, otherwise iterating by rows can take advantage of
-        // vectorized axpy.
-        let rres = match (rhs_t.storage(), cols >= 8) {
-            (CSR, true) => {
-                let mut res = Array::zeros((rows, cols));
-                prod::csr_mulacc_dense_rowmaj(rhs_t, lhs_t, res.view_mut());
-                res.reversed_axes()
-            }
-            (CSR, false) => {
-                let mut res = Array::zeros((rows, cols).f());
-                prod::csr_mulacc_dense_colmaj(rhs_t, lhs_t, res.view_mut());
-                res.reversed_axes()
-            }
-            (CSC, true) => {
-                let mut res = Array::zeros((rows, cols));
-                prod::csc_mulacc_dense_rowmaj(rhs_t, lhs_t, res.view_mut());
-                res.reversed_axes()
+    /// Check the structure of CsMat components, reporting which property
+    /// is violated on failure.
+    ///
+    /// The checked properties are the same as for
+    /// `check_compressed_structure`. This is mostly useful to diagnose
+    /// a matrix obtained by unsafe construction or from an external source.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::errors::StructureError;
+    /// use sprs::CsMatView;
+    /// // the index 3 is out of bounds in the second row
+    /// let indptr = [0, 1, 2];
+    /// let indices = [0, 3];
+    /// let data = [1., 2.];
+    /// let mat = unsafe {
+    ///     CsMatView::new_view_raw(
+    ///         sprs::CSR,
+    ///         (2, 2),
+    ///         indptr.as_ptr(),
+    ///         indices.as_ptr(),
+    ///         data.as_ptr(),
+    ///     )
+    /// };
+    /// assert_eq!(
+    ///     mat.check_structure(),
+    ///     Err(StructureError::OutOfBoundsIndex { outer: 1, index: 3 })
+    /// );
+    /// ```
+    pub fn check_structure(&self) -> Result<(), StructureError> {
+        // Make sure both indptr and indices can be converted to usize
+        if self.indptr.iter().any(|i| i.try_index().is_none())
+            || self.indices.iter().any(|i| i.try_index().is_none())
+        {
+            return Err(StructureError::IndexConversion);
+        }
+
+        let outer = self.outer_dims();
+
+        if self.indptr.len() != outer + 1 {
+            return Err(StructureError::IndptrLength {
+                expected: outer + 1,
+                found: self.indptr.len(),
+            });
+        }
+        if self.indptr[0].index_unchecked() != 0 {
+            return Err(StructureError::IndptrStart);
+        }
+        if !self
+            .indptr
+            .deref()
+            .windows(2)
+            .all(|x| x[0].index_unchecked() <= x[1].index_unchecked())
+        {
+            return Err(StructureError::UnsortedIndptr);
+        }
+        if self.nnz() > usize::max_value() / 2 {
+            // We do not allow indptr values to be larger than half
+            // the maximum value of an usize, as that would clearly exhaust
+            // all available memory
+            // This means we could have an isize, but in practice it's
+            // easier to work with usize for indexing.
+            return Err(StructureError::IndptrOverflow);
+        }
+        if self.indices.len() != self.data.len() {
+            return Err(StructureError::IndicesDataLength {
+                indices: self.indices.len(),
+                data: self.data.len(),
+            });
+        }
+        if self.nnz() != self.indices.len() {
+            return Err(StructureError::IndptrEnd {
+                nnz: self.nnz(),
+                indices: self.indices.len(),
+            });
+        }
+
+        // check that the indices are sorted and in bounds for each row
+        let inner = self.inner_dims();
+        for (outer_ind, range) in self.indptr.windows(2).enumerate() {
+            let start = range[0].index_unchecked();
+            let stop = range[1].index_unchecked();
+            let indices = &self.indices[start..stop];
+            if !indices.windows(2).all(|x| x[0] < x[1]) {
+                return Err(StructureError::UnsortedIndices {
+                    outer: outer_ind,
+                });
             }
-            (CSC, false) => {
-                let mut res = Array::zeros((rows, cols).f());
-                prod::csc_mulacc_dense_colmaj(rhs_t, lhs_t, res.view_mut());
-                res.reversed_axes()
+            if let Some(&index) = indices.last() {
+                if index.index_unchecked() >= inner {
+                    return Err(StructureError::OutOfBoundsIndex {
+                        outer: outer_ind,
+                        index: index.index_unchecked(),
+                    });
+                }
             }
-        };
+        }
 
-        assert_eq!(self.shape()[0], rres.shape()[0]);
-        assert_eq!(rhs.cols(), rres.shape()[1]);
-        rres
+        Ok(())
     }
-}
 
-impl<'a, 'b, N, I, Iptr, IpS, IS, DS, DS2> Dot<ArrayBase<DS2, Ix2>>
-    for CsMatBase<N, I, IpS, IS, DS, Iptr>
-where
-    N: 'a + Copy + Num + Default,
-    I: 'a + SpIndex,
-    Iptr: 'a + SpIndex,
-    IpS: 'a + Deref<Target = [Iptr]>,
-    IS: 'a + Deref<Target = [I]>,
-    DS: 'a + Deref<Target = [N]>,
-    DS2: 'b + ndarray::Data<Elem = N>,
-{
-    type Output = Array<N, Ix2>;
+    /// Check whether the matrix is exactly symmetric, see
+    /// `sprs::is_symmetric`.
+    pub fn is_symmetric(&self) -> bool
+    where
+        N: PartialEq,
+    {
+        crate::sparse::symmetric::is_symmetric(self)
+    }
 
-    fn dot(&self, rhs: &ArrayBase<DS2, Ix2>) -> Array<N, Ix2> {
-        Mul::mul(self, rhs)
+    /// Check whether the matrix is symmetric up to an absolute tolerance,
+    /// see `sprs::is_symmetric_approx`.
+    pub fn is_symmetric_approx(&self, tol: N) -> bool
+    where
+        N: Float,
+    {
+        crate::sparse::symmetric::is_symmetric_approx(self, tol)
+    }
+
+    /// Compute the symmetric part of the matrix, `(A + A^T) / 2`, with the
+    /// same storage as `A`.
+    ///
+    /// This is useful to build an exactly symmetric operator from a nearly
+    /// symmetric assembly.
+    ///
+    /// # Panics
+    ///
+    /// If the matrix is not square.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// // | 1 2 |
+    /// // | 0 3 |
+    /// let mat = CsMat::new((2, 2), vec![0, 2, 3], vec![0, 1, 1],
+    ///                      vec![1., 2., 3.]);
+    /// let sym = mat.symmetrize();
+    /// assert!(sym.is_symmetric());
+    /// assert_eq!(sym.get(1, 0), Some(&1.));
+    /// ```
+    pub fn symmetrize(&self) -> CsMatI<N, I, Iptr>
+    where
+        N: Num + Copy + Default,
+    {
+        assert_eq!(self.rows(), self.cols(), "Non square matrix");
+        let transposed = self.transpose_view().to_other_storage();
+        let two = N::one() + N::one();
+        binop::csmat_binop(self.view(), transposed.view(), |&x, &y| {
+            (x + y) / two
+        })
+    }
+
+    /// Get an iterator that yields the non-zero locations and values stored in
+    /// this matrix, in the fastest iteration order.
+    pub fn iter(&self) -> CsIter<N, I, Iptr> {
+        CsIter {
+            storage: self.storage,
+            cur_outer: I::zero(),
+            indptr: &self.indptr[..],
+            inner_iter: self.indices.iter().zip(self.data.iter()).enumerate(),
+        }
+    }
+
+    /// Get an iterator that yields the non-zero values stored in this
+    /// matrix along with their `(row, col)` location, as triplets
+    /// `(row, col, &value)`, in the fastest iteration order.
+    ///
+    /// The locations are correct whatever the storage, which makes the
+    /// output suitable to build a matrix from triplets.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// let mat = CsMat::new_csc((2, 2), vec![0, 1, 2], vec![1, 0],
+    ///                          vec![1., 2.]);
+    /// let triplets: Vec<_> = mat.iter_nnz().collect();
+    /// assert_eq!(triplets, vec![(1, 0, &1.), (0, 1, &2.)]);
+    /// ```
+    pub fn iter_nnz(&self) -> impl Iterator<Item = (usize, usize, &N)> + '_ {
+        let storage = self.storage;
+        self.indptr
+            .windows(2)
+            .enumerate()
+            .flat_map(move |(outer, ptrs)| {
+                let start = ptrs[0].index_unchecked();
+                let stop = ptrs[1].index_unchecked();
+                self.indices[start..stop]
+                    .iter()
+                    .zip(self.data[start..stop].iter())
+                    .map(move |(inner, val)| {
+                        let inner = inner.index_unchecked();
+                        match storage {
+                            CSR => (outer, inner, val),
+                            CSC => (inner, outer, val),
+                        }
+                    })
+            })
+    }
+
+    /// Get an iterator over the neighbors of a node of the graph whose
+    /// adjacency matrix is this matrix, yielding `(neighbor, &weight)` pairs
+    /// in increasing neighbor order for sorted indices.
+    ///
+    /// This is the outer view of `node`: for a CSR matrix, the neighbors
+    /// are the out-neighbors of `node`, ie the columns of the non-zeros of
+    /// its row, while for a CSC matrix they are its in-neighbors.
+    ///
+    /// # Panics
+    ///
+    /// If `node >= self.outer_dims()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// // the edges 0 -> 1, 0 -> 2 and 2 -> 1
+    /// let adj = CsMat::new((3, 3), vec![0, 2, 2, 3], vec![1, 2, 1],
+    ///                      vec![1., 2., 3.]);
+    /// let neighbors: Vec<_> = adj.neighbors(0).collect();
+    /// assert_eq!(neighbors, vec![(1, &1.), (2, &2.)]);
+    /// assert_eq!(adj.neighbors(1).count(), 0);
+    /// ```
+    pub fn neighbors(&self, node: usize) -> impl Iterator<Item = (usize, &N)> {
+        if node >= self.outer_dims() {
+            panic!("Out of bounds node {}", node);
+        }
+        let start = self.indptr[node].index_unchecked();
+        let stop = self.indptr[node + 1].index_unchecked();
+        self.indices[start..stop]
+            .iter()
+            .map(|inner| inner.index_unchecked())
+            .zip(self.data[start..stop].iter())
     }
 }
 
-impl<'a, 'b, N, I, Iptr, IpS, IS, DS, DS2> Mul<&'b ArrayBase<DS2, Ix1>>
-    for &'a CsMatBase<N, I, IpS, IS, DS, Iptr>
+/// # Methods to convert between storage orders
+impl<N, I, Iptr, IptrStorage, IndStorage, DataStorage>
+    CsMatBase<N, I, IptrStorage, IndStorage, DataStorage, Iptr>
 where
-    N: 'a + Copy + Num + Default,
-    I: 'a + SpIndex,
-    Iptr: 'a + SpIndex,
-    IpS: 'a + Deref<Target = [Iptr]>,
-    IS: 'a + Deref<Target = [I]>,
-    DS: 'a + Deref<Target = [N]>,
-    DS2: 'b + ndarray::Data<Elem = N>,
+    N: Default,
+    I: SpIndex,
+    Iptr: SpIndex,
+    IptrStorage: Deref<Target = [Iptr]>,
+    IndStorage: Deref<Target = [I]>,
+    DataStorage: Deref<Target = [N]>,
 {
-    type Output = Array<N, Ix1>;
+    /// Create a matrix mathematically equal to this one, but with the
+    /// opposed storage (a CSC matrix will be converted to CSR, and vice versa)
+    pub fn to_other_storage(&self) -> CsMatI<N, I, Iptr>
+    where
+        N: Clone,
+    {
+        let mut indptr = vec![Iptr::zero(); self.inner_dims() + 1];
+        let mut indices = vec![I::zero(); self.nnz()];
+        let mut data = vec![N::default(); self.nnz()];
+        raw::convert_mat_storage(
+            self.view(),
+            &mut indptr,
+            &mut indices,
+            &mut data,
+        );
+        CsMatI {
+            storage: self.storage().other_storage(),
+            nrows: self.nrows,
+            ncols: self.ncols,
+            indptr,
+            indices,
+            data,
+        }
+    }
 
-    fn mul(self, rhs: &'b ArrayBase<DS2, Ix1>) -> Array<N, Ix1> {
-        let rows = self.rows();
-        let cols = rhs.shape()[0];
-        let rhs_reshape = rhs.view().into_shape((cols, 1)).unwrap();
-        let mut res = Array::zeros(rows);
-        {
-            let res_reshape = res.view_mut().into_shape((rows, 1)).unwrap();
-            match self.storage() {
-                CSR => {
-                    prod::csr_mulacc_dense_colmaj(
-                        self.view(),
-                        rhs_reshape,
-                        res_reshape,
-                    );
-                }
-                CSC => {
-                    prod::csc_mulacc_dense_colmaj(
-                        self.view(),
-                        rhs_reshape,
-                        res_reshape,
-                    );
-                }
-            }
+    /// Create a new CSC matrix equivalent to this one.
+    /// A new matrix will be created even if this matrix was already CSC.
+    pub fn to_csc(&self) -> CsMatI<N, I, Iptr>
+    where
+        N: Clone,
+    {
+        match self.storage {
+            CSR => self.to_other_storage(),
+            CSC => self.to_owned(),
+        }
+    }
+
+    /// Create a new CSR matrix equivalent to this one.
+    /// A new matrix will be created even if this matrix was already CSR.
+    pub fn to_csr(&self) -> CsMatI<N, I, Iptr>
+    where
+        N: Clone,
+    {
+        match self.storage {
+            CSR => self.to_owned(),
+            CSC => self.to_other_storage(),
         }
-        res
     }
 }
 
-impl<'a, 'b, N, I, Iptr, IpS, IS, DS, DS2> Dot<ArrayBase<DS2, Ix1>>
-    for CsMatBase<N, I, IpS, IS, DS, Iptr>
+impl<N, I, Iptr> CsMatI<N, I, Iptr>
 where
-    N: 'a + Copy + Num + Default,
-    I: 'a + SpIndex,
-    Iptr: 'a + SpIndex,
-    IpS: 'a + Deref<Target = [Iptr]>,
-    IS: 'a + Deref<Target = [I]>,
-    DS: 'a + Deref<Target = [N]>,
-    DS2: 'b + ndarray::Data<Elem = N>,
+    N: Default,
+
+    I: SpIndex,
+    Iptr: SpIndex,
 {
-    type Output = Array<N, Ix1>;
+    /// Create a new CSC matrix equivalent to this one.
+    /// If this matrix is CSR, it is converted to CSC
+    /// If this matrix is CSC, it is returned by value
+    pub fn into_csc(self) -> CsMatI<N, I, Iptr>
+    where
+        N: Clone,
+    {
+        match self.storage {
+            CSR => self.to_other_storage(),
+            CSC => self,
+        }
+    }
+
+    /// Create a new CSR matrix equivalent to this one.
+    /// If this matrix is CSC, it is converted to CSR
+    /// If this matrix is CSR, it is returned by value
+    pub fn into_csr(self) -> CsMatI<N, I, Iptr>
+    where
+        N: Clone,
+    {
+        match self.storage {
+            CSR => self,
+            CSC => self.to_other_storage(),
+        }
+    }
+}
+
+/// # Methods for complex valued sparse matrices
+impl<T, I, Iptr, IptrStorage, IndStorage, DataStorage>
+    CsMatBase<Complex<T>, I, IptrStorage, IndStorage, DataStorage, Iptr>
+where
+    T: Clone + Num + Neg<Output = T>,
+    I: SpIndex,
+    Iptr: SpIndex,
+    IptrStorage: Deref<Target = [Iptr]>,
+    IndStorage: Deref<Target = [I]>,
+    DataStorage: Deref<Target = [Complex<T>]>,
+{
+    /// Compute the conjugate transpose (or hermitian transpose) of this
+    /// matrix.
+    ///
+    /// Like `transpose_view`, this swaps the storage order: the conjugate
+    /// transpose of a CSR matrix is a CSC matrix. The values have to be
+    /// conjugated, so the result is an owned matrix.
+    pub fn conj_transpose(&self) -> CsMatI<Complex<T>, I, Iptr> {
+        self.transpose_view().map(|x| x.conj())
+    }
+}
+
+/// # Methods for sparse matrices holding mutable access to their values.
+impl<N, I, Iptr, IptrStorage, IndStorage, DataStorage>
+    CsMatBase<N, I, IptrStorage, IndStorage, DataStorage, Iptr>
+where
+    I: SpIndex,
+    Iptr: SpIndex,
+    IptrStorage: Deref<Target = [Iptr]>,
+    IndStorage: Deref<Target = [I]>,
+    DataStorage: DerefMut<Target = [N]>,
+{
+    /// Mutable access to the non zero values
+    ///
+    /// This enables changing the values without changing the matrix's
+    /// structure. To also change the matrix's structure,
+    /// see [modify](fn.modify.html)
+    pub fn data_mut(&mut self) -> &mut [N] {
+        &mut self.data[..]
+    }
+
+    /// Sparse matrix self-multiplication by a scalar
+    pub fn scale(&mut self, val: N)
+    where
+        N: Num + Copy,
+    {
+        for data in self.data_mut() {
+            *data = *data * val;
+        }
+    }
+
+    /// Multiply each row of the matrix by the corresponding diagonal
+    /// factor, ie compute `diag(diag) * self` in place.
+    ///
+    /// This is cheaper for a CSR matrix, but works for both storages.
+    ///
+    /// # Panics
+    ///
+    /// - on dimension mismatch between `diag` and the number of rows
+    pub fn scale_rows(&mut self, diag: &[N])
+    where
+        N: Num + Copy,
+    {
+        assert_eq!(diag.len(), self.rows(), "Dimension mismatch");
+        self.scale_along(CSR, diag);
+    }
+
+    /// Multiply each column of the matrix by the corresponding diagonal
+    /// factor, ie compute `self * diag(diag)` in place.
+    ///
+    /// This is cheaper for a CSC matrix, but works for both storages.
+    ///
+    /// # Panics
+    ///
+    /// - on dimension mismatch between `diag` and the number of columns
+    pub fn scale_cols(&mut self, diag: &[N])
+    where
+        N: Num + Copy,
+    {
+        assert_eq!(diag.len(), self.cols(), "Dimension mismatch");
+        self.scale_along(CSC, diag);
+    }
+
+    /// Scale the rows (`axis == CSR`) or the columns (`axis == CSC`)
+    fn scale_along(&mut self, axis: CompressedStorage, diag: &[N])
+    where
+        N: Num + Copy,
+    {
+        let outer_scaling = self.storage == axis;
+        for (outer_ind, mut vec) in self.outer_iterator_mut().enumerate() {
+            if outer_scaling {
+                let factor = diag[outer_ind];
+                for (_, val) in vec.iter_mut() {
+                    *val = *val * factor;
+                }
+            } else {
+                for (inner_ind, val) in vec.iter_mut() {
+                    *val = *val * diag[inner_ind];
+                }
+            }
+        }
+    }
+
+    /// Get a mutable view into the i-th outer dimension
+    /// (eg i-th row for a CSR matrix)
+    pub fn outer_view_mut(&mut self, i: usize) -> Option<CsVecViewMutI<N, I>> {
+        if i >= self.outer_dims() {
+            return None;
+        }
+        let start = self.indptr[i].index_unchecked();
+        let stop = self.indptr[i + 1].index_unchecked();
+        // CsMat invariants imply CsVec invariants
+        Some(CsVecBase {
+            dim: self.inner_dims(),
+            indices: &self.indices[start..stop],
+            data: &mut self.data[start..stop],
+        })
+    }
+
+    /// Get a mutable reference to the element located at row i and column j.
+    /// Will return None if there is no non-zero element at this location.
+    ///
+    /// This access is logarithmic in the number of non-zeros
+    /// in the corresponding outer slice. It is therefore advisable not to rely
+    /// on this for algorithms, and prefer outer_iterator_mut() which accesses
+    /// elements in storage order.
+    /// TODO: outer_iterator_mut is not yet implemented
+    pub fn get_mut(&mut self, i: usize, j: usize) -> Option<&mut N> {
+        match self.storage {
+            CSR => self.get_outer_inner_mut(i, j),
+            CSC => self.get_outer_inner_mut(j, i),
+        }
+    }
+
+    /// Get a mutable reference to an element given its outer_ind and inner_ind.
+    /// Will return None if there is no non-zero element at this location.
+    ///
+    /// This access is logarithmic in the number of non-zeros
+    /// in the corresponding outer slice. It is therefore advisable not to rely
+    /// on this for algorithms, and prefer outer_iterator_mut() which accesses
+    /// elements in storage order.
+    pub fn get_outer_inner_mut(
+        &mut self,
+        outer_ind: usize,
+        inner_ind: usize,
+    ) -> Option<&mut N> {
+        if let Some(NnzIndex(index)) =
+            self.nnz_index_outer_inner(outer_ind, inner_ind)
+        {
+            Some(&mut self.data[index])
+        } else {
+            None
+        }
+    }
+
+    /// Set the value of the non-zero element located at (row, col)
+    ///
+    /// # Panics
+    ///
+    /// - on out-of-bounds access
+    /// - if no non-zero element exists at the given location
+    pub fn set(&mut self, row: usize, col: usize, val: N) {
+        let outer = outer_dimension(self.storage(), row, col);
+        let inner = inner_dimension(self.storage(), row, col);
+        let vec::NnzIndex(index) = self
+            .outer_view(outer)
+            .and_then(|vec| vec.nnz_index(inner))
+            .unwrap();
+        self.data[index] = val;
+    }
+
+    /// Apply a function to every non-zero element
+    pub fn map_inplace<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&N) -> N,
+    {
+        for val in &mut self.data[..] {
+            *val = f(val);
+        }
+    }
+
+    /// Return a mutable outer iterator for the matrix
+    ///
+    /// This iterator yields mutable sparse vector views for each outer
+    /// dimension. Only the non-zero values can be modified, the
+    /// structure is kept immutable.
+    pub fn outer_iterator_mut(&mut self) -> OuterIteratorMut<N, I, Iptr> {
+        let inner_len = match self.storage {
+            CSR => self.ncols,
+            CSC => self.nrows,
+        };
+        OuterIteratorMut {
+            inner_len,
+            indptr_iter: self.indptr.windows(2),
+            indices: &self.indices[..],
+            data: &mut self.data[..],
+        }
+    }
+}
+
+impl<N, I, Iptr, IptrStorage, IndStorage, DataStorage>
+    CsMatBase<N, I, IptrStorage, IndStorage, DataStorage, Iptr>
+where
+    I: SpIndex,
+    Iptr: SpIndex,
+    IptrStorage: DerefMut<Target = [Iptr]>,
+    IndStorage: DerefMut<Target = [I]>,
+    DataStorage: DerefMut<Target = [N]>,
+{
+    /// Modify the matrix's structure without changing its nonzero count.
+    ///
+    /// The coherence of the structure will be checked afterwards.
+    ///
+    /// # Panics
+    ///
+    /// If the resulting matrix breaks the CsMat invariants (sorted indices,
+    /// no out of bounds indices).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// // |   1   |
+    /// // | 1     |
+    /// // |   1 1 |
+    /// let mut mat = CsMat::new_csc((3, 3),
+    ///                                   vec![0, 1, 3, 4],
+    ///                                   vec![1, 0, 2, 2],
+    ///                                   vec![1.; 4]);
+    ///
+    /// // | 1 2   |
+    /// // | 1     |
+    /// // |   1   |
+    /// mat.modify(|indptr, indices, data| {
+    ///     indptr[1] = 2;
+    ///     indptr[2] = 4;
+    ///     indices[0] = 0;
+    ///     indices[1] = 1;
+    ///     indices[2] = 0;
+    ///     data[2] = 2.;
+    /// });
+    /// ```
+    pub fn modify<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut [Iptr], &mut [I], &mut [N]),
+    {
+        f(
+            &mut self.indptr[..],
+            &mut self.indices[..],
+            &mut self.data[..],
+        );
+        self.check_compressed_structure().unwrap();
+    }
+}
+
+/// Raw functions acting directly on the compressed structure.
+pub mod raw {
+    use crate::indexing::SpIndex;
+    use crate::sparse::prelude::*;
+    use std::mem::swap;
+
+    /*
+        /// Copy-convert a compressed matrix into the oppposite storage.
+        ///
+        /// The input compressed matrix does not need to have its indices sorted,
+        /// but the output compressed matrix will have its indices sorted.
+        ///
+        /// Can be used to implement CSC <-> CSR conversions, or to implement
+        /// same-storage (copy) transposition.
+        ///
+        /// # Panics
+        ///
+        /// Panics if indptr contains non-zero values
+        ///
+        /// Panics if the output slices don't match the input matrices'
+        /// corresponding slices.
+        pub fn convert_storage<N, I>(
+            in_storage: super::CompressedStorage,
+            shape: Shape,
+            in_indtpr: &[I],
+            in_indices: &[I],
+            in_data: &[N],
+            indptr: &mut [I],
+            indices: &mut [I],
+            data: &mut [N],
+        ) where
+            N: Clone,
+            I: SpIndex,
+        {
+            // we're building a csmat even though the indices are not sorted,
+            // but it's not a problem since we don't rely on this property.
+            // FIXME: this would be better with an explicit unsorted matrix type
+            let mat = CsMatBase {
+                storage: in_storage,
+                nrows: shape.0,
+                ncols: shape.1,
+                indptr: in_indtpr,
+                indices: in_indices,
+                data: in_data,
+            };
+
+            convert_mat_storage(mat, indptr, indices, data);
+        }
+    */
+
+    /// Copy-convert a csmat into the oppposite storage.
+    ///
+    /// Can be used to implement CSC <-> CSR conversions, or to implement
+    /// same-storage (copy) transposition.
+    ///
+    /// # Panics
+    ///
+    /// Panics if indptr contains non-zero values
+    ///
+    /// Panics if the output slices don't match the input matrices'
+    /// corresponding slices.
+    pub fn convert_mat_storage<N: Clone, I: SpIndex, Iptr: SpIndex>(
+        mat: CsMatViewI<N, I, Iptr>,
+        indptr: &mut [Iptr],
+        indices: &mut [I],
+        data: &mut [N],
+    ) {
+        assert_eq!(indptr.len(), mat.inner_dims() + 1);
+        assert_eq!(indices.len(), mat.indices().len());
+        assert_eq!(data.len(), mat.data().len());
+
+        assert!(indptr.iter().all(|x| x.is_zero()));
+
+        for vec in mat.outer_iterator() {
+            for (inner_dim, _) in vec.iter() {
+                indptr[inner_dim] += Iptr::one();
+            }
+        }
+
+        let mut cumsum = Iptr::zero();
+        for iptr in indptr.iter_mut() {
+            let tmp = *iptr;
+            *iptr = cumsum;
+            cumsum += tmp;
+        }
+        if let Some(last_iptr) = indptr.last() {
+            assert_eq!(last_iptr.index(), mat.nnz());
+        }
+
+        for (outer_dim, vec) in mat.outer_iterator().enumerate() {
+            for (inner_dim, val) in vec.iter() {
+                let dest = indptr[inner_dim].index();
+                data[dest] = val.clone();
+                indices[dest] = I::from_usize_unchecked(outer_dim);
+                indptr[inner_dim] += Iptr::one();
+            }
+        }
+
+        let mut last = Iptr::zero();
+        for iptr in indptr.iter_mut() {
+            swap(iptr, &mut last);
+        }
+    }
+}
+
+impl<'a, N: 'a, I: 'a + SpIndex, Iptr: 'a + SpIndex>
+    CsMatBase<N, I, Vec<Iptr>, &'a [I], &'a [N], Iptr>
+{
+    /// Create a borrowed row or column CsMat matrix from raw data,
+    /// without checking their validity
+    ///
+    /// # Safety
+    /// This is unsafe because algorithms are free to assume
+    /// that properties guaranteed by check_compressed_structure are enforced.
+    /// For instance, non out-of-bounds indices can be relied upon to
+    /// perform unchecked slice access.
+    pub unsafe fn new_vecview_raw(
+        storage: CompressedStorage,
+        nrows: usize,
+        ncols: usize,
+        indptr: *const Iptr,
+        indices: *const I,
+        data: *const N,
+    ) -> CsMatVecView_<'a, N, I, Iptr> {
+        let indptr = slice::from_raw_parts(indptr, 2);
+        let nnz = indptr[1].index_unchecked();
+        CsMatVecView_ {
+            storage,
+            nrows,
+            ncols,
+            indptr: Array2 {
+                data: [indptr[0], indptr[1]],
+            },
+            indices: slice::from_raw_parts(indices, nnz),
+            data: slice::from_raw_parts(data, nnz),
+        }
+    }
+}
+
+impl<'a, 'b, N, I, Iptr, IpStorage, IStorage, DStorage, IpS2, IS2, DS2>
+    Add<&'b CsMatBase<N, I, IpS2, IS2, DS2, Iptr>>
+    for &'a CsMatBase<N, I, IpStorage, IStorage, DStorage, Iptr>
+where
+    N: 'a + Copy + Num + Default,
+    I: 'a + SpIndex,
+    Iptr: 'a + SpIndex,
+    IpStorage: 'a + Deref<Target = [Iptr]>,
+    IStorage: 'a + Deref<Target = [I]>,
+    DStorage: 'a + Deref<Target = [N]>,
+    IpS2: 'a + Deref<Target = [Iptr]>,
+    IS2: 'a + Deref<Target = [I]>,
+    DS2: 'a + Deref<Target = [N]>,
+{
+    type Output = CsMatI<N, I, Iptr>;
+
+    fn add(
+        self,
+        rhs: &'b CsMatBase<N, I, IpS2, IS2, DS2, Iptr>,
+    ) -> CsMatI<N, I, Iptr> {
+        if self.storage() != rhs.view().storage() {
+            return binop::add_mat_same_storage(
+                self,
+                &rhs.view().to_other_storage(),
+            );
+        }
+        binop::add_mat_same_storage(self, rhs)
+    }
+}
+
+impl<'a, 'b, N, I, Iptr, IpStorage, IStorage, DStorage, Mat> Sub<&'b Mat>
+    for &'a CsMatBase<N, I, IpStorage, IStorage, DStorage, Iptr>
+where
+    N: 'a + Copy + Num + Default,
+    I: 'a + SpIndex,
+    Iptr: 'a + SpIndex,
+    IpStorage: 'a + Deref<Target = [Iptr]>,
+    IStorage: 'a + Deref<Target = [I]>,
+    DStorage: 'a + Deref<Target = [N]>,
+    Mat: SpMatView<N, I, Iptr>,
+{
+    type Output = CsMatI<N, I, Iptr>;
+
+    fn sub(self, rhs: &'b Mat) -> CsMatI<N, I, Iptr> {
+        if self.storage() != rhs.view().storage() {
+            return binop::sub_mat_same_storage(
+                self,
+                &rhs.view().to_other_storage(),
+            );
+        }
+        binop::sub_mat_same_storage(self, rhs)
+    }
+}
+
+impl<N, I, Iptr, IpS1, IS1, DS1> CsMatBase<N, I, IpS1, IS1, DS1, Iptr>
+where
+    I: SpIndex,
+    Iptr: SpIndex,
+    IpS1: Deref<Target = [Iptr]>,
+    IS1: Deref<Target = [I]>,
+    DS1: Deref<Target = [N]>,
+{
+    /// Compare two matrices logically: they are equal if they have the same
+    /// shape and the same non-zero values, regardless of their storage and
+    /// of any explicitly stored zeros.
+    ///
+    /// By contrast, `PartialEq` compares the storage of the matrices, so
+    /// a matrix with an explicitly stored zero differs from the same matrix
+    /// without it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsMat;
+    /// let a = CsMat::new((2, 2), vec![0, 1, 2], vec![0, 1], vec![1, 2]);
+    /// let b = CsMat::new((2, 2), vec![0, 2, 3], vec![0, 1, 1],
+    ///                    vec![1, 0, 2]);
+    /// assert!(a != b);
+    /// assert!(a.logically_eq(&b));
+    /// assert!(a.logically_eq(&b.to_csc()));
+    /// ```
+    pub fn logically_eq<IpS2, IS2, DS2>(
+        &self,
+        other: &CsMatBase<N, I, IpS2, IS2, DS2, Iptr>,
+    ) -> bool
+    where
+        N: PartialEq + Zero,
+        IpS2: Deref<Target = [Iptr]>,
+        IS2: Deref<Target = [I]>,
+        DS2: Deref<Target = [N]>,
+    {
+        if self.shape() != other.shape() {
+            return false;
+        }
+        if self.storage() == other.storage() {
+            self.outer_iterator().zip(other.outer_iterator()).all(
+                |(outer1, outer2)| {
+                    outer1.iter().nnz_or_zip(outer2.iter()).all(|elem| {
+                        match elem {
+                            Both((_, val1, val2)) => val1 == val2,
+                            Left((_, val)) | Right((_, val)) => val.is_zero(),
+                        }
+                    })
+                },
+            )
+        } else {
+            // Values absent from one of the matrices should be zero
+            // in the other one
+            let zero = N::zero();
+            self.iter().all(|(val, (i, j))| {
+                val == other.get(i.index(), j.index()).unwrap_or(&zero)
+            }) && other.iter().all(|(val, (i, j))| {
+                val == self.get(i.index(), j.index()).unwrap_or(&zero)
+            })
+        }
+    }
+}
+
+/// Maximum number of non-zeros printed by the `Display` implementation
+const DISPLAY_MAX_NNZ: usize = 20;
+
+/// Prints the shape, storage and number of non-zeros of the matrix, followed
+/// by its first non-zeros in storage order, one per line, as
+/// `(row, col): value`.
+impl<N, I, Iptr, IpS, IS, DS> fmt::Display
+    for CsMatBase<N, I, IpS, IS, DS, Iptr>
+where
+    N: fmt::Display,
+    I: SpIndex,
+    Iptr: SpIndex,
+    IpS: Deref<Target = [Iptr]>,
+    IS: Deref<Target = [I]>,
+    DS: Deref<Target = [N]>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let storage = match self.storage {
+            CSR => "CSR",
+            CSC => "CSC",
+        };
+        writeln!(
+            f,
+            "{}x{} {} matrix with {} non-zeros",
+            self.rows(),
+            self.cols(),
+            storage,
+            self.nnz()
+        )?;
+        for (val, (row, col)) in self.iter().take(DISPLAY_MAX_NNZ) {
+            writeln!(f, "({}, {}): {}", row.index(), col.index(), val)?;
+        }
+        if self.nnz() > DISPLAY_MAX_NNZ {
+            writeln!(f, "...")?;
+        }
+        Ok(())
+    }
+}
+
+macro_rules! sparse_scalar_mul {
+    ($scalar: ident) => {
+        impl<'a, I, Iptr, IpStorage, IStorage, DStorage> Mul<$scalar>
+            for &'a CsMatBase<$scalar, I, IpStorage, IStorage, DStorage, Iptr>
+        where
+            I: 'a + SpIndex,
+            Iptr: 'a + SpIndex,
+            IpStorage: 'a + Deref<Target = [Iptr]>,
+            IStorage: 'a + Deref<Target = [I]>,
+            DStorage: 'a + Deref<Target = [$scalar]>,
+        {
+            type Output = CsMatI<$scalar, I, Iptr>;
+
+            fn mul(self, rhs: $scalar) -> CsMatI<$scalar, I, Iptr> {
+                binop::scalar_mul_mat(self, rhs)
+            }
+        }
+
+        impl<'a, I, Iptr, IpStorage, IStorage, DStorage>
+            Mul<&'a CsMatBase<$scalar, I, IpStorage, IStorage, DStorage, Iptr>>
+            for $scalar
+        where
+            I: 'a + SpIndex,
+            Iptr: 'a + SpIndex,
+            IpStorage: 'a + Deref<Target = [Iptr]>,
+            IStorage: 'a + Deref<Target = [I]>,
+            DStorage: 'a + Deref<Target = [$scalar]>,
+        {
+            type Output = CsMatI<$scalar, I, Iptr>;
+
+            fn mul(
+                self,
+                rhs: &'a CsMatBase<
+                    $scalar,
+                    I,
+                    IpStorage,
+                    IStorage,
+                    DStorage,
+                    Iptr,
+                >,
+            ) -> CsMatI<$scalar, I, Iptr> {
+                binop::scalar_mul_mat(rhs, self)
+            }
+        }
+    };
+}
+
+sparse_scalar_mul!(u32);
+sparse_scalar_mul!(i32);
+sparse_scalar_mul!(u64);
+sparse_scalar_mul!(i64);
+sparse_scalar_mul!(isize);
+sparse_scalar_mul!(usize);
+sparse_scalar_mul!(f32);
+sparse_scalar_mul!(f64);
+
+impl<'a, I, Iptr, IpStorage, IStorage, DStorage, T> std::ops::MulAssign<T>
+    for CsMatBase<T, I, IpStorage, IStorage, DStorage, Iptr>
+where
+    I: 'a + SpIndex,
+    Iptr: 'a + SpIndex,
+    IpStorage: 'a + Deref<Target = [Iptr]>,
+    IStorage: 'a + Deref<Target = [I]>,
+    DStorage: 'a + DerefMut<Target = [T]>,
+    T: std::ops::MulAssign<T> + Clone,
+{
+    fn mul_assign(&mut self, rhs: T) {
+        self.data_mut()
+            .iter_mut()
+            .for_each(|v| v.mul_assign(rhs.clone()));
+    }
+}
+
+impl<'a, I, Iptr, IpStorage, IStorage, DStorage, T> std::ops::DivAssign<T>
+    for CsMatBase<T, I, IpStorage, IStorage, DStorage, Iptr>
+where
+    I: 'a + SpIndex,
+    Iptr: 'a + SpIndex,
+    IpStorage: 'a + Deref<Target = [Iptr]>,
+    IStorage: 'a + Deref<Target = [I]>,
+    DStorage: 'a + DerefMut<Target = [T]>,
+    T: std::ops::DivAssign<T> + Clone,
+{
+    fn div_assign(&mut self, rhs: T) {
+        self.data_mut()
+            .iter_mut()
+            .for_each(|v| v.div_assign(rhs.clone()));
+    }
+}
+
+/// Sparse matrix product, for any combination of storages.
+///
+/// The product is computed in the storage order of the left hand side
+/// matrix. The right hand side is only converted when its storage differs
+/// from the storage of the left hand side.
+impl<'a, 'b, N, I, Iptr, IpS1, IS1, DS1, IpS2, IS2, DS2>
+    Mul<&'b CsMatBase<N, I, IpS2, IS2, DS2, Iptr>>
+    for &'a CsMatBase<N, I, IpS1, IS1, DS1, Iptr>
+where
+    N: 'a + Copy + Num + Default + std::ops::AddAssign + Send + Sync,
+    I: 'a + SpIndex,
+    Iptr: 'a + SpIndex,
+    IpS1: 'a + Deref<Target = [Iptr]>,
+    IS1: 'a + Deref<Target = [I]>,
+    DS1: 'a + Deref<Target = [N]>,
+    IpS2: 'b + Deref<Target = [Iptr]>,
+    IS2: 'b + Deref<Target = [I]>,
+    DS2: 'b + Deref<Target = [N]>,
+{
+    type Output = CsMatI<N, I, Iptr>;
+
+    fn mul(
+        self,
+        rhs: &'b CsMatBase<N, I, IpS2, IS2, DS2, Iptr>,
+    ) -> CsMatI<N, I, Iptr> {
+        match (self.storage(), rhs.storage()) {
+            (CSR, CSR) => smmp::mul_csr_csr(self.view(), rhs.view()),
+            (CSR, CSC) => {
+                let rhs_csr = rhs.to_other_storage();
+                smmp::mul_csr_csr(self.view(), rhs_csr.view())
+            }
+            (CSC, CSR) => {
+                let rhs_csc = rhs.to_other_storage();
+                smmp::mul_csc_csc(self.view(), rhs_csc.view())
+            }
+            (CSC, CSC) => smmp::mul_csc_csc(self.view(), rhs.view()),
+        }
+    }
+}
+
+impl<'a, 'b, N, I, Iptr, IpS, IS, DS, DS2> Add<&'b ArrayBase<DS2, Ix2>>
+    for &'a CsMatBase<N, I, IpS, IS, DS, Iptr>
+where
+    N: 'a + Copy + Num + Default,
+    I: 'a + SpIndex,
+    Iptr: 'a + SpIndex,
+    IpS: 'a + Deref<Target = [Iptr]>,
+    IS: 'a + Deref<Target = [I]>,
+    DS: 'a + Deref<Target = [N]>,
+    DS2: 'b + ndarray::Data<Elem = N>,
+{
+    type Output = Array<N, Ix2>;
+
+    fn add(self, rhs: &'b ArrayBase<DS2, Ix2>) -> Array<N, Ix2> {
+        match (self.storage(), rhs.is_standard_layout()) {
+            (CSR, true) => binop::add_dense_mat_same_ordering(
+                self,
+                rhs,
+                N::one(),
+                N::one(),
+            ),
+            (CSR, false) => {
+                let lhs = self.to_other_storage();
+                binop::add_dense_mat_same_ordering(
+                    &lhs,
+                    rhs,
+                    N::one(),
+                    N::one(),
+                )
+            }
+            (CSC, true) => {
+                let lhs = self.to_other_storage();
+                binop::add_dense_mat_same_ordering(
+                    &lhs,
+                    rhs,
+                    N::one(),
+                    N::one(),
+                )
+            }
+            (CSC, false) => binop::add_dense_mat_same_ordering(
+                self,
+                rhs,
+                N::one(),
+                N::one(),
+            ),
+        }
+    }
+}
+
+impl<'a, 'b, N, I, Iptr, IpS, IS, DS, DS2> Mul<&'b ArrayBase<DS2, Ix2>>
+    for &'a CsMatBase<N, I, IpS, IS, DS, Iptr>
+where
+    N: 'a + Copy + Num + Default,
+    I: 'a + SpIndex,
+    Iptr: 'a + SpIndex,
+    IpS: 'a + Deref<Target = [Iptr]>,
+    IS: 'a + Deref<Target = [I]>,
+    DS: 'a + Deref<Target = [N]>,
+    DS2: 'b + ndarray::Data<Elem = N>,
+{
+    type Output = Array<N, Ix2>;
+
+    fn mul(self, rhs: &'b ArrayBase<DS2, Ix2>) -> Array<N, Ix2> {
+        let rows = self.rows();
+        let cols = rhs.shape()[1];
+        // when the number of colums is small, it is more efficient
+        // to perform the product by iterating over the columns of
+        // the rhs, otherwise iterating by rows can take advantage of
+        // vectorized axpy.
+        match (self.storage(), cols >= 8) {
+            (CSR, true) => {
+                let mut res = Array::zeros((rows, cols));
+                prod::csr_mulacc_dense_rowmaj(
+                    self.view(),
+                    rhs.view(),
+                    res.view_mut(),
+                );
+                res
+            }
+            (CSR, false) => {
+                let mut res = Array::zeros((rows, cols).f());
+                prod::csr_mulacc_dense_colmaj(
+                    self.view(),
+                    rhs.view(),
+                    res.view_mut(),
+                );
+                res
+            }
+            (CSC, true) => {
+                let mut res = Array::zeros((rows, cols));
+                prod::csc_mulacc_dense_rowmaj(
+                    self.view(),
+                    rhs.view(),
+                    res.view_mut(),
+                );
+                res
+            }
+            (CSC, false) => {
+                let mut res = Array::zeros((rows, cols).f());
+                prod::csc_mulacc_dense_colmaj(
+                    self.view(),
+                    rhs.view(),
+                    res.view_mut(),
+                );
+                res
+            }
+        }
+    }
+}
+
+impl<'a, 'b, N, I, IpS, IS, DS, DS2> Dot<CsMatBase<N, I, IpS, IS, DS>>
+    for ArrayBase<DS2, Ix2>
+where
+    N: 'a + Copy + Num + Default + std::fmt::Debug,
+    I: 'a + SpIndex,
+    IpS: 'a + Deref<Target = [I]>,
+    IS: 'a + Deref<Target = [I]>,
+    DS: 'a + Deref<Target = [N]>,
+    DS2: 'b + ndarray::Data<Elem = N>,
+{
+    type Output = Array<N, Ix2>;
+
+    fn dot(&self, rhs: &CsMatBase<N, I, IpS, IS, DS>) -> Array<N, Ix2> {
+        let rhs_t = rhs.transpose_view();
+        let lhs_t = self.t();
+
+        let rows = rhs_t.rows();
+        let cols = lhs_t.ncols();
+        // when the number of colums is small, it is more efficient
+        // to perform the product by iterating over the columns of
+        // the rhs, otherwise iterating by rows can take advantage of
+        // vectorized axpy.
+        let rres = match (rhs_t.storage(), cols >= 8) {
+            (CSR, true) => {
+                let mut res = Array::zeros((rows, cols));
+                prod::csr_mulacc_dense_rowmaj(rhs_t, lhs_t, res.view_mut());
+                res.reversed_axes()
+            }
+            (CSR, false) => {
+                let mut res = Array::zeros((rows, cols).f());
+                prod::csr_mulacc_dense_colmaj(rhs_t, lhs_t, res.view_mut());
+                res.reversed_axes()
+            }
+            (CSC, true) => {
+                let mut res = Array::zeros((rows, cols));
+                prod::csc_mulacc_dense_rowmaj(rhs_t, lhs_t, res.view_mut());
+                res.reversed_axes()
+            }
+            (CSC, false) => {
+                let mut res = Array::zeros((rows, cols).f());
+                prod::csc_mulacc_dense_colmaj(rhs_t, lhs_t, res.view_mut());
+                res.reversed_axes()
+            }
+        };
+
+        assert_eq!(self.shape()[0], rres.shape()[0]);
+        assert_eq!(rhs.cols(), rres.shape()[1]);
+        rres
+    }
+}
+
+impl<'a, 'b, N, I, Iptr, IpS, IS, DS, DS2> Dot<ArrayBase<DS2, Ix2>>
+    for CsMatBase<N, I, IpS, IS, DS, Iptr>
+where
+    N: 'a + Copy + Num + Default,
+    I: 'a + SpIndex,
+    Iptr: 'a + SpIndex,
+    IpS: 'a + Deref<Target = [Iptr]>,
+    IS: 'a + Deref<Target = [I]>,
+    DS: 'a + Deref<Target = [N]>,
+    DS2: 'b + ndarray::Data<Elem = N>,
+{
+    type Output = Array<N, Ix2>;
+
+    fn dot(&self, rhs: &ArrayBase<DS2, Ix2>) -> Array<N, Ix2> {
+        Mul::mul(self, rhs)
+    }
+}
+
+impl<'a, 'b, N, I, Iptr, IpS, IS, DS, DS2> Mul<&'b ArrayBase<DS2, Ix1>>
+    for &'a CsMatBase<N, I, IpS, IS, DS, Iptr>
+where
+    N: 'a + Copy + Num + Default,
+    I: 'a + SpIndex,
+    Iptr: 'a + SpIndex,
+    IpS: 'a + Deref<Target = [Iptr]>,
+    IS: 'a + Deref<Target = [I]>,
+    DS: 'a + Deref<Target = [N]>,
+    DS2: 'b + ndarray::Data<Elem = N>,
+{
+    type Output = Array<N, Ix1>;
+
+    fn mul(self, rhs: &'b ArrayBase<DS2, Ix1>) -> Array<N, Ix1> {
+        let rows = self.rows();
+        let cols = rhs.shape()[0];
+        let rhs_reshape = rhs.view().into_shape((cols, 1)).unwrap();
+        let mut res = Array::zeros(rows);
+        {
+            let res_reshape = res.view_mut().into_shape((rows, 1)).unwrap();
+            match self.storage() {
+                CSR => {
+                    prod::csr_mulacc_dense_colmaj(
+                        self.view(),
+                        rhs_reshape,
+                        res_reshape,
+                    );
+                }
+                CSC => {
+                    prod::csc_mulacc_dense_colmaj(
+                        self.view(),
+                        rhs_reshape,
+                        res_reshape,
+                    );
+                }
+            }
+        }
+        res
+    }
+}
+
+impl<'a, 'b, N, I, Iptr, IpS, IS, DS> Mul<&'b [N]>
+    for &'a CsMatBase<N, I, IpS, IS, DS, Iptr>
+where
+    N: 'a + Copy + Num,
+    I: 'a + SpIndex,
+    Iptr: 'a + SpIndex,
+    IpS: 'a + Deref<Target = [Iptr]>,
+    IS: 'a + Deref<Target = [I]>,
+    DS: 'a + Deref<Target = [N]>,
+{
+    type Output = Vec<N>;
+
+    fn mul(self, rhs: &'b [N]) -> Vec<N> {
+        let mut res = vec![N::zero(); self.rows()];
+        match self.storage() {
+            CSR => prod::mul_acc_mat_vec_csr(self.view(), rhs, &mut res),
+            CSC => prod::mul_acc_mat_vec_csc(self.view(), rhs, &mut res),
+        }
+        res
+    }
+}
+
+impl<'a, 'b, N, I, Iptr, IpS, IS, DS, DS2> Dot<ArrayBase<DS2, Ix1>>
+    for CsMatBase<N, I, IpS, IS, DS, Iptr>
+where
+    N: 'a + Copy + Num + Default,
+    I: 'a + SpIndex,
+    Iptr: 'a + SpIndex,
+    IpS: 'a + Deref<Target = [Iptr]>,
+    IS: 'a + Deref<Target = [I]>,
+    DS: 'a + Deref<Target = [N]>,
+    DS2: 'b + ndarray::Data<Elem = N>,
+{
+    type Output = Array<N, Ix1>;
+
+    fn dot(&self, rhs: &ArrayBase<DS2, Ix1>) -> Array<N, Ix1> {
+        Mul::mul(self, rhs)
+    }
+}
+
+impl<N, I, Iptr, IpS, IS, DS> Index<[usize; 2]>
+    for CsMatBase<N, I, IpS, IS, DS, Iptr>
+where
+    I: SpIndex,
+    Iptr: SpIndex,
+    IpS: Deref<Target = [Iptr]>,
+    IS: Deref<Target = [I]>,
+    DS: Deref<Target = [N]>,
+{
+    type Output = N;
+
+    fn index(&self, index: [usize; 2]) -> &N {
+        let i = index[0];
+        let j = index[1];
+        self.get(i, j).unwrap()
+    }
+}
+
+impl<N, I, Iptr, IpS, IS, DS> IndexMut<[usize; 2]>
+    for CsMatBase<N, I, IpS, IS, DS, Iptr>
+where
+    I: SpIndex,
+    Iptr: SpIndex,
+    IpS: Deref<Target = [Iptr]>,
+    IS: Deref<Target = [I]>,
+    DS: DerefMut<Target = [N]>,
+{
+    fn index_mut(&mut self, index: [usize; 2]) -> &mut N {
+        let i = index[0];
+        let j = index[1];
+        self.get_mut(i, j).unwrap()
+    }
+}
+
+impl<N, I, Iptr, IpS, IS, DS> Index<NnzIndex>
+    for CsMatBase<N, I, IpS, IS, DS, Iptr>
+where
+    I: SpIndex,
+    Iptr: SpIndex,
+    IpS: Deref<Target = [Iptr]>,
+    IS: Deref<Target = [I]>,
+    DS: Deref<Target = [N]>,
+{
+    type Output = N;
+
+    fn index(&self, index: NnzIndex) -> &N {
+        let NnzIndex(i) = index;
+        self.data().get(i).unwrap()
+    }
+}
+
+impl<N, I, Iptr, IpS, IS, DS> IndexMut<NnzIndex>
+    for CsMatBase<N, I, IpS, IS, DS, Iptr>
+where
+    I: SpIndex,
+    Iptr: SpIndex,
+    IpS: Deref<Target = [Iptr]>,
+    IS: Deref<Target = [I]>,
+    DS: DerefMut<Target = [N]>,
+{
+    fn index_mut(&mut self, index: NnzIndex) -> &mut N {
+        let NnzIndex(i) = index;
+        self.data_mut().get_mut(i).unwrap()
+    }
+}
+
+impl<N, I, Iptr, IpS, IS, DS> SparseMat for CsMatBase<N, I, IpS, IS, DS, Iptr>
+where
+    I: SpIndex,
+    Iptr: SpIndex,
+    IpS: Deref<Target = [Iptr]>,
+    IS: Deref<Target = [I]>,
+    DS: Deref<Target = [N]>,
+{
+    fn rows(&self) -> usize {
+        self.rows()
+    }
+
+    fn cols(&self) -> usize {
+        self.cols()
+    }
+
+    fn nnz(&self) -> usize {
+        self.nnz()
+    }
+}
+
+impl<'a, N, I, Iptr, IpS, IS, DS> SparseMat
+    for &'a CsMatBase<N, I, IpS, IS, DS, Iptr>
+where
+    I: 'a + SpIndex,
+    Iptr: 'a + SpIndex,
+    N: 'a,
+    IpS: Deref<Target = [Iptr]>,
+    IS: Deref<Target = [I]>,
+    DS: Deref<Target = [N]>,
+{
+    fn rows(&self) -> usize {
+        (*self).rows()
+    }
+
+    fn cols(&self) -> usize {
+        (*self).cols()
+    }
+
+    fn nnz(&self) -> usize {
+        (*self).nnz()
+    }
+}
+
+impl<'a, N, I, IpS, IS, DS, Iptr> IntoIterator
+    for &'a CsMatBase<N, I, IpS, IS, DS, Iptr>
+where
+    I: 'a + SpIndex,
+    Iptr: 'a + SpIndex,
+    N: 'a,
+    IpS: Deref<Target = [Iptr]>,
+    IS: Deref<Target = [I]>,
+    DS: Deref<Target = [N]>,
+{
+    type Item = (&'a N, (I, I));
+    type IntoIter = CsIter<'a, N, I, Iptr>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, N, I, Iptr> IntoIterator for CsMatViewI<'a, N, I, Iptr>
+where
+    I: 'a + SpIndex,
+    Iptr: 'a + SpIndex,
+    N: 'a,
+{
+    type Item = (&'a N, (I, I));
+    type IntoIter = CsIter<'a, N, I, Iptr>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_rbr()
+    }
+}
+
+/// An iterator over non-overlapping blocks of a matrix,
+/// along the least-varying dimension
+pub struct ChunkOuterBlocks<'a, N: 'a, I: 'a + SpIndex, Iptr: 'a + SpIndex = I>
+{
+    mat: CsMatViewI<'a, N, I, Iptr>,
+    dims_in_bloc: usize,
+    bloc_count: usize,
+}
+
+impl<'a, N: 'a, I: 'a + SpIndex, Iptr: 'a + SpIndex> Iterator
+    for ChunkOuterBlocks<'a, N, I, Iptr>
+{
+    type Item = CsMatViewI<'a, N, I, Iptr>;
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        let cur_dim = self.dims_in_bloc * self.bloc_count;
+        let end_dim = self.dims_in_bloc + cur_dim;
+        let count = if self.dims_in_bloc == 0 {
+            return None;
+        } else if end_dim > self.mat.outer_dims() {
+            let count = self.mat.outer_dims() - cur_dim;
+            self.dims_in_bloc = 0;
+            count
+        } else {
+            self.dims_in_bloc
+        };
+        let view = self.mat.middle_outer_views(cur_dim, count);
+        self.bloc_count += 1;
+        Some(view)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CompressedStorage::{CSC, CSR};
+    use crate::errors::SprsError;
+    use crate::sparse::{CsMat, CsMatI, CsMatView};
+    use crate::test_data::{mat1, mat1_csc, mat1_times_2};
+    use ndarray::{arr2, s, Array};
+
+    #[test]
+    fn test_copy() {
+        let m = mat1();
+        let view1 = m.view();
+        let view2 = view1; // this shouldn't move
+        assert_eq!(view1, view2);
+    }
+
+    #[test]
+    fn test_new_csr_success() {
+        let indptr_ok: &[usize] = &[0, 1, 2, 3];
+        let indices_ok: &[usize] = &[0, 1, 2];
+        let data_ok: &[f64] = &[1., 1., 1.];
+        let m =
+            CsMatView::new_view(CSR, (3, 3), indptr_ok, indices_ok, data_ok);
+        assert!(m.is_ok());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_new_csr_bad_indptr_length() {
+        let indptr_fail1: &[usize] = &[0, 1, 2];
+        let indices_ok: &[usize] = &[0, 1, 2];
+        let data_ok: &[f64] = &[1., 1., 1.];
+        let res =
+            CsMatView::new_view(CSR, (3, 3), indptr_fail1, indices_ok, data_ok);
+        res.unwrap(); // unreachable
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_new_csr_out_of_bounds_index() {
+        let indptr_ok: &[usize] = &[0, 1, 2, 3];
+        let data_ok: &[f64] = &[1., 1., 1.];
+        let indices_fail2: &[usize] = &[0, 1, 4];
+        let res =
+            CsMatView::new_view(CSR, (3, 3), indptr_ok, indices_fail2, data_ok);
+        res.unwrap(); //unreachable
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_new_csr_bad_nnz_count() {
+        let indices_ok: &[usize] = &[0, 1, 2];
+        let data_ok: &[f64] = &[1., 1., 1.];
+        let indptr_fail2: &[usize] = &[0, 1, 2, 4];
+        let res =
+            CsMatView::new_view(CSR, (3, 3), indptr_fail2, indices_ok, data_ok);
+        res.unwrap(); //unreachable
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_new_csr_data_indices_mismatch1() {
+        let indptr_ok: &[usize] = &[0, 1, 2, 3];
+        let data_ok: &[f64] = &[1., 1., 1.];
+        let indices_fail1: &[usize] = &[0, 1];
+        let res =
+            CsMatView::new_view(CSR, (3, 3), indptr_ok, indices_fail1, data_ok);
+        res.unwrap(); //unreachable
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_new_csr_data_indices_mismatch2() {
+        let indptr_ok: &[usize] = &[0, 1, 2, 3];
+        let indices_ok: &[usize] = &[0, 1, 2];
+        let data_fail1: &[f64] = &[1., 1., 1., 1.];
+        let res =
+            CsMatView::new_view(CSR, (3, 3), indptr_ok, indices_ok, data_fail1);
+        res.unwrap(); //unreachable
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_new_csr_data_indices_mismatch3() {
+        let indptr_ok: &[usize] = &[0, 1, 2, 3];
+        let indices_ok: &[usize] = &[0, 1, 2];
+        let data_fail2: &[f64] = &[1., 1.];
+        let res =
+            CsMatView::new_view(CSR, (3, 3), indptr_ok, indices_ok, data_fail2);
+        res.unwrap(); //unreachable
+    }
+
+    #[test]
+    fn test_new_csr_fails() {
+        let indices_ok: &[usize] = &[0, 1, 2];
+        let data_ok: &[f64] = &[1., 1., 1.];
+        let indptr_fail3: &[usize] = &[0, 2, 1, 3];
+        assert_eq!(
+            CsMatView::new_view(CSR, (3, 3), indptr_fail3, indices_ok, data_ok),
+            Err(SprsError::UnsortedIndptr)
+        );
+    }
+
+    #[test]
+    fn test_new_csr_fail_indices_ordering() {
+        let indptr: &[usize] = &[0, 2, 4, 5, 6, 7];
+        // good indices would be [2, 3, 3, 4, 2, 1, 3];
+        let indices: &[usize] = &[3, 2, 3, 4, 2, 1, 3];
+        let data: &[f64] = &[
+            0.35310881, 0.42380633, 0.28035896, 0.58082095, 0.53350123,
+            0.88132896, 0.72527863,
+        ];
+        assert_eq!(
+            CsMatView::new_view(CSR, (5, 5), indptr, indices, data),
+            Err(SprsError::NonSortedIndices)
+        );
+    }
+
+    #[test]
+    fn test_new_csr_csc_success() {
+        let indptr_ok: &[usize] = &[0, 2, 5, 6];
+        let indices_ok: &[usize] = &[2, 3, 1, 2, 3, 3];
+        let data_ok: &[f64] = &[
+            0.05734571, 0.15543348, 0.75628258, 0.83054515, 0.71851547,
+            0.46202352,
+        ];
+        assert!(CsMatView::new_view(
+            CSR,
+            (3, 4),
+            indptr_ok,
+            indices_ok,
+            data_ok
+        )
+        .is_ok());
+        assert!(CsMatView::new_view(
+            CSC,
+            (4, 3),
+            indptr_ok,
+            indices_ok,
+            data_ok
+        )
+        .is_ok());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_new_csc_bad_indptr_length() {
+        let indptr_ok: &[usize] = &[0, 2, 5, 6];
+        let indices_ok: &[usize] = &[2, 3, 1, 2, 3, 3];
+        let data_ok: &[f64] = &[
+            0.05734571, 0.15543348, 0.75628258, 0.83054515, 0.71851547,
+            0.46202352,
+        ];
+        let res =
+            CsMatView::new_view(CSC, (3, 4), indptr_ok, indices_ok, data_ok);
+        res.unwrap(); //unreachable
+    }
+
+    #[test]
+    fn test_new_csr_vec_borrowed() {
+        let indptr_ok = vec![0, 1, 2, 3];
+        let indices_ok = vec![0, 1, 2];
+        let data_ok: Vec<f64> = vec![1., 1., 1.];
+        assert!(CsMatView::new_view(
+            CSR,
+            (3, 3),
+            &indptr_ok,
+            &indices_ok,
+            &data_ok
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_new_csr_vec_owned() {
+        let indptr_ok = vec![0, 1, 2, 3];
+        let indices_ok = vec![0, 1, 2];
+        let data_ok: Vec<f64> = vec![1., 1., 1.];
+        assert!(
+            CsMat::new_(CSR, (3, 3), indptr_ok, indices_ok, data_ok).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_csr_from_dense() {
+        let m = Array::eye(3);
+        let m_sparse = CsMat::csr_from_dense(m.view(), 0.);
+
+        assert_eq!(m_sparse, CsMat::eye(3));
+
+        let m = arr2(&[
+            [1., 0., 2., 1e-7, 1.],
+            [0., 0., 0., 1., 0.],
+            [3., 0., 1., 0., 0.],
+        ]);
+        let m_sparse = CsMat::csr_from_dense(m.view(), 1e-5);
+
+        let expected_output = CsMat::new(
+            (3, 5),
+            vec![0, 3, 4, 6],
+            vec![0, 2, 4, 3, 0, 2],
+            vec![1., 2., 1., 1., 3., 1.],
+        );
+
+        assert_eq!(m_sparse, expected_output);
+    }
+
+    #[test]
+    fn test_csc_from_dense() {
+        let m = Array::eye(3);
+        let m_sparse = CsMat::csc_from_dense(m.view(), 0.);
+
+        assert_eq!(m_sparse, CsMat::eye_csc(3));
+
+        let m = arr2(&[
+            [1., 0., 2., 1e-7, 1.],
+            [0., 0., 0., 1., 0.],
+            [3., 0., 1., 0., 0.],
+        ]);
+        let m_sparse = CsMat::csc_from_dense(m.view(), 1e-5);
+
+        let expected_output = CsMat::new_csc(
+            (3, 5),
+            vec![0, 2, 2, 4, 5, 6],
+            vec![0, 2, 0, 2, 1, 0],
+            vec![1., 3., 2., 1., 1., 1.],
+        );
+
+        assert_eq!(m_sparse, expected_output);
+    }
+
+    #[test]
+    fn from_dense_all_zeros() {
+        let m = Array::<f64, _>::zeros((3, 4));
+        let m_sparse = CsMat::csr_from_dense(m.view(), 0.);
+        assert_eq!(m_sparse.shape(), (3, 4));
+        assert_eq!(m_sparse.nnz(), 0);
+        assert_eq!(m_sparse.indptr(), &[0; 4]);
+        let m_sparse = CsMat::csc_from_dense(m.view(), 0.);
+        assert_eq!(m_sparse.shape(), (3, 4));
+        assert_eq!(m_sparse.nnz(), 0);
+        assert_eq!(m_sparse.indptr(), &[0; 5]);
+    }
+
+    #[test]
+    fn from_dense_round_trip() {
+        let m = mat1().to_dense();
+        let m_csr = CsMat::csr_from_dense(m.view(), 0.);
+        assert_eq!(m_csr, mat1());
+        assert_eq!(m_csr.to_dense(), m);
+        let m_csc = CsMat::csc_from_dense(m.view(), 0.);
+        assert_eq!(m_csc, mat1_csc());
+        assert_eq!(m_csc.to_dense(), m);
+    }
+
+    #[test]
+    fn owned_csr_unsorted_indices() {
+        let indptr = vec![0, 3, 3, 5, 6, 7];
+        let indices_sorted = &[1, 2, 3, 2, 3, 4, 4];
+        let indices_shuffled = vec![1, 3, 2, 2, 3, 4, 4];
+        let mut data: Vec<i32> = (0..7).collect();
+        let m = CsMat::new((5, 5), indptr, indices_shuffled, data.clone());
+        assert_eq!(m.indices(), indices_sorted);
+        data.swap(1, 2);
+        assert_eq!(m.data(), &data[..]);
+    }
+
+    #[test]
+    fn new_csr_with_empty_row() {
+        let indptr: &[usize] = &[0, 3, 3, 5, 6, 7];
+        let indices: &[usize] = &[1, 2, 3, 2, 3, 4, 4];
+        let data: &[f64] = &[
+            0.75672424, 0.1649078, 0.30140296, 0.10358244, 0.6283315,
+            0.39244208, 0.57202407,
+        ];
+        assert!(CsMatView::new_view(CSR, (5, 5), indptr, indices, data).is_ok());
+    }
+
+    #[test]
+    fn csr_to_csc() {
+        let a = mat1();
+        let a_csc_ground_truth = mat1_csc();
+        let a_csc = a.to_other_storage();
+        assert_eq!(a_csc, a_csc_ground_truth);
+    }
+
+    #[test]
+    fn test_self_smul() {
+        let mut a = mat1();
+        a.scale(2.);
+        let c_true = mat1_times_2();
+        assert_eq!(a.indptr(), c_true.indptr());
+        assert_eq!(a.indices(), c_true.indices());
+        assert_eq!(a.data(), c_true.data());
+    }
+
+    #[test]
+    fn outer_block_iter() {
+        let mat: CsMat<f64> = CsMat::eye(11);
+        let mut block_iter = mat.outer_block_iter(3);
+        assert_eq!(block_iter.next().unwrap().rows(), 3);
+        assert_eq!(block_iter.next().unwrap().rows(), 3);
+        assert_eq!(block_iter.next().unwrap().rows(), 3);
+        assert_eq!(block_iter.next().unwrap().rows(), 2);
+        assert_eq!(block_iter.next(), None);
+
+        let mut block_iter = mat.outer_block_iter(4);
+        assert_eq!(block_iter.next().unwrap().cols(), 11);
+        block_iter.next().unwrap();
+        block_iter.next().unwrap();
+        assert_eq!(block_iter.next(), None);
+    }
+
+    #[test]
+    fn nnz_index() {
+        let mat: CsMat<f64> = CsMat::eye(11);
+
+        assert_eq!(mat.nnz_index(2, 3), None);
+        assert_eq!(mat.nnz_index(5, 7), None);
+        assert_eq!(mat.nnz_index(0, 11), None);
+        assert_eq!(mat.nnz_index(0, 0), Some(super::NnzIndex(0)));
+        assert_eq!(mat.nnz_index(7, 7), Some(super::NnzIndex(7)));
+        assert_eq!(mat.nnz_index(10, 10), Some(super::NnzIndex(10)));
+
+        let index = mat.nnz_index(8, 8).unwrap();
+        assert_eq!(mat[index], 1.);
+        let mut mat = mat;
+        mat[index] = 2.;
+        assert_eq!(mat[index], 2.);
+    }
+
+    #[test]
+    fn index() {
+        // | 0 2 0 |
+        // | 1 0 0 |
+        // | 0 3 4 |
+        let mat = CsMat::new_csc(
+            (3, 3),
+            vec![0, 1, 3, 4],
+            vec![1, 0, 2, 2],
+            vec![1., 2., 3., 4.],
+        );
+        assert_eq!(mat[[1, 0]], 1.);
+        assert_eq!(mat[[0, 1]], 2.);
+        assert_eq!(mat[[2, 1]], 3.);
+        assert_eq!(mat[[2, 2]], 4.);
+        assert_eq!(mat.get(0, 0), None);
+        assert_eq!(mat.get(4, 4), None);
+    }
+
+    #[test]
+    fn get_hits_and_misses() {
+        for mat in [mat1(), mat1_csc()].iter() {
+            assert_eq!(mat.get(0, 2), Some(&3.));
+            assert_eq!(mat.get(1, 4), Some(&5.));
+            assert_eq!(mat.get(3, 1), Some(&8.));
+            assert_eq!(mat.get(4, 3), Some(&7.));
+            // structural zeros
+            assert_eq!(mat.get(0, 0), None);
+            assert_eq!(mat.get(3, 3), None);
+            assert_eq!(mat.get(4, 4), None);
+            // out of bounds
+            assert_eq!(mat.get(5, 0), None);
+            assert_eq!(mat.get(0, 5), None);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_out_of_bounds() {
+        let mat = mat1();
+        let _ = mat[[0, 5]];
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_structural_zero() {
+        let mat = mat1_csc();
+        let _ = mat[[0, 0]];
+    }
+
+    #[test]
+    fn get_mut() {
+        // | 0 1 0 |
+        // | 1 0 0 |
+        // | 0 1 1 |
+        let mut mat = CsMat::new_csc(
+            (3, 3),
+            vec![0, 1, 3, 4],
+            vec![1, 0, 2, 2],
+            vec![1.; 4],
+        );
+
+        *mat.get_mut(2, 1).unwrap() = 3.;
+
+        let exp = CsMat::new_csc(
+            (3, 3),
+            vec![0, 1, 3, 4],
+            vec![1, 0, 2, 2],
+            vec![1., 1., 3., 1.],
+        );
+
+        assert_eq!(mat, exp);
+
+        mat[[2, 2]] = 5.;
+        let exp = CsMat::new_csc(
+            (3, 3),
+            vec![0, 1, 3, 4],
+            vec![1, 0, 2, 2],
+            vec![1., 1., 3., 5.],
+        );
+
+        assert_eq!(mat, exp);
+    }
+
+    #[test]
+    fn map() {
+        // | 0 1 0 |
+        // | 1 0 0 |
+        // | 0 1 1 |
+        let mat = CsMat::new_csc(
+            (3, 3),
+            vec![0, 1, 3, 4],
+            vec![1, 0, 2, 2],
+            vec![1.; 4],
+        );
+
+        let mut res = mat.map(|&x| x + 2.);
+        let expected = CsMat::new_csc(
+            (3, 3),
+            vec![0, 1, 3, 4],
+            vec![1, 0, 2, 2],
+            vec![3.; 4],
+        );
+        assert_eq!(res, expected);
+
+        res.map_inplace(|&x| x / 3.);
+        assert_eq!(res, mat);
+    }
+
+    #[test]
+    fn insert() {
+        // | 0 1 0 |
+        // | 1 0 0 |
+        // | 0 1 1 |
+        let mut mat = CsMat::empty(CSR, 0);
+        mat.reserve_outer_dim(3);
+        mat.reserve_nnz(4);
+        // exercise the fast and easy path where the elements are added
+        // in row order for a CSR matrix
+        mat.insert(0, 1, 1.);
+        mat.insert(1, 0, 1.);
+        mat.insert(2, 1, 1.);
+        mat.insert(2, 2, 1.);
+
+        let expected =
+            CsMat::new((3, 3), vec![0, 1, 2, 4], vec![1, 0, 1, 2], vec![1.; 4]);
+        assert_eq!(mat, expected);
+
+        // | 2 1 0 |
+        // | 1 0 0 |
+        // | 0 1 1 |
+        // exercise adding inside an already formed row (ie a search needs
+        // to be performed)
+        mat.insert(0, 0, 2.);
+        let expected = CsMat::new(
+            (3, 3),
+            vec![0, 2, 3, 5],
+            vec![0, 1, 0, 1, 2],
+            vec![2., 1., 1., 1., 1.],
+        );
+        assert_eq!(mat, expected);
+
+        // | 2 1 0 |
+        // | 3 0 0 |
+        // | 0 1 1 |
+        // exercise the fact that inserting in an existing element
+        // should change this element's value
+        mat.insert(1, 0, 3.);
+        let expected = CsMat::new(
+            (3, 3),
+            vec![0, 2, 3, 5],
+            vec![0, 1, 0, 1, 2],
+            vec![2., 1., 3., 1., 1.],
+        );
+        assert_eq!(mat, expected);
+    }
+
+    #[test]
+    /// Non-regression test for https://github.com/vbarrielle/sprs/issues/129
+    fn bug_129() {
+        let mut mat = CsMat::zero((3, 100));
+        mat.insert(2, 3, 42);
+        let mut iter = mat.iter();
+        assert_eq!(iter.next(), Some((&42, (2, 3))));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn iter_mut() {
+        // | 0 1 0 |
+        // | 1 0 0 |
+        // | 0 1 1 |
+        let mut mat = CsMat::new_csc(
+            (3, 3),
+            vec![0, 1, 3, 4],
+            vec![1, 0, 2, 2],
+            vec![1.; 4],
+        );
+
+        for mut col_vec in mat.outer_iterator_mut() {
+            for (row_ind, val) in col_vec.iter_mut() {
+                *val = row_ind as f64 + 1.;
+            }
+        }
+
+        let expected = CsMat::new_csc(
+            (3, 3),
+            vec![0, 1, 3, 4],
+            vec![1, 0, 2, 2],
+            vec![2., 1., 3., 3.],
+        );
+        assert_eq!(mat, expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn modify_fail() {
+        let mut mat = CsMat::new_csc(
+            (3, 3),
+            vec![0, 1, 3, 4],
+            vec![1, 0, 2, 2],
+            vec![1.; 4],
+        );
+
+        // we panic because we forget to modify the last index, which gets
+        // pushed in the same col as its predecessor, yet has the same value
+        mat.modify(|indptr, indices, data| {
+            indptr[1] = 2;
+            indptr[2] = 4;
+            indices[0] = 0;
+            indices[1] = 1;
+            data[2] = 2.;
+        });
+    }
+
+    #[test]
+    fn convert_types() {
+        let mat: CsMat<f32> = CsMat::eye(3);
+        let mat_: CsMatI<f64, u32> = mat.to_other_types();
+        assert_eq!(mat_.indptr(), &[0, 1, 2, 3]);
+
+        let mat = CsMatI::new_csc(
+            (3, 3),
+            vec![0u32, 1, 3, 4],
+            vec![1, 0, 2, 2],
+            vec![1.; 4],
+        );
+        let mat_: CsMatI<f32, usize, u32> = mat.to_other_types();
+        assert_eq!(mat_.indptr(), &[0, 1, 3, 4]);
+        assert_eq!(mat_.data(), &[1.0f32, 1., 1., 1.]);
+    }
+
+    #[test]
+    fn iter() {
+        let mat = CsMat::new_csc(
+            (3, 3),
+            vec![0, 1, 3, 4],
+            vec![1, 0, 2, 2],
+            vec![1.; 4],
+        );
+        let mut iter = mat.iter();
+        assert_eq!(iter.next(), Some((&1., (1, 0))));
+        assert_eq!(iter.next(), Some((&1., (0, 1))));
+        assert_eq!(iter.next(), Some((&1., (2, 1))));
+        assert_eq!(iter.next(), Some((&1., (2, 2))));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn degrees() {
+        // | 1 0 0 3 1 |
+        // | 0 2 0 0 0 |
+        // | 0 0 0 1 0 |
+        // | 3 0 1 1 0 |
+        // | 1 0 0 0 1 |
+        let mat = CsMat::new_csc(
+            (5, 5),
+            vec![0, 3, 4, 5, 8, 10],
+            vec![0, 3, 4, 1, 3, 0, 2, 3, 0, 4],
+            vec![1, 3, 1, 2, 1, 3, 1, 1, 1, 1],
+        );
 
-    fn dot(&self, rhs: &ArrayBase<DS2, Ix1>) -> Array<N, Ix1> {
-        Mul::mul(self, rhs)
+        let degrees = mat.degrees();
+        assert_eq!(&degrees, &[2, 0, 1, 2, 1],);
     }
-}
 
-impl<N, I, Iptr, IpS, IS, DS> Index<[usize; 2]>
-    for CsMatBase<N, I, IpS, IS, DS, Iptr>
-where
-    I: SpIndex,
-    Iptr: SpIndex,
-    IpS: Deref<Target = [Iptr]>,
-    IS: Deref<Target = [I]>,
-    DS: Deref<Target = [N]>,
-{
-    type Output = N;
+    #[test]
+    fn onehot_zero() {
+        let onehot: CsMat<f32> = CsMat::zero((3, 3)).to_inner_onehot();
 
-    fn index(&self, index: [usize; 2]) -> &N {
-        let i = index[0];
-        let j = index[1];
-        self.get(i, j).unwrap()
+        assert!(onehot.is_csr());
+        assert_eq!(CsMat::zero((3, 3)), onehot);
     }
-}
 
-impl<N, I, Iptr, IpS, IS, DS> IndexMut<[usize; 2]>
-    for CsMatBase<N, I, IpS, IS, DS, Iptr>
-where
-    I: SpIndex,
-    Iptr: SpIndex,
-    IpS: Deref<Target = [Iptr]>,
-    IS: Deref<Target = [I]>,
-    DS: DerefMut<Target = [N]>,
-{
-    fn index_mut(&mut self, index: [usize; 2]) -> &mut N {
-        let i = index[0];
-        let j = index[1];
-        self.get_mut(i, j).unwrap()
-    }
-}
+    #[test]
+    fn onehot_eye() {
+        let mat = CsMat::new(
+            (2, 2),
+            vec![0, 2, 4],
+            vec![0, 1, 0, 1],
+            vec![2.0, 0.0, 0.0, 2.0],
+        );
 
-impl<N, I, Iptr, IpS, IS, DS> Index<NnzIndex>
-    for CsMatBase<N, I, IpS, IS, DS, Iptr>
-where
-    I: SpIndex,
-    Iptr: SpIndex,
-    IpS: Deref<Target = [Iptr]>,
-    IS: Deref<Target = [I]>,
-    DS: Deref<Target = [N]>,
-{
-    type Output = N;
+        let onehot = mat.to_inner_onehot();
 
-    fn index(&self, index: NnzIndex) -> &N {
-        let NnzIndex(i) = index;
-        self.data().get(i).unwrap()
+        assert!(onehot.is_csr());
+        assert_eq!(CsMat::eye(2), onehot);
     }
-}
 
-impl<N, I, Iptr, IpS, IS, DS> IndexMut<NnzIndex>
-    for CsMatBase<N, I, IpS, IS, DS, Iptr>
-where
-    I: SpIndex,
-    Iptr: SpIndex,
-    IpS: Deref<Target = [Iptr]>,
-    IS: Deref<Target = [I]>,
-    DS: DerefMut<Target = [N]>,
-{
-    fn index_mut(&mut self, index: NnzIndex) -> &mut N {
-        let NnzIndex(i) = index;
-        self.data_mut().get_mut(i).unwrap()
-    }
-}
+    #[test]
+    fn onehot_sparse_csc() {
+        let mat = CsMat::new_csc((2, 3), vec![0, 0, 1, 1], vec![1], vec![2.0]);
 
-impl<N, I, Iptr, IpS, IS, DS> SparseMat for CsMatBase<N, I, IpS, IS, DS, Iptr>
-where
-    I: SpIndex,
-    Iptr: SpIndex,
-    IpS: Deref<Target = [Iptr]>,
-    IS: Deref<Target = [I]>,
-    DS: Deref<Target = [N]>,
-{
-    fn rows(&self) -> usize {
-        self.rows()
-    }
+        let onehot = mat.to_inner_onehot();
 
-    fn cols(&self) -> usize {
-        self.cols()
+        let expected =
+            CsMat::new_csc((2, 3), vec![0, 0, 1, 1], vec![1], vec![1.0]);
+
+        assert!(onehot.is_csc());
+        assert_eq!(expected, onehot);
     }
 
-    fn nnz(&self) -> usize {
-        self.nnz()
+    #[test]
+    fn onehot_ignores_nan() {
+        let mat = CsMat::new(
+            (2, 2),
+            vec![0, 2, 3],
+            vec![0, 1, 1],
+            vec![2.0, std::f64::NAN, 2.0],
+        );
+
+        let onehot = mat.to_inner_onehot();
+
+        assert!(onehot.is_csr());
+        assert_eq!(CsMat::eye(2), onehot);
     }
-}
 
-impl<'a, N, I, Iptr, IpS, IS, DS> SparseMat
-    for &'a CsMatBase<N, I, IpS, IS, DS, Iptr>
-where
-    I: 'a + SpIndex,
-    Iptr: 'a + SpIndex,
-    N: 'a,
-    IpS: Deref<Target = [Iptr]>,
-    IS: Deref<Target = [I]>,
-    DS: Deref<Target = [N]>,
-{
-    fn rows(&self) -> usize {
-        (*self).rows()
+    #[test]
+    fn mul_assign() {
+        let mut m1 = crate::TriMat::new((6, 9));
+        m1.add_triplet(1, 1, 8_i32);
+        m1.add_triplet(1, 2, 7);
+        m1.add_triplet(0, 1, 6);
+        m1.add_triplet(0, 8, 5);
+        m1.add_triplet(4, 2, 4);
+        let mut m1 = m1.to_csr();
+
+        m1 *= 2;
+        for (&v, (j, i)) in m1.iter() {
+            match (j, i) {
+                (1, 1) => assert_eq!(v, 16),
+                (1, 2) => assert_eq!(v, 14),
+                (0, 1) => assert_eq!(v, 12),
+                (0, 8) => assert_eq!(v, 10),
+                (4, 2) => assert_eq!(v, 8),
+                _ => panic!(),
+            }
+        }
     }
 
-    fn cols(&self) -> usize {
-        (*self).cols()
+    #[test]
+    fn div_assign() {
+        let mut m1 = crate::TriMat::new((6, 9));
+        m1.add_triplet(1, 1, 8_i32);
+        m1.add_triplet(1, 2, 7);
+        m1.add_triplet(0, 1, 6);
+        m1.add_triplet(0, 8, 5);
+        m1.add_triplet(4, 2, 4);
+        let mut m1 = m1.to_csr();
+
+        m1 /= 2;
+        for (&v, (j, i)) in m1.iter() {
+            match (j, i) {
+                (1, 1) => assert_eq!(v, 4),
+                (1, 2) => assert_eq!(v, 3),
+                (0, 1) => assert_eq!(v, 3),
+                (0, 8) => assert_eq!(v, 2),
+                (4, 2) => assert_eq!(v, 2),
+                _ => panic!(),
+            }
+        }
     }
 
-    fn nnz(&self) -> usize {
-        (*self).nnz()
+    #[test]
+    fn issue_99() {
+        let a = crate::TriMat::<i32>::new((10, 1)).to_csc();
+        let b = crate::TriMat::<i32>::new((1, 9)).to_csr();
+        let _c = &a * &b;
     }
-}
 
-impl<'a, N, I, IpS, IS, DS, Iptr> IntoIterator
-    for &'a CsMatBase<N, I, IpS, IS, DS, Iptr>
-where
-    I: 'a + SpIndex,
-    Iptr: 'a + SpIndex,
-    N: 'a,
-    IpS: Deref<Target = [Iptr]>,
-    IS: Deref<Target = [I]>,
-    DS: Deref<Target = [N]>,
-{
-    type Item = (&'a N, (I, I));
-    type IntoIter = CsIter<'a, N, I, Iptr>;
-    fn into_iter(self) -> Self::IntoIter {
-        self.iter()
+    #[test]
+    fn transpose_view_matches_transposed_entries() {
+        let a = mat1();
+        let a_t = a.transpose_view();
+        assert!(a_t.is_csc());
+        assert_eq!(a_t.shape(), (a.cols(), a.rows()));
+        // the view shares the storage of the original matrix
+        assert_eq!(a_t.indptr().as_ptr(), a.indptr().as_ptr());
+        assert_eq!(a_t.data().as_ptr(), a.data().as_ptr());
+        for i in 0..a.rows() {
+            for j in 0..a.cols() {
+                assert_eq!(a.get(i, j), a_t.get(j, i));
+            }
+        }
+        for (&val, (row, col)) in a_t.iter() {
+            assert_eq!(a.get(col, row), Some(&val));
+        }
+        assert_eq!(a_t.to_dense(), a.to_dense().t());
+        assert_eq!(a_t.transpose_view(), a.view());
     }
-}
 
-impl<'a, N, I, Iptr> IntoIterator for CsMatViewI<'a, N, I, Iptr>
-where
-    I: 'a + SpIndex,
-    Iptr: 'a + SpIndex,
-    N: 'a,
-{
-    type Item = (&'a N, (I, I));
-    type IntoIter = CsIter<'a, N, I, Iptr>;
-    fn into_iter(self) -> Self::IntoIter {
-        self.iter_rbr()
+    #[test]
+    fn to_other_storage_round_trip() {
+        // | . 1 . . |
+        // | . . . . |
+        // | 2 . 3 4 |
+        // | . 5 . . |
+        let a = CsMat::new(
+            (4, 4),
+            vec![0, 1, 1, 4, 5],
+            vec![1, 0, 2, 3, 1],
+            vec![1, 2, 3, 4, 5],
+        );
+        let a_csc = a.to_other_storage();
+        assert!(a_csc.is_csc());
+        assert_eq!(a_csc.shape(), a.shape());
+        assert_eq!(a_csc.indptr(), &[0, 1, 3, 4, 5]);
+        assert_eq!(a_csc.indices(), &[2, 0, 3, 2, 2]);
+        assert_eq!(a_csc.data(), &[2, 1, 5, 3, 4]);
+        for outer in a_csc.outer_iterator() {
+            assert!(outer.indices().windows(2).all(|x| x[0] < x[1]));
+        }
+        assert_eq!(a_csc.to_dense(), a.to_dense());
+        assert_eq!(a_csc.to_other_storage(), a);
+
+        let b = mat1_csc();
+        assert_eq!(b.to_other_storage().to_other_storage(), b);
     }
-}
 
-/// An iterator over non-overlapping blocks of a matrix,
-/// along the least-varying dimension
-pub struct ChunkOuterBlocks<'a, N: 'a, I: 'a + SpIndex, Iptr: 'a + SpIndex = I>
-{
-    mat: CsMatViewI<'a, N, I, Iptr>,
-    dims_in_bloc: usize,
-    bloc_count: usize,
-}
+    #[test]
+    fn scale_rows_then_matvec() {
+        let x = [1., 2., 3., 4., 5.];
+        let diag = [1., -2., 0.5, 3., 0.];
+        for mat in [mat1(), mat1_csc()].iter() {
+            let ax = mat * &x[..];
+            let mut scaled = mat.clone();
+            scaled.scale_rows(&diag);
+            let res = &scaled * &x[..];
+            let expected: Vec<f64> =
+                diag.iter().zip(ax.iter()).map(|(d, y)| d * y).collect();
+            assert_eq!(res, expected);
+        }
+    }
 
-impl<'a, N: 'a, I: 'a + SpIndex, Iptr: 'a + SpIndex> Iterator
-    for ChunkOuterBlocks<'a, N, I, Iptr>
-{
-    type Item = CsMatViewI<'a, N, I, Iptr>;
-    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
-        let cur_dim = self.dims_in_bloc * self.bloc_count;
-        let end_dim = self.dims_in_bloc + cur_dim;
-        let count = if self.dims_in_bloc == 0 {
-            return None;
-        } else if end_dim > self.mat.outer_dims() {
-            let count = self.mat.outer_dims() - cur_dim;
-            self.dims_in_bloc = 0;
-            count
-        } else {
-            self.dims_in_bloc
-        };
-        let view = self.mat.middle_outer_views(cur_dim, count);
-        self.bloc_count += 1;
-        Some(view)
+    #[test]
+    fn scale_cols_then_matvec() {
+        let x = [1., 2., 3., 4., 5.];
+        let diag = [1., -2., 0.5, 3., 0.];
+        let dx: Vec<f64> =
+            diag.iter().zip(x.iter()).map(|(d, x)| d * x).collect();
+        for mat in [mat1(), mat1_csc()].iter() {
+            let mut scaled = mat.clone();
+            scaled.scale_cols(&diag);
+            assert_eq!(&scaled * &x[..], mat * &dx[..]);
+        }
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::CompressedStorage::{CSC, CSR};
-    use crate::errors::SprsError;
-    use crate::sparse::{CsMat, CsMatI, CsMatView};
-    use crate::test_data::{mat1, mat1_csc, mat1_times_2};
-    use ndarray::{arr2, Array};
+    #[test]
+    #[should_panic]
+    fn scale_rows_dim_mismatch() {
+        let mut mat = mat1();
+        mat.scale_rows(&[1., 2.]);
+    }
 
     #[test]
-    fn test_copy() {
-        let m = mat1();
-        let view1 = m.view();
-        let view2 = view1; // this shouldn't move
-        assert_eq!(view1, view2);
+    fn to_dense_csc_fixture() {
+        let expected = arr2(&[
+            [0., 0., 3., 4., 0.],
+            [0., 0., 0., 2., 5.],
+            [0., 0., 5., 0., 0.],
+            [0., 8., 0., 0., 0.],
+            [0., 0., 0., 7., 0.],
+        ]);
+        assert_eq!(mat1_csc().to_dense(), expected);
+        assert_eq!(mat1().to_dense(), expected);
+        assert_eq!(
+            mat1_csc().to_dense().into_raw_vec(),
+            expected.iter().cloned().collect::<Vec<_>>(),
+        );
     }
 
     #[test]
-    fn test_new_csr_success() {
-        let indptr_ok: &[usize] = &[0, 1, 2, 3];
-        let indices_ok: &[usize] = &[0, 1, 2];
-        let data_ok: &[f64] = &[1., 1., 1.];
-        let m =
-            CsMatView::new_view(CSR, (3, 3), indptr_ok, indices_ok, data_ok);
-        assert!(m.is_ok());
+    fn nnz_and_nnz_outer() {
+        let a = mat1();
+        assert_eq!(a.nnz(), a.data().len());
+        assert_eq!(a.nnz(), 7);
+        let row_nnz: Vec<_> = (0..5).map(|i| a.nnz_outer(i)).collect();
+        assert_eq!(row_nnz, vec![2, 2, 1, 1, 1]);
+        let a = mat1_csc();
+        assert_eq!(a.nnz(), a.data().len());
+        let col_nnz: Vec<_> = (0..5).map(|i| a.nnz_outer(i)).collect();
+        assert_eq!(col_nnz, vec![0, 1, 2, 3, 1]);
+        assert_eq!(col_nnz.iter().sum::<usize>(), a.nnz());
     }
 
     #[test]
     #[should_panic]
-    fn test_new_csr_bad_indptr_length() {
-        let indptr_fail1: &[usize] = &[0, 1, 2];
-        let indices_ok: &[usize] = &[0, 1, 2];
-        let data_ok: &[f64] = &[1., 1., 1.];
-        let res =
-            CsMatView::new_view(CSR, (3, 3), indptr_fail1, indices_ok, data_ok);
-        res.unwrap(); // unreachable
+    fn nnz_outer_out_of_bounds() {
+        let a = mat1();
+        a.nnz_outer(5);
     }
 
     #[test]
-    #[should_panic]
-    fn test_new_csr_out_of_bounds_index() {
-        let indptr_ok: &[usize] = &[0, 1, 2, 3];
-        let data_ok: &[f64] = &[1., 1., 1.];
-        let indices_fail2: &[usize] = &[0, 1, 4];
-        let res =
-            CsMatView::new_view(CSR, (3, 3), indptr_ok, indices_fail2, data_ok);
-        res.unwrap(); //unreachable
+    fn diag_with_missing_entries() {
+        assert_eq!(mat1().diag(), vec![0., 0., 5., 0., 0.]);
+        assert_eq!(mat1_csc().diag(), vec![0., 0., 5., 0., 0.]);
+        assert_eq!(CsMat::<f64>::eye(3).diag(), vec![1.; 3]);
+        // rectangular matrices
+        let a = CsMat::new(
+            (3, 2),
+            vec![0, 1, 2, 3],
+            vec![0, 0, 1],
+            vec![1., 2., 3.],
+        );
+        assert_eq!(a.diag(), vec![1., 0.]);
+        assert_eq!(a.transpose_view().diag(), vec![1., 0.]);
     }
 
     #[test]
-    #[should_panic]
-    fn test_new_csr_bad_nnz_count() {
-        let indices_ok: &[usize] = &[0, 1, 2];
-        let data_ok: &[f64] = &[1., 1., 1.];
-        let indptr_fail2: &[usize] = &[0, 1, 2, 4];
-        let res =
-            CsMatView::new_view(CSR, (3, 3), indptr_fail2, indices_ok, data_ok);
-        res.unwrap(); //unreachable
+    fn trace() {
+        assert_eq!(mat1().trace(), 5.);
+        assert_eq!(mat1_csc().trace(), 5.);
+        assert_eq!(CsMat::<i32>::eye(4).trace(), 4);
+        // no stored diagonal entries
+        let a = CsMat::new((2, 2), vec![0, 1, 2], vec![1, 0], vec![3, 4]);
+        assert_eq!(a.trace(), 0);
+        assert_eq!(CsMat::<f64>::zero((3, 3)).trace(), 0.);
     }
 
     #[test]
     #[should_panic]
-    fn test_new_csr_data_indices_mismatch1() {
-        let indptr_ok: &[usize] = &[0, 1, 2, 3];
-        let data_ok: &[f64] = &[1., 1., 1.];
-        let indices_fail1: &[usize] = &[0, 1];
-        let res =
-            CsMatView::new_view(CSR, (3, 3), indptr_ok, indices_fail1, data_ok);
-        res.unwrap(); //unreachable
+    fn trace_non_square() {
+        let a = CsMat::<f64>::zero((3, 2));
+        a.trace();
+    }
+
+    #[test]
+    fn matrix_norms() {
+        let a = CsMat::new(
+            (3, 4),
+            vec![0, 2, 3, 5],
+            vec![0, 3, 1, 0, 2],
+            vec![1., -2., 3., -4., 2.],
+        );
+        let dense = a.to_dense();
+        let frob = dense.iter().map(|x| x * x).sum::<f64>().sqrt();
+        let col_sums = dense.map(|x| x.abs()).sum_axis(ndarray::Axis(0));
+        let row_sums = dense.map(|x| x.abs()).sum_axis(ndarray::Axis(1));
+        let max =
+            |v: ndarray::Array1<f64>| v.iter().cloned().fold(0., f64::max);
+        assert_eq!(max(col_sums.clone()), 5.);
+        assert_eq!(max(row_sums.clone()), 6.);
+        for mat in [a.clone(), a.to_csc()].iter() {
+            assert_eq!(mat.norm_frobenius(), frob);
+            assert_eq!(mat.norm_1(), max(col_sums.clone()));
+            assert_eq!(mat.norm_inf(), max(row_sums.clone()));
+        }
+        // views only account for their own outer dimensions
+        for mat in [a.clone(), a.to_csc()].iter() {
+            let view = mat.view();
+            let view = view.middle_outer_views(1, 2);
+            let dense = view.to_dense();
+            let frob = dense.iter().map(|x| x * x).sum::<f64>().sqrt();
+            let col_sums = dense.map(|x| x.abs()).sum_axis(ndarray::Axis(0));
+            let row_sums = dense.map(|x| x.abs()).sum_axis(ndarray::Axis(1));
+            assert_eq!(view.norm_frobenius(), frob);
+            assert_eq!(view.norm_1(), max(col_sums));
+            assert_eq!(view.norm_inf(), max(row_sums));
+        }
+        let view = a.view();
+        let view = view.middle_outer_views(1, 1);
+        assert_eq!(view.norm_frobenius(), 3.);
+        assert_eq!(view.norm_1(), 3.);
+        assert_eq!(view.norm_inf(), 3.);
+
+        let zero = CsMat::<f64>::zero((2, 2));
+        assert_eq!(zero.norm_frobenius(), 0.);
+        assert_eq!(zero.norm_1(), 0.);
+        assert_eq!(zero.norm_inf(), 0.);
+    }
+
+    #[test]
+    fn outer_view_matches_outer_iterator() {
+        for mat in [mat1(), mat1_csc()].iter() {
+            for (i, vec) in mat.outer_iterator().enumerate() {
+                let view = mat.outer_view(i).unwrap();
+                assert_eq!(view.dim(), mat.inner_dims());
+                assert!(view.iter().eq(vec.iter()));
+                // the view borrows the matrix storage
+                let start = mat.indptr()[i];
+                assert!(std::ptr::eq(
+                    view.indices().as_ptr(),
+                    mat.indices()[start..].as_ptr()
+                ));
+            }
+            assert!(mat.outer_view(mat.outer_dims()).is_none());
+        }
+    }
+
+    #[test]
+    fn outer_nnz_counts() {
+        for mat in [mat1(), mat1_csc(), CsMat::<f64>::zero((3, 2))].iter() {
+            let counts = mat.outer_nnz_counts();
+            assert_eq!(counts.len(), mat.outer_dims());
+            assert_eq!(counts.iter().sum::<usize>(), mat.nnz());
+            for (i, &count) in counts.iter().enumerate() {
+                assert_eq!(count, mat.nnz_outer(i));
+            }
+        }
+        assert_eq!(mat1().outer_nnz_counts(), vec![2, 2, 1, 1, 1]);
+    }
+
+    #[test]
+    fn permute_outer_inner() {
+        use crate::PermOwned;
+        // | 0 1 2 |
+        // | 3 0 0 |
+        // | 0 0 4 |
+        // | 5 6 0 |
+        let a = CsMat::new(
+            (4, 3),
+            vec![0, 2, 3, 4, 6],
+            vec![1, 2, 0, 2, 0, 1],
+            vec![1, 2, 3, 4, 5, 6],
+        );
+        let row_perm = PermOwned::new(vec![3, 0, 2, 1]);
+        let col_perm = PermOwned::new(vec![2, 0, 1]);
+
+        // P * A: row i of the result is row perm[i] of a
+        let pa = a.permute_outer(row_perm.view());
+        let expected = arr2(&[[5, 6, 0], [0, 1, 2], [0, 0, 4], [3, 0, 0]]);
+        assert_eq!(pa.to_dense(), expected);
+
+        // A * P^T: column j of the result is column perm[j] of a
+        let apt = a.permute_inner(col_perm.view());
+        let expected = arr2(&[[2, 0, 1], [0, 3, 0], [4, 0, 0], [0, 5, 6]]);
+        assert_eq!(apt.to_dense(), expected);
+        assert!(apt.check_compressed_structure().is_ok());
+
+        // same permutations on the other storage
+        let a_csc = a.to_csc();
+        let pa_csc = a_csc.permute_inner(row_perm.view());
+        assert_eq!(pa_csc, pa.to_csc());
+        let apt_csc = a_csc.permute_outer(col_perm.view());
+        assert_eq!(apt_csc, apt.to_csc());
+
+        // applying the inverse permutation gives back the original matrix
+        assert_eq!(pa.permute_outer(row_perm.inv()), a);
+        assert_eq!(apt.permute_inner(col_perm.inv()), a);
+        assert_eq!(a.permute_outer(PermOwned::identity(4).view()), a);
+
+        // permuting a view whose indptr does not start at zero
+        let a_view = a.view();
+        let view = a_view.middle_outer_views(1, 2);
+        let view_pt = view.permute_inner(col_perm.view());
+        assert!(view_pt.check_compressed_structure().is_ok());
+        let expected = arr2(&[[0, 3, 0], [4, 0, 0]]);
+        assert_eq!(view_pt.to_dense(), expected);
     }
 
     #[test]
     #[should_panic]
-    fn test_new_csr_data_indices_mismatch2() {
-        let indptr_ok: &[usize] = &[0, 1, 2, 3];
-        let indices_ok: &[usize] = &[0, 1, 2];
-        let data_fail1: &[f64] = &[1., 1., 1., 1.];
-        let res =
-            CsMatView::new_view(CSR, (3, 3), indptr_ok, indices_ok, data_fail1);
-        res.unwrap(); //unreachable
+    fn permute_outer_dim_mismatch() {
+        let a = mat1();
+        let perm = crate::PermOwned::new(vec![1, 0]);
+        a.permute_outer(perm.view());
     }
 
     #[test]
     #[should_panic]
-    fn test_new_csr_data_indices_mismatch3() {
-        let indptr_ok: &[usize] = &[0, 1, 2, 3];
-        let indices_ok: &[usize] = &[0, 1, 2];
-        let data_fail2: &[f64] = &[1., 1.];
-        let res =
-            CsMatView::new_view(CSR, (3, 3), indptr_ok, indices_ok, data_fail2);
-        res.unwrap(); //unreachable
+    fn permute_invalid_permutation() {
+        let _ = crate::PermOwned::new(vec![1, 1, 0]);
     }
 
     #[test]
-    fn test_new_csr_fails() {
-        let indices_ok: &[usize] = &[0, 1, 2];
-        let data_ok: &[f64] = &[1., 1., 1.];
-        let indptr_fail3: &[usize] = &[0, 2, 1, 3];
-        assert_eq!(
-            CsMatView::new_view(CSR, (3, 3), indptr_fail3, indices_ok, data_ok),
-            Err(SprsError::UnsortedIndptr)
+    fn permute_symmetric() {
+        use crate::PermOwned;
+        // | 1 2 0 0 |
+        // | 2 3 0 4 |
+        // | 0 0 5 6 |
+        // | 0 4 6 7 |
+        let a = CsMat::new(
+            (4, 4),
+            vec![0, 2, 5, 7, 10],
+            vec![0, 1, 0, 1, 3, 2, 3, 1, 2, 3],
+            vec![1, 2, 2, 3, 4, 5, 6, 4, 6, 7],
         );
+        assert!(crate::is_symmetric(&a));
+        let perm = PermOwned::new(vec![2, 0, 3, 1]);
+        for mat in [a.clone(), a.to_csc()].iter() {
+            let papt = mat.permute_symmetric(perm.view());
+            assert!(crate::is_symmetric(&papt));
+            assert_eq!(papt.storage(), mat.storage());
+            // diagonal entries move to the permuted positions
+            assert_eq!(papt.diag(), vec![5, 1, 7, 3]);
+            for i in 0..4 {
+                for j in 0..4 {
+                    assert_eq!(papt.get(i, j), mat.get(perm.at(i), perm.at(j)));
+                }
+            }
+            // same result as permuting rows then columns
+            let expected =
+                mat.permute_outer(perm.view()).permute_inner(perm.view());
+            assert_eq!(papt, expected);
+            assert_eq!(&papt.permute_symmetric(perm.inv()), mat);
+        }
     }
 
     #[test]
-    fn test_new_csr_fail_indices_ordering() {
-        let indptr: &[usize] = &[0, 2, 4, 5, 6, 7];
-        // good indices would be [2, 3, 3, 4, 2, 1, 3];
-        let indices: &[usize] = &[3, 2, 3, 4, 2, 1, 3];
-        let data: &[f64] = &[
-            0.35310881, 0.42380633, 0.28035896, 0.58082095, 0.53350123,
-            0.88132896, 0.72527863,
-        ];
-        assert_eq!(
-            CsMatView::new_view(CSR, (5, 5), indptr, indices, data),
-            Err(SprsError::NonSortedIndices)
+    fn eye_structure_and_product() {
+        let eye: CsMat<f64> = CsMat::eye(4);
+        assert_eq!(eye.indptr(), &[0, 1, 2, 3, 4]);
+        assert_eq!(eye.indices(), &[0, 1, 2, 3]);
+        assert_eq!(eye.data(), &[1.; 4]);
+        let x = [1., -2., 3., 0.5];
+        assert_eq!(&eye * &x[..], x.to_vec());
+        assert_eq!(&CsMat::<f64>::eye_csc(4) * &x[..], x.to_vec());
+
+        // A + 2I
+        let a = mat1();
+        let shifted = &a + &(&CsMat::<f64>::eye(5) * 2.);
+        assert_eq!(shifted.diag(), vec![2., 2., 7., 2., 2.]);
+
+        let empty: CsMat<f64> = CsMat::eye(0);
+        assert_eq!(empty.shape(), (0, 0));
+        assert_eq!(empty.nnz(), 0);
+    }
+
+    #[test]
+    fn from_diag_round_trip() {
+        let v = vec![1., -2., 3., 4.];
+        let mat: CsMat<f64> = CsMat::from_diag(&v);
+        assert_eq!(mat.shape(), (4, 4));
+        assert_eq!(mat.nnz(), 4);
+        assert_eq!(mat.diag(), v);
+        assert!(mat.check_compressed_structure().is_ok());
+
+        let v = vec![0., 2., 0., 5.];
+        let mat: CsMat<f64> = CsMat::from_diag(&v);
+        assert_eq!(mat.nnz(), 2);
+        assert_eq!(mat.indptr(), &[0, 0, 1, 1, 2]);
+        assert_eq!(mat.indices(), &[1, 3]);
+        assert_eq!(mat.diag(), v);
+        assert_eq!(CsMat::from_diag(&[1.; 3]), CsMat::<f64>::eye(3));
+    }
+
+    #[test]
+    fn logical_equality() {
+        let a = CsMat::new((2, 3), vec![0, 1, 3], vec![1, 0, 2], vec![1, 2, 3]);
+        // same matrix, with an explicitly stored zero
+        let b = CsMat::new(
+            (2, 3),
+            vec![0, 2, 4],
+            vec![0, 1, 0, 2],
+            vec![0, 1, 2, 3],
+        );
+        assert!(a != b);
+        assert!(a.logically_eq(&b));
+        assert!(b.logically_eq(&a));
+        // different storage, also with an explicit zero
+        let c = CsMat::new_csc(
+            (2, 3),
+            vec![0, 1, 3, 4],
+            vec![1, 0, 1, 1],
+            vec![2, 1, 0, 3],
         );
+        assert!(a.logically_eq(&c));
+        assert!(c.logically_eq(&a));
+        assert!(a.view().logically_eq(&c.view()));
+        assert!(mat1().logically_eq(&mat1_csc()));
+
+        // differences in values, non-zeros or shapes
+        let d = CsMat::new((2, 3), vec![0, 1, 3], vec![1, 0, 2], vec![1, 2, 4]);
+        assert!(!a.logically_eq(&d));
+        assert!(!a.to_csc().logically_eq(&d));
+        let e = CsMat::new(
+            (2, 3),
+            vec![0, 2, 4],
+            vec![0, 1, 0, 2],
+            vec![5, 1, 2, 3],
+        );
+        assert!(!a.logically_eq(&e));
+        assert!(!e.logically_eq(&a));
+        assert!(!c.logically_eq(&e));
+        assert!(!e.logically_eq(&c));
+        let f = CsMat::new((2, 4), vec![0, 1, 3], vec![1, 0, 2], vec![1, 2, 3]);
+        assert!(!a.logically_eq(&f));
     }
 
     #[test]
-    fn test_new_csr_csc_success() {
-        let indptr_ok: &[usize] = &[0, 2, 5, 6];
-        let indices_ok: &[usize] = &[2, 3, 1, 2, 3, 3];
-        let data_ok: &[f64] = &[
-            0.05734571, 0.15543348, 0.75628258, 0.83054515, 0.71851547,
-            0.46202352,
-        ];
-        assert!(CsMatView::new_view(
-            CSR,
-            (3, 4),
-            indptr_ok,
-            indices_ok,
-            data_ok
-        )
-        .is_ok());
-        assert!(CsMatView::new_view(
-            CSC,
-            (4, 3),
-            indptr_ok,
-            indices_ok,
-            data_ok
-        )
-        .is_ok());
+    fn display() {
+        let expected = "5x5 CSR matrix with 7 non-zeros\n\
+                        (0, 2): 3\n\
+                        (0, 3): 4\n\
+                        (1, 3): 2\n\
+                        (1, 4): 5\n\
+                        (2, 2): 5\n\
+                        (3, 1): 8\n\
+                        (4, 3): 7\n";
+        assert_eq!(format!("{}", mat1()), expected);
+        let printed = format!("{}", mat1_csc());
+        assert!(printed.starts_with("5x5 CSC matrix with 7 non-zeros\n"));
+        assert!(printed.contains("(3, 1): 8\n"));
+
+        // large matrices are truncated
+        let eye: CsMat<i32> = CsMat::eye(30);
+        let printed = format!("{}", eye);
+        assert!(printed.contains("(19, 19): 1\n"));
+        assert!(!printed.contains("(20, 20): 1\n"));
+        assert!(printed.ends_with("...\n"));
+    }
+
+    #[test]
+    fn slice_outer() {
+        for mat in [mat1(), mat1_csc()].iter() {
+            let slice = mat.slice_outer(1..4);
+            assert_eq!(slice.outer_dims(), 3);
+            assert_eq!(slice.inner_dims(), 5);
+            assert!(slice.check_compressed_structure().is_ok());
+            for (i, vec) in slice.outer_iterator().enumerate() {
+                assert!(vec.iter().eq(mat.outer_view(i + 1).unwrap().iter()));
+            }
+            let nnz: usize = (1..4).map(|i| mat.nnz_outer(i)).sum();
+            assert_eq!(slice.nnz(), nnz);
+            assert_eq!(slice.data().len(), nnz);
+
+            let empty = mat.slice_outer(2..2);
+            assert_eq!(empty.outer_dims(), 0);
+            assert_eq!(empty.nnz(), 0);
+        }
+        assert_eq!(mat1().slice_outer(1..4).shape(), (3, 5));
+        assert_eq!(mat1_csc().slice_outer(1..4).shape(), (5, 3));
     }
 
     #[test]
     #[should_panic]
-    fn test_new_csc_bad_indptr_length() {
-        let indptr_ok: &[usize] = &[0, 2, 5, 6];
-        let indices_ok: &[usize] = &[2, 3, 1, 2, 3, 3];
-        let data_ok: &[f64] = &[
-            0.05734571, 0.15543348, 0.75628258, 0.83054515, 0.71851547,
-            0.46202352,
-        ];
-        let res =
-            CsMatView::new_view(CSC, (3, 4), indptr_ok, indices_ok, data_ok);
-        res.unwrap(); //unreachable
+    fn slice_outer_out_of_bounds() {
+        let mat = mat1();
+        mat.slice_outer(3..6);
     }
 
     #[test]
-    fn test_new_csr_vec_borrowed() {
-        let indptr_ok = vec![0, 1, 2, 3];
-        let indices_ok = vec![0, 1, 2];
-        let data_ok: Vec<f64> = vec![1., 1., 1.];
-        assert!(CsMatView::new_view(
-            CSR,
-            (3, 3),
-            &indptr_ok,
-            &indices_ok,
-            &data_ok
-        )
-        .is_ok());
+    fn submatrix() {
+        let dense = mat1().to_dense();
+        for mat in [mat1(), mat1_csc()].iter() {
+            let sub = mat.submatrix(1..4, 2..5);
+            assert_eq!(sub.storage(), mat.storage());
+            assert_eq!(sub.to_dense(), dense.slice(s![1..4, 2..5]));
+            assert!(sub.check_compressed_structure().is_ok());
+            let sub = mat.submatrix(0..5, 0..0);
+            assert_eq!(sub.shape(), (5, 0));
+            assert_eq!(sub.nnz(), 0);
+        }
     }
 
     #[test]
-    fn test_new_csr_vec_owned() {
-        let indptr_ok = vec![0, 1, 2, 3];
-        let indices_ok = vec![0, 1, 2];
-        let data_ok: Vec<f64> = vec![1., 1., 1.];
-        assert!(
-            CsMat::new_(CSR, (3, 3), indptr_ok, indices_ok, data_ok).is_ok()
+    fn middle_outer_views_csc_shape() {
+        let mat = mat1_csc();
+        let view = mat.view();
+        let middle = view.middle_outer_views(1, 2);
+        assert_eq!(middle.shape(), (5, 2));
+    }
+
+    #[test]
+    fn map_square_keeps_structure() {
+        let mat = mat1();
+        let squared = mat.map(|&x| x * x);
+        assert_eq!(squared.indptr(), mat.indptr());
+        assert_eq!(squared.indices(), mat.indices());
+        assert_eq!(squared.data(), &[9., 16., 4., 25., 25., 64., 49.]);
+        // structural zeros are not touched
+        let shifted = mat.map(|&x| x + 1.);
+        assert_eq!(shifted.nnz(), mat.nnz());
+        assert_eq!(shifted.get(0, 0), None);
+        // explicit zeros are kept in storage
+        let zeroed = mat.map(|_| 0.);
+        assert_eq!(zeroed.nnz(), mat.nnz());
+    }
+
+    #[test]
+    fn prune_thresholds() {
+        let mat = mat1();
+        // mat1 values lie in [2, 8]
+        let empty = mat.prune(|_, _, &x| x.abs() > 10.);
+        assert_eq!(empty.shape(), mat.shape());
+        assert_eq!(empty.nnz(), 0);
+        assert_eq!(empty.indptr(), &[0; 6]);
+
+        let same = mat.prune(|_, _, &x| x.abs() > 1.);
+        assert_eq!(same.indptr(), mat.indptr());
+        assert_eq!(same.indices(), mat.indices());
+        assert_eq!(same.data(), mat.data());
+
+        let mid = mat.prune(|_, _, &x| x.abs() >= 5.);
+        let expected = CsMat::new(
+            (5, 5),
+            vec![0, 0, 1, 2, 3, 4],
+            vec![4, 2, 1, 3],
+            vec![5., 5., 8., 7.],
         );
+        assert_eq!(mid.indptr(), expected.indptr());
+        assert_eq!(mid, expected);
     }
 
     #[test]
-    fn test_csr_from_dense() {
-        let m = Array::eye(3);
-        let m_sparse = CsMat::csr_from_dense(m.view(), 0.);
+    fn prune_uses_coordinates() {
+        let mat = mat1_csc();
+        assert!(mat.is_csc());
+        let upper = mat.prune(|row, col, _| row < col);
+        assert!(upper.is_csc());
+        for (_, (row, col)) in upper.iter() {
+            assert!(row < col);
+        }
+        // the only entries of mat1 on or below the diagonal are (2, 2),
+        // (3, 1) and (4, 3)
+        assert_eq!(upper.nnz(), mat.nnz() - 3);
+    }
 
-        assert_eq!(m_sparse, CsMat::eye(3));
+    #[test]
+    fn try_new_sorts_indices() {
+        // | 0 2 1 |
+        // | 3 0 4 |
+        let mat = CsMat::try_new(
+            (2, 3),
+            vec![0, 2, 4],
+            vec![2, 1, 2, 0],
+            vec![1, 2, 4, 3],
+        )
+        .unwrap();
+        assert_eq!(mat.indices(), &[1, 2, 0, 2]);
+        assert_eq!(mat.data(), &[2, 1, 3, 4]);
+        assert_eq!(mat.get(0, 1), Some(&2));
+        assert_eq!(mat.get(0, 2), Some(&1));
+        assert_eq!(mat.get(1, 0), Some(&3));
+        let entries: Vec<_> = mat.iter().map(|(&v, ij)| (ij, v)).collect();
+        assert_eq!(
+            entries,
+            vec![((0, 1), 2), ((0, 2), 1), ((1, 0), 3), ((1, 2), 4)]
+        );
+        // duplicate entries cannot be sorted
+        assert_eq!(
+            CsMat::try_new((2, 3), vec![0, 2, 2], vec![1, 1], vec![1, 2]),
+            Err(SprsError::NonSortedIndices)
+        );
+    }
 
-        let m = arr2(&[
-            [1., 0., 2., 1e-7, 1.],
-            [0., 0., 0., 1., 0.],
-            [3., 0., 1., 0., 0.],
-        ]);
-        let m_sparse = CsMat::csr_from_dense(m.view(), 1e-5);
+    #[test]
+    fn try_new_bad_indptr_no_panic() {
+        // an invalid indptr is reported rather than used for sorting
+        let res = CsMat::try_new((2, 2), vec![0, 3, 2], vec![1, 0], vec![1, 1]);
+        assert!(res.is_err());
+        let res = CsMat::try_new((2, 2), vec![0, 1, 5], vec![1, 0], vec![1, 1]);
+        assert!(res.is_err());
+        let res = CsMat::try_new((2, 2), vec![0, 1], vec![1, 0], vec![1, 1]);
+        assert!(res.is_err());
+        let res = CsMatI::<i32, i32>::try_new(
+            (2, 2),
+            vec![-1, 1, 2],
+            vec![1, 0],
+            vec![1, 1],
+        );
+        assert!(res.is_err());
+    }
 
-        let expected_output = CsMat::new(
-            (3, 5),
-            vec![0, 3, 4, 6],
-            vec![0, 2, 4, 3, 0, 2],
-            vec![1., 2., 1., 1., 3., 1.],
+    #[test]
+    fn sort_indices() {
+        let mut mat = CsMat::new_trusted(
+            CSC,
+            (3, 2),
+            vec![0, 3, 4],
+            vec![2, 0, 1, 2],
+            vec![1., 2., 3., 4.],
+        );
+        assert_eq!(
+            mat.check_compressed_structure(),
+            Err(SprsError::NonSortedIndices)
+        );
+        mat.sort_indices();
+        assert!(mat.check_compressed_structure().is_ok());
+        assert_eq!(mat.indices(), &[0, 1, 2, 2]);
+        assert_eq!(mat.data(), &[2., 3., 1., 4.]);
+        assert_eq!(mat.get(2, 0), Some(&1.));
+    }
+
+    #[test]
+    fn check_structure_failures() {
+        use crate::errors::StructureError;
+        let check = |storage, shape, indptr: &[i32], indices: &[i32]| {
+            let data = vec![1.; indices.len()];
+            CsMatI::<f64, i32>::new_trusted(
+                storage,
+                shape,
+                indptr.to_vec(),
+                indices.to_vec(),
+                data,
+            )
+            .check_structure()
+        };
+        assert_eq!(check(CSR, (2, 3), &[0, 1, 2], &[0, 2]), Ok(()));
+        assert_eq!(
+            check(CSR, (2, 3), &[0, 1, 2], &[-1, 2]),
+            Err(StructureError::IndexConversion)
+        );
+        assert_eq!(
+            check(CSC, (2, 3), &[0, 1, 2], &[0, 1]),
+            Err(StructureError::IndptrLength {
+                expected: 4,
+                found: 3
+            })
+        );
+        assert_eq!(
+            check(CSR, (2, 3), &[1, 1, 2], &[0, 2]),
+            Err(StructureError::IndptrStart)
+        );
+        assert_eq!(
+            check(CSR, (2, 3), &[0, 2, 1], &[0, 2]),
+            Err(StructureError::UnsortedIndptr)
+        );
+        assert_eq!(
+            check(CSR, (2, 3), &[0, 1, 3], &[0, 2]),
+            Err(StructureError::IndptrEnd { nnz: 3, indices: 2 })
+        );
+        assert_eq!(
+            check(CSR, (2, 3), &[0, 2, 2], &[2, 0]),
+            Err(StructureError::UnsortedIndices { outer: 0 })
+        );
+        assert_eq!(
+            check(CSR, (2, 3), &[0, 1, 3], &[0, 1, 1]),
+            Err(StructureError::UnsortedIndices { outer: 1 })
+        );
+        assert_eq!(
+            check(CSR, (2, 3), &[0, 1, 2], &[0, 3]),
+            Err(StructureError::OutOfBoundsIndex { outer: 1, index: 3 })
         );
 
-        assert_eq!(m_sparse, expected_output);
+        let mat = CsMatI::<f64, i32>::new_trusted(
+            CSR,
+            (2, 3),
+            vec![0, 1, 2],
+            vec![0, 2],
+            vec![1.],
+        );
+        assert_eq!(
+            mat.check_structure(),
+            Err(StructureError::IndicesDataLength {
+                indices: 2,
+                data: 1
+            })
+        );
+        // the coarser check maps to the historical errors
+        assert!(matches!(
+            mat.check_compressed_structure(),
+            Err(SprsError::IllegalArguments(_))
+        ));
     }
 
     #[test]
-    fn test_csc_from_dense() {
-        let m = Array::eye(3);
-        let m_sparse = CsMat::csc_from_dense(m.view(), 0.);
-
-        assert_eq!(m_sparse, CsMat::eye_csc(3));
-
-        let m = arr2(&[
-            [1., 0., 2., 1e-7, 1.],
-            [0., 0., 0., 1., 0.],
-            [3., 0., 1., 0., 0.],
-        ]);
-        let m_sparse = CsMat::csc_from_dense(m.view(), 1e-5);
-
-        let expected_output = CsMat::new_csc(
-            (3, 5),
-            vec![0, 2, 2, 4, 5, 6],
-            vec![0, 2, 0, 2, 1, 0],
-            vec![1., 3., 2., 1., 1., 1.],
+    fn dedup() {
+        // | 1+2   0  4 |
+        // |   0 3+5  0 |
+        // |   0   0  0 |
+        let mut mat = CsMat::new_trusted(
+            CSR,
+            (3, 3),
+            vec![0, 3, 5, 5],
+            vec![2, 0, 0, 1, 1],
+            vec![4, 1, 2, 3, 5],
         );
+        mat.dedup();
+        assert!(mat.check_compressed_structure().is_ok());
+        assert_eq!(mat.indptr(), &[0, 2, 3, 3]);
+        assert_eq!(mat.indices(), &[0, 2, 1]);
+        assert_eq!(mat.data(), &[3, 4, 8]);
 
-        assert_eq!(m_sparse, expected_output);
+        // no-op on a valid matrix
+        let mut mat = mat1();
+        mat.dedup();
+        assert_eq!(mat.indptr(), mat1().indptr());
+        assert_eq!(mat.indices(), mat1().indices());
+        assert_eq!(mat.data(), mat1().data());
     }
 
     #[test]
-    fn owned_csr_unsorted_indices() {
-        let indptr = vec![0, 3, 3, 5, 6, 7];
-        let indices_sorted = &[1, 2, 3, 2, 3, 4, 4];
-        let indices_shuffled = vec![1, 3, 2, 2, 3, 4, 4];
-        let mut data: Vec<i32> = (0..7).collect();
-        let m = CsMat::new((5, 5), indptr, indices_shuffled, data.clone());
-        assert_eq!(m.indices(), indices_sorted);
-        data.swap(1, 2);
-        assert_eq!(m.data(), &data[..]);
+    fn conj_transpose() {
+        use num_complex::Complex64;
+        let i = Complex64::i();
+        let one = Complex64::new(1., 0.);
+        // | 1   i |
+        // | 0 1-i |
+        let mat = CsMat::new(
+            (2, 2),
+            vec![0, 2, 3],
+            vec![0, 1, 1],
+            vec![one, i, one - i],
+        );
+        let mat_h = mat.conj_transpose();
+        assert!(mat_h.is_csc());
+        assert_eq!(mat_h.get(0, 0), Some(&one));
+        assert_eq!(mat_h.get(1, 0), Some(&-i));
+        assert_eq!(mat_h.get(0, 1), None);
+        assert_eq!(mat_h.get(1, 1), Some(&(one + i)));
+        // the hermitian transpose is an involution
+        assert_eq!(mat_h.conj_transpose(), mat);
+        // A^H A is hermitian
+        let prod = &mat_h.to_csr() * &mat;
+        assert!(prod.conj_transpose().logically_eq(&prod));
     }
 
     #[test]
-    fn new_csr_with_empty_row() {
-        let indptr: &[usize] = &[0, 3, 3, 5, 6, 7];
-        let indices: &[usize] = &[1, 2, 3, 2, 3, 4, 4];
-        let data: &[f64] = &[
-            0.75672424, 0.1649078, 0.30140296, 0.10358244, 0.6283315,
-            0.39244208, 0.57202407,
-        ];
-        assert!(CsMatView::new_view(CSR, (5, 5), indptr, indices, data).is_ok());
+    fn cast() {
+        let mat: CsMat<f32> = CsMat::new(
+            (2, 3),
+            vec![0, 2, 3],
+            vec![0, 2, 1],
+            vec![0.1, -3.5, 1e-7],
+        );
+        let mat64: CsMat<f64> = mat.cast();
+        assert_eq!(mat64.indptr(), mat.indptr());
+        assert_eq!(mat64.indices(), mat.indices());
+        for (&x, &y) in mat.data().iter().zip(mat64.data()) {
+            assert_eq!(x as f64, y);
+            assert!((y - x as f64).abs() <= std::f32::EPSILON as f64 * y.abs());
+            assert_eq!(y as f32, x);
+        }
+
+        let mat: CsMat<u8> = CsMat::eye(3);
+        let mat_f: CsMat<f64> = mat.cast();
+        assert_eq!(mat_f, CsMat::eye(3));
     }
 
     #[test]
-    fn csr_to_csc() {
-        let a = mat1();
-        let a_csc_ground_truth = mat1_csc();
-        let a_csc = a.to_other_storage();
-        assert_eq!(a_csc, a_csc_ground_truth);
+    fn try_cast() {
+        let mat: CsMat<i64> =
+            CsMat::new((2, 2), vec![0, 1, 2], vec![1, 0], vec![-5, 1 << 20]);
+        let narrow: CsMat<i32> = mat.try_cast().unwrap();
+        assert_eq!(narrow.data(), &[-5, 1 << 20]);
+        assert!(mat.try_cast::<i16>().is_err());
+        assert!(mat.try_cast::<u32>().is_err());
+
+        // the values outside of a view are not converted
+        let view = mat.view();
+        let view = view.middle_outer_views(0, 1);
+        let narrow: CsMat<i16> = view.try_cast().unwrap();
+        assert_eq!(narrow.indptr(), &[0, 1]);
+        assert_eq!(narrow.indices(), &[1]);
+        assert_eq!(narrow.data(), &[-5]);
+        let view = mat.view();
+        let view = view.middle_outer_views(1, 1);
+        let narrow: CsMat<i32> = view.try_cast().unwrap();
+        assert!(narrow.check_compressed_structure().is_ok());
+        assert_eq!(narrow.indptr(), &[0, 1]);
+        assert_eq!(narrow.indices(), &[0]);
+        assert_eq!(narrow.data(), &[1 << 20]);
     }
 
     #[test]
-    fn test_self_smul() {
-        let mut a = mat1();
-        a.scale(2.);
-        let c_true = mat1_times_2();
-        assert_eq!(a.indptr(), c_true.indptr());
-        assert_eq!(a.indices(), c_true.indices());
-        assert_eq!(a.data(), c_true.data());
+    fn mul_transpose_vec() {
+        let x = [1., -2., 0.5, 3., 4.];
+        for mat in &[mat1(), mat1_csc(), crate::test_data::mat3()] {
+            let x = &x[..mat.rows()];
+            let mut res = vec![42.; mat.cols()];
+            mat.mul_transpose_vec(x, &mut res);
+            let mut mat_t = mat.to_other_storage();
+            mat_t.transpose_mut();
+            let expected = &mat_t * &ndarray::arr1(x);
+            assert_eq!(res, expected.to_vec());
+        }
     }
 
     #[test]
-    fn outer_block_iter() {
-        let mat: CsMat<f64> = CsMat::eye(11);
-        let mut block_iter = mat.outer_block_iter(3);
-        assert_eq!(block_iter.next().unwrap().rows(), 3);
-        assert_eq!(block_iter.next().unwrap().rows(), 3);
-        assert_eq!(block_iter.next().unwrap().rows(), 3);
-        assert_eq!(block_iter.next().unwrap().rows(), 2);
-        assert_eq!(block_iter.next(), None);
+    #[should_panic(expected = "Dimension mismatch")]
+    fn mul_transpose_vec_fail_dim_mismatch() {
+        let mat = crate::test_data::mat3();
+        let mut res = vec![0.; 4];
+        mat.mul_transpose_vec(&[1.; 4], &mut res);
+    }
 
-        let mut block_iter = mat.outer_block_iter(4);
-        assert_eq!(block_iter.next().unwrap().cols(), 11);
-        block_iter.next().unwrap();
-        block_iter.next().unwrap();
-        assert_eq!(block_iter.next(), None);
+    #[test]
+    fn sum_and_sum_outer() {
+        let mat = mat1();
+        assert_eq!(mat.sum(), 34.);
+        let row_sums = mat.sum_outer();
+        assert_eq!(row_sums, vec![7., 7., 5., 8., 7.]);
+        assert_eq!(row_sums.iter().sum::<f64>(), mat.sum());
+        let ones = Array::ones(5);
+        assert_eq!(row_sums, (&mat * &ones).to_vec());
+
+        let mat = mat1_csc();
+        let col_sums = mat.sum_outer();
+        assert_eq!(col_sums.iter().sum::<f64>(), mat.sum());
+        let dense = mat.to_dense();
+        assert_eq!(col_sums, dense.sum_axis(ndarray::Axis(0)).to_vec());
+        assert_eq!(mat.sum(), dense.sum());
+
+        // a view only sums its own outer dimensions
+        let mat = mat1();
+        let view = mat.view();
+        let view = view.middle_outer_views(1, 2);
+        assert_eq!(view.sum(), 12.);
+        assert_eq!(view.sum(), view.to_dense().sum());
+
+        assert_eq!(CsMat::<f64>::zero((3, 2)).sum(), 0.);
+        assert_eq!(CsMat::<f64>::zero((3, 2)).sum_outer(), vec![0.; 3]);
     }
 
     #[test]
-    fn nnz_index() {
-        let mat: CsMat<f64> = CsMat::eye(11);
+    fn max_min() {
+        // |  0  -2   3 |
+        // | -5   0   3 |
+        let mat = CsMat::new(
+            (2, 3),
+            vec![0, 2, 4],
+            vec![1, 2, 0, 2],
+            vec![-2i32, 3, -5, 3],
+        );
+        assert_eq!(mat.max(), Some((0, 2, 3)));
+        assert_eq!(mat.min(), Some((1, 0, -5)));
+        let mat_csc = mat.to_csc();
+        assert_eq!(mat_csc.max(), Some((0, 2, 3)));
+        assert_eq!(mat_csc.min(), Some((1, 0, -5)));
 
-        assert_eq!(mat.nnz_index(2, 3), None);
-        assert_eq!(mat.nnz_index(5, 7), None);
-        assert_eq!(mat.nnz_index(0, 11), None);
-        assert_eq!(mat.nnz_index(0, 0), Some(super::NnzIndex(0)));
-        assert_eq!(mat.nnz_index(7, 7), Some(super::NnzIndex(7)));
-        assert_eq!(mat.nnz_index(10, 10), Some(super::NnzIndex(10)));
+        // stored values only, the implicit zeros are not considered
+        let neg = mat.map(|&x| -x.abs());
+        assert_eq!(neg.max(), Some((0, 1, -2)));
 
-        let index = mat.nnz_index(8, 8).unwrap();
-        assert_eq!(mat[index], 1.);
-        let mut mat = mat;
-        mat[index] = 2.;
-        assert_eq!(mat[index], 2.);
+        assert_eq!(mat1().max(), Some((3, 1, 8.)));
+        assert_eq!(mat1().min(), Some((1, 3, 2.)));
+        assert_eq!(CsMat::<f64>::zero((2, 2)).max(), None);
+        assert_eq!(CsMat::<f64>::zero((2, 2)).min(), None);
     }
 
     #[test]
-    fn index() {
-        // | 0 2 0 |
-        // | 1 0 0 |
-        // | 0 3 4 |
+    fn abs() {
+        // | -1  0  2 |
+        // |  0 -3  0 |
         let mat = CsMat::new_csc(
-            (3, 3),
-            vec![0, 1, 3, 4],
-            vec![1, 0, 2, 2],
-            vec![1., 2., 3., 4.],
+            (2, 3),
+            vec![0, 1, 2, 3],
+            vec![0, 1, 0],
+            vec![-1., -3., 2.],
         );
-        assert_eq!(mat[[1, 0]], 1.);
-        assert_eq!(mat[[0, 1]], 2.);
-        assert_eq!(mat[[2, 1]], 3.);
-        assert_eq!(mat[[2, 2]], 4.);
-        assert_eq!(mat.get(0, 0), None);
-        assert_eq!(mat.get(4, 4), None);
+        let abs = mat.abs();
+        assert!(abs.is_csc());
+        assert_eq!(abs.indptr(), mat.indptr());
+        assert_eq!(abs.indices(), mat.indices());
+        assert_eq!(abs.data(), &[1., 3., 2.]);
+        assert_eq!(abs.sum(), 6.);
+        assert_eq!(abs.max(), Some((1, 1, 3.)));
+        assert_eq!(mat.norm_1(), abs.norm_1());
+        assert_eq!(mat.norm_inf(), 3.);
     }
 
     #[test]
-    fn get_mut() {
-        // | 0 1 0 |
-        // | 1 0 0 |
-        // | 0 1 1 |
-        let mut mat = CsMat::new_csc(
-            (3, 3),
-            vec![0, 1, 3, 4],
-            vec![1, 0, 2, 2],
-            vec![1.; 4],
-        );
-
-        *mat.get_mut(2, 1).unwrap() = 3.;
+    fn with_capacity() {
+        let mut mat: CsMat<f64> = CsMat::with_capacity(CSC, 3, 4, 6);
+        assert_eq!(mat.shape(), (3, 0));
+        assert_eq!(mat.nnz(), 0);
+        let indptr_ptr = mat.indptr().as_ptr();
+        let indices_ptr = mat.indices().as_ptr();
+        let data_ptr = mat.data().as_ptr();
+        for _ in 0..3 {
+            mat = mat.append_outer(&[1., 0., 2.]);
+        }
+        // the reserved storage was large enough
+        assert_eq!(mat.indptr().as_ptr(), indptr_ptr);
+        assert_eq!(mat.indices().as_ptr(), indices_ptr);
+        assert_eq!(mat.data().as_ptr(), data_ptr);
+        mat.reserve_nnz(3);
+        let indices_ptr = mat.indices().as_ptr();
+        mat = mat.append_outer(&[3., 4., 5.]);
+        assert_eq!(mat.indices().as_ptr(), indices_ptr);
+        assert_eq!(mat.shape(), (3, 4));
+        assert_eq!(mat.nnz(), 9);
+    }
 
-        let exp = CsMat::new_csc(
-            (3, 3),
-            vec![0, 1, 3, 4],
-            vec![1, 0, 2, 2],
-            vec![1., 1., 3., 1.],
-        );
+    #[test]
+    fn append_outer_csvec_matches_triplets() {
+        let n = 50;
+        let mut triplets = crate::sparse::TriMat::new((n, n));
+        let mut mat = CsMat::with_capacity(CSR, n, n, 0);
+        for i in 0..n {
+            let inds: Vec<usize> =
+                (0..n).filter(|j| (i * j) % 7 == 3).collect();
+            let vals: Vec<f64> = inds.iter().map(|&j| (i + j) as f64).collect();
+            for (&j, &v) in inds.iter().zip(vals.iter()) {
+                triplets.add_triplet(i, j, v);
+            }
+            let row = crate::sparse::CsVec::new(n, inds, vals);
+            mat = mat.append_outer_csvec(row.view());
+        }
+        assert!(mat.check_structure().is_ok());
+        assert_eq!(mat, triplets.to_csr());
+        assert_eq!(mat.to_csc(), triplets.to_csc());
+    }
 
-        assert_eq!(mat, exp);
+    #[test]
+    #[should_panic]
+    fn append_outer_csvec_fail_dim_mismatch() {
+        let mat = CsMat::<f64>::empty(CSR, 3);
+        let row = crate::sparse::CsVec::new(4, vec![0], vec![1.]);
+        let _ = mat.append_outer_csvec(row.view());
+    }
 
-        mat[[2, 2]] = 5.;
-        let exp = CsMat::new_csc(
-            (3, 3),
-            vec![0, 1, 3, 4],
-            vec![1, 0, 2, 2],
-            vec![1., 1., 3., 5.],
-        );
+    #[test]
+    fn ndarray_round_trip() {
+        let dense =
+            arr2(&[[0., 1., 0., 2.], [0., 0., 0., 0.], [3., 0., -4., 0.]]);
+        let csr: CsMat<f64> = CsMat::csr_from_dense(dense.view(), 0.);
+        let csc: CsMat<f64> = CsMat::csc_from_dense(dense.view(), 0.);
+        assert_eq!(csr.nnz(), 4);
+        assert!(csr.logically_eq(&csc));
+        assert_eq!(csr.to_dense(), dense);
+        assert_eq!(csc.to_dense(), dense);
+        // going through a non-standard layout changes nothing
+        let transposed: CsMat<f64> = CsMat::csr_from_dense(dense.t(), 0.);
+        assert!(transposed.logically_eq(&csr.transpose_view()));
+        // products with dense vectors agree with the dense product
+        let x = Array::range(1., 5., 1.);
+        assert_eq!(&csr * &x.view(), dense.dot(&x));
+        assert_eq!(&csc * &x, dense.dot(&x));
+    }
 
-        assert_eq!(mat, exp);
+    #[test]
+    fn row_sums_col_sums() {
+        let mat = mat1();
+        let dense = mat.to_dense();
+        let row_sums: Vec<f64> = dense.sum_axis(ndarray::Axis(1)).to_vec();
+        let col_sums: Vec<f64> = dense.sum_axis(ndarray::Axis(0)).to_vec();
+        assert_eq!(mat.row_sums(), row_sums);
+        assert_eq!(mat.col_sums(), col_sums);
+        assert_eq!(mat.to_csc().row_sums(), row_sums);
+        assert_eq!(mat.to_csc().col_sums(), col_sums);
+        assert_eq!(mat.row_sums().iter().sum::<f64>(), mat.sum());
+        assert_eq!(mat.col_sums().iter().sum::<f64>(), mat.sum());
+
+        // a view in the middle of a matrix
+        let view = mat.view().middle_outer_views(1, 3);
+        assert_eq!(view.row_sums(), vec![7., 5., 8.]);
+        assert_eq!(view.col_sums(), vec![0., 8., 5., 2., 5.]);
+
+        let empty = CsMat::<f64>::zero((2, 3));
+        assert_eq!(empty.row_sums(), vec![0.; 2]);
+        assert_eq!(empty.col_sums(), vec![0.; 3]);
     }
 
     #[test]
-    fn map() {
-        // | 0 1 0 |
-        // | 1 0 0 |
-        // | 0 1 1 |
-        let mat = CsMat::new_csc(
-            (3, 3),
-            vec![0, 1, 3, 4],
-            vec![1, 0, 2, 2],
-            vec![1.; 4],
-        );
+    fn iter_nnz_to_triplets() {
+        let mat = mat1();
+        for mat in &[mat.clone(), mat.to_csc()] {
+            let (mut rows, mut cols, mut data) = (vec![], vec![], vec![]);
+            for (row, col, &val) in mat.iter_nnz() {
+                assert_eq!(mat.get(row, col), Some(&val));
+                rows.push(row);
+                cols.push(col);
+                data.push(val);
+            }
+            assert_eq!(rows.len(), mat.nnz());
+            let triplets = crate::sparse::TriMat::from_triplets(
+                mat.shape(),
+                rows,
+                cols,
+                data,
+            );
+            assert!(triplets.to_csr().logically_eq(mat));
+        }
+        // a view in the middle of a matrix
+        let view = mat.view().middle_outer_views(1, 2);
+        let triplets: Vec<_> = view.iter_nnz().collect();
+        assert_eq!(triplets, vec![(0, 3, &2.), (0, 4, &5.), (1, 2, &5.)]);
+    }
 
-        let mut res = mat.map(|&x| x + 2.);
-        let expected = CsMat::new_csc(
-            (3, 3),
-            vec![0, 1, 3, 4],
-            vec![1, 0, 2, 2],
-            vec![3.; 4],
-        );
-        assert_eq!(res, expected);
+    #[test]
+    #[cfg(feature = "multi_thread")]
+    fn par_outer_iterator() {
+        use rayon::prelude::*;
+        let mat = crate::test_data::laplacian_2d(20);
+        let par_sums: Vec<f64> = mat
+            .par_outer_iterator()
+            .map(|(_, vec)| vec.data().iter().sum())
+            .collect();
+        assert_eq!(par_sums, mat.sum_outer());
+        let par_sum: f64 = mat
+            .par_outer_iterator()
+            .map(|(_, vec)| vec.data().iter().sum::<f64>())
+            .sum();
+        assert_eq!(par_sum, mat.sum());
+        // the outer indices match the serial iteration
+        let view = mat.view().middle_outer_views(3, 10);
+        assert_eq!(view.par_outer_iterator().len(), 10);
+        let items: Vec<_> = view.par_outer_iterator().collect();
+        for ((i, vec), (j, serial)) in
+            items.iter().zip(view.outer_iterator().enumerate())
+        {
+            assert_eq!(*i, j);
+            assert_eq!(vec, &serial);
+        }
+    }
 
-        res.map_inplace(|&x| x / 3.);
-        assert_eq!(res, mat);
+    #[test]
+    fn into_iter_outer() {
+        for mat in &[mat1(), mat1_csc()] {
+            let storage = mat.storage();
+            let iter = mat.clone().into_iter();
+            assert_eq!(iter.len(), 5);
+            let mut rebuilt = CsMat::empty(storage, mat.inner_dims());
+            for (vec, view) in mat.clone().into_iter().zip(mat.outer_iterator())
+            {
+                assert_eq!(vec.view(), view);
+                rebuilt = rebuilt.append_outer_csvec(vec.view());
+            }
+            assert_eq!(&rebuilt, mat);
+            assert_eq!(rebuilt.storage(), storage);
+        }
+        let empty_rows = CsMat::<f64>::zero((3, 2));
+        let rows: Vec<_> = empty_rows.into_iter().collect();
+        assert_eq!(rows.len(), 3);
+        assert!(rows.iter().all(|row| row.nnz() == 0 && row.dim() == 2));
     }
 
     #[test]
-    fn insert() {
-        // | 0 1 0 |
-        // | 1 0 0 |
-        // | 0 1 1 |
-        let mut mat = CsMat::empty(CSR, 0);
-        mat.reserve_outer_dim(3);
-        mat.reserve_nnz(4);
-        // exercise the fast and easy path where the elements are added
-        // in row order for a CSR matrix
-        mat.insert(0, 1, 1.);
-        mat.insert(1, 0, 1.);
-        mat.insert(2, 1, 1.);
-        mat.insert(2, 2, 1.);
+    fn from_iterator_triplets() {
+        let triplets = vec![
+            (3, 1, 8.),
+            (0, 3, 4.),
+            (1, 4, 5.),
+            (0, 2, 1.),
+            (2, 2, 5.),
+            (1, 3, 2.),
+            (4, 3, 7.),
+            (0, 2, 2.),
+        ];
+        let mat: CsMat<f64> = triplets.into_iter().collect();
+        assert!(mat.is_csr());
+        assert_eq!(mat, mat1());
 
-        let expected =
-            CsMat::new((3, 3), vec![0, 1, 2, 4], vec![1, 0, 1, 2], vec![1.; 4]);
-        assert_eq!(mat, expected);
+        let mat: CsMatI<f64, u32> =
+            vec![(0, 1, 1.), (0, 1, -1.)].into_iter().collect();
+        assert_eq!(mat.shape(), (1, 2));
+        assert_eq!(mat.get(0, 1), Some(&0.));
 
-        // | 2 1 0 |
-        // | 1 0 0 |
-        // | 0 1 1 |
-        // exercise adding inside an already formed row (ie a search needs
-        // to be performed)
-        mat.insert(0, 0, 2.);
-        let expected = CsMat::new(
-            (3, 3),
-            vec![0, 2, 3, 5],
-            vec![0, 1, 0, 1, 2],
-            vec![2., 1., 1., 1., 1.],
-        );
-        assert_eq!(mat, expected);
+        let empty: CsMat<f64> = std::iter::empty().collect();
+        assert_eq!(empty.shape(), (0, 0));
+        assert_eq!(empty.nnz(), 0);
+    }
 
-        // | 2 1 0 |
-        // | 3 0 0 |
-        // | 0 1 1 |
-        // exercise the fact that inserting in an existing element
-        // should change this element's value
-        mat.insert(1, 0, 3.);
-        let expected = CsMat::new(
-            (3, 3),
-            vec![0, 2, 3, 5],
-            vec![0, 1, 0, 1, 2],
-            vec![2., 1., 3., 1., 1.],
+    #[test]
+    fn transpose_into_square() {
+        let mat = mat1();
+        let transposed = mat.clone().transpose_into();
+        assert_eq!(transposed.to_dense(), mat.to_dense().t());
+        let transposed = mat1().transpose_into();
+        let back = transposed.transpose_into();
+        assert_eq!(back, mat);
+        assert!(back.is_csr());
+        // the storages are reused
+        let mat = mat1();
+        let ptrs = (
+            mat.indptr().as_ptr(),
+            mat.indices().as_ptr(),
+            mat.data().as_ptr(),
         );
-        assert_eq!(mat, expected);
+        let transposed = mat.transpose_into();
+        assert_eq!(
+            ptrs,
+            (
+                transposed.indptr().as_ptr(),
+                transposed.indices().as_ptr(),
+                transposed.data().as_ptr()
+            )
+        );
+        // a transpose with the original storage
+        let transposed_csr = transposed.into_csr();
+        assert!(transposed_csr.is_csr());
+        assert_eq!(transposed_csr.to_dense(), mat1().to_dense().t());
     }
 
     #[test]
-    /// Non-regression test for https://github.com/vbarrielle/sprs/issues/129
-    fn bug_129() {
-        let mut mat = CsMat::zero((3, 100));
-        mat.insert(2, 3, 42);
-        let mut iter = mat.iter();
-        assert_eq!(iter.next(), Some((&42, (2, 3))));
-        assert_eq!(iter.next(), None);
+    fn symmetrize() {
+        for mat in &[mat1(), mat1_csc()] {
+            let sym = mat.symmetrize();
+            assert_eq!(sym.storage(), mat.storage());
+            assert!(sym.is_symmetric());
+            let dense = mat.to_dense();
+            let expected = (&dense + &dense.t()) / 2.;
+            assert_eq!(sym.to_dense(), expected);
+        }
+        // a symmetric matrix is unchanged
+        let lap = crate::test_data::laplacian_2d(3);
+        assert_eq!(lap.symmetrize(), lap);
     }
 
     #[test]
-    fn iter_mut() {
-        // | 0 1 0 |
-        // | 1 0 0 |
-        // | 0 1 1 |
-        let mut mat = CsMat::new_csc(
-            (3, 3),
-            vec![0, 1, 3, 4],
-            vec![1, 0, 2, 2],
-            vec![1.; 4],
-        );
+    #[should_panic(expected = "Non square matrix")]
+    fn symmetrize_fail_non_square() {
+        let mat = CsMat::new((2, 3), vec![0, 1, 1], vec![0], vec![1.]);
+        let _ = mat.symmetrize();
+    }
 
-        for mut col_vec in mat.outer_iterator_mut() {
-            for (row_ind, val) in col_vec.iter_mut() {
-                *val = row_ind as f64 + 1.;
+    #[test]
+    fn neighbors() {
+        let mat = mat1();
+        let neighbors: Vec<_> = mat.neighbors(0).collect();
+        assert_eq!(neighbors, vec![(2, &3.), (3, &4.)]);
+        let neighbors: Vec<_> = mat.neighbors(4).collect();
+        assert_eq!(neighbors, vec![(3, &7.)]);
+        // a breadth first search from node 0
+        let mut seen = vec![false; 5];
+        seen[0] = true;
+        let mut queue = std::collections::VecDeque::from(vec![0]);
+        let mut order = Vec::new();
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            for (neighbor, _) in mat.neighbors(node) {
+                if !seen[neighbor] {
+                    seen[neighbor] = true;
+                    queue.push_back(neighbor);
+                }
             }
         }
+        assert_eq!(order, vec![0, 2, 3, 1, 4]);
+        // in-neighbors for a csc matrix
+        let mat = mat1_csc();
+        let neighbors: Vec<_> = mat.neighbors(3).collect();
+        assert_eq!(neighbors, vec![(0, &4.), (1, &2.), (4, &7.)]);
+    }
 
-        let expected = CsMat::new_csc(
-            (3, 3),
-            vec![0, 1, 3, 4],
-            vec![1, 0, 2, 2],
-            vec![2., 1., 3., 3.],
-        );
-        assert_eq!(mat, expected);
+    #[test]
+    #[should_panic(expected = "Out of bounds node")]
+    fn neighbors_fail_out_of_bounds() {
+        let _ = mat1().neighbors(5);
     }
 
     #[test]
-    #[should_panic]
-    fn modify_fail() {
-        let mut mat = CsMat::new_csc(
-            (3, 3),
-            vec![0, 1, 3, 4],
-            vec![1, 0, 2, 2],
-            vec![1.; 4],
-        );
+    fn bandwidth() {
+        // mat1 has its farthest non-zero at (4, 1) or (1, 4)
+        let mat = mat1();
+        assert_eq!(mat.bandwidth(), 3);
+        assert_eq!(mat.to_csc().bandwidth(), 3);
+        assert_eq!(mat.transpose_view().bandwidth(), 3);
+        assert_eq!(CsMat::<f64>::eye(4).bandwidth(), 0);
+        assert_eq!(CsMat::<f64>::zero((3, 3)).bandwidth(), 0);
+
+        // reversing the order of the vertices of a tridiagonal matrix
+        // keeps its bandwidth, while interleaving them increases it
+        let tridiag = crate::test_data::laplacian_1d(6);
+        assert_eq!(tridiag.bandwidth(), 1);
+        let reverse = crate::Permutation::new(vec![5, 4, 3, 2, 1, 0]);
+        assert_eq!(tridiag.permute_symmetric(reverse.view()).bandwidth(), 1);
+        let interleave = crate::Permutation::new(vec![0, 3, 1, 4, 2, 5]);
+        let interleaved = tridiag.permute_symmetric(interleave.view());
+        assert_eq!(interleaved.bandwidth(), 3);
+
+        // the bandwidth of a rectangular matrix
+        let mat = CsMat::new((2, 5), vec![0, 1, 2], vec![4, 0], vec![1, 1]);
+        assert_eq!(mat.bandwidth(), 4);
+    }
 
-        // we panic because we forget to modify the last index, which gets
-        // pushed in the same col as its predecessor, yet has the same value
-        mat.modify(|indptr, indices, data| {
-            indptr[1] = 2;
-            indptr[2] = 4;
-            indices[0] = 0;
-            indices[1] = 1;
-            data[2] = 2.;
-        });
+    #[test]
+    fn profile() {
+        // each row of a tridiagonal matrix but the first one starts just
+        // before the diagonal
+        let tridiag = crate::test_data::laplacian_1d(6);
+        assert_eq!(tridiag.profile(), 5);
+        assert_eq!(tridiag.to_csc().profile(), 5);
+        assert_eq!(CsMat::<f64>::eye(4).profile(), 0);
+
+        // the rows of mat1 start at the columns 2, 3, 2, 1 and 3
+        let mat = mat1();
+        assert_eq!(mat.profile(), 2 + 1);
+        assert_eq!(mat.to_csc().profile(), mat.profile());
+        // the profile of the transpose is the upper profile, the columns
+        // 2, 3 and 4 of mat1 start at the rows 0, 0 and 1
+        assert_eq!(mat.transpose_view().profile(), 2 + 3 + 3);
     }
 
     #[test]
-    fn convert_types() {
-        let mat: CsMat<f32> = CsMat::eye(3);
-        let mat_: CsMatI<f64, u32> = mat.to_other_types();
-        assert_eq!(mat_.indptr(), &[0, 1, 2, 3]);
+    fn slice_middle_cols() {
+        let x = vec![1., -2., 3., 0.5, 4.];
+        for mat in [mat1(), mat1_csc()].iter() {
+            let slice = mat.slice_middle_cols(1..4);
+            assert_eq!(slice.shape(), (5, 3));
+            assert_eq!(slice.storage(), mat.storage());
+            assert!(slice.view().check_structure().is_ok());
+            assert_eq!(slice.view(), mat.submatrix(0..5, 1..4).view());
+            // the slice times the restricted vector is the product of the
+            // matrix with the vector vanishing outside of the columns
+            let mut x_zeroed = x.clone();
+            x_zeroed[0] = 0.;
+            x_zeroed[4] = 0.;
+            assert_eq!(&slice * &x[1..4], mat * &x_zeroed[..]);
+        }
+        // the slice of a csc matrix borrows its storage
+        let mat = mat1_csc();
+        let slice = mat.slice_middle_cols(2..5);
+        assert!(matches!(slice.indices, std::borrow::Cow::Borrowed(_)));
+        assert_eq!(slice.data().as_ptr(), mat.data()[1..].as_ptr());
+        assert_eq!(mat.slice_middle_cols(2..2).nnz(), 0);
+    }
 
-        let mat = CsMatI::new_csc(
-            (3, 3),
-            vec![0u32, 1, 3, 4],
-            vec![1, 0, 2, 2],
-            vec![1.; 4],
-        );
-        let mat_: CsMatI<f32, usize, u32> = mat.to_other_types();
-        assert_eq!(mat_.indptr(), &[0, 1, 3, 4]);
-        assert_eq!(mat_.data(), &[1.0f32, 1., 1., 1.]);
+    #[test]
+    #[should_panic(expected = "Out of bounds index")]
+    fn slice_middle_cols_fail_out_of_bounds() {
+        let _ = mat1().slice_middle_cols(3..6);
     }
 
     #[test]
-    fn iter() {
-        let mat = CsMat::new_csc(
-            (3, 3),
-            vec![0, 1, 3, 4],
-            vec![1, 0, 2, 2],
-            vec![1.; 4],
-        );
-        let mut iter = mat.iter();
-        assert_eq!(iter.next(), Some((&1., (1, 0))));
-        assert_eq!(iter.next(), Some((&1., (0, 1))));
-        assert_eq!(iter.next(), Some((&1., (2, 1))));
-        assert_eq!(iter.next(), Some((&1., (2, 2))));
-        assert_eq!(iter.next(), None);
+    fn append_diag_block() {
+        let block =
+            CsMat::new((2, 3), vec![0, 2, 3], vec![0, 2, 1], vec![1., 2., 3.]);
+        for (mat, block) in
+            [(mat1(), block.clone()), (mat1_csc(), block.to_csc())].iter()
+        {
+            let expected = crate::block_diag(&[mat.view(), block.view()]);
+            let mut grown = mat.clone();
+            grown.append_diag_block(block.view());
+            assert_eq!(grown.shape(), (7, 8));
+            assert!(grown.check_structure().is_ok());
+            assert_eq!(grown.to_csr(), expected);
+            // the blocks can be appended incrementally
+            grown.append_diag_block(block.view());
+            let expected =
+                crate::block_diag(&[mat.view(), block.view(), block.view()]);
+            assert_eq!(grown.to_csr(), expected);
+        }
+        // appending to an empty matrix gives the block
+        let mut empty = CsMat::<f64>::zero((0, 0));
+        empty.append_diag_block(block.view());
+        assert_eq!(empty, block);
     }
 
     #[test]
-    fn degrees() {
-        // | 1 0 0 3 1 |
-        // | 0 2 0 0 0 |
-        // | 0 0 0 1 0 |
-        // | 3 0 1 1 0 |
-        // | 1 0 0 0 1 |
-        let mat = CsMat::new_csc(
-            (5, 5),
-            vec![0, 3, 4, 5, 8, 10],
-            vec![0, 3, 4, 1, 3, 0, 2, 3, 0, 4],
-            vec![1, 3, 1, 2, 1, 3, 1, 1, 1, 1],
-        );
+    #[should_panic(expected = "Storage mismatch")]
+    fn append_diag_block_fail_storage_mismatch() {
+        let mut mat = mat1();
+        mat.append_diag_block(mat1_csc().view());
+    }
 
-        let degrees = mat.degrees();
-        assert_eq!(&degrees, &[2, 0, 1, 2, 1],);
+    #[test]
+    fn tile() {
+        let mat = mat1();
+        let tiled = mat.tile(2, 2);
+        assert_eq!(tiled.shape(), (10, 10));
+        assert_eq!(tiled.nnz(), 4 * mat.nnz());
+        assert!(tiled.check_structure().is_ok());
+        for (&val, (row, col)) in mat.iter() {
+            for &(row_offset, col_offset) in &[(0, 0), (0, 5), (5, 0), (5, 5)] {
+                assert_eq!(
+                    tiled.get(row + row_offset, col + col_offset),
+                    Some(&val)
+                );
+            }
+        }
+        // tiling is a kronecker product with a matrix of ones
+        let ones = CsMat::csr_from_dense(Array::ones((3, 2)).view(), 0.);
+        let expected = crate::kronecker_product(ones.view(), mat.view());
+        assert_eq!(mat.tile(3, 2), expected);
+        let tiled_csc = mat.to_csc().tile(3, 2);
+        assert!(tiled_csc.is_csc());
+        assert_eq!(tiled_csc.to_csr(), expected);
+        // no repetition gives an empty matrix
+        assert_eq!(mat.tile(0, 2).shape(), (0, 10));
+        assert_eq!(mat.tile(2, 0).nnz(), 0);
     }
 
     #[test]
-    fn onehot_zero() {
-        let onehot: CsMat<f32> = CsMat::zero((3, 3)).to_inner_onehot();
+    fn empty_like_with_same_structure() {
+        for mat in [mat1(), mat1_csc()].iter() {
+            let empty = mat.empty_like();
+            assert_eq!(empty.shape(), mat.shape());
+            assert_eq!(empty.storage(), mat.storage());
+            assert_eq!(empty.nnz(), 0);
+            assert!(empty.check_structure().is_ok());
+            assert_eq!(empty.to_dense(), Array::zeros((5, 5)));
+
+            let pattern = mat.with_same_structure(true);
+            assert_eq!(pattern.storage(), mat.storage());
+            assert_eq!(pattern.indptr(), mat.indptr());
+            assert_eq!(pattern.indices(), mat.indices());
+            assert!(pattern.data().iter().all(|&x| x));
+        }
+        let rect = CsMat::<f64>::zero((2, 7)).empty_like();
+        assert_eq!(rect.shape(), (2, 7));
+        assert_eq!(rect.indptr(), &[0, 0, 0]);
+    }
 
-        assert!(onehot.is_csr());
-        assert_eq!(CsMat::zero((3, 3)), onehot);
+    #[test]
+    fn density() {
+        // mat1 has 7 non-zeros out of 25 elements
+        assert_eq!(mat1().density(), 7. / 25.);
+        assert_eq!(mat1_csc().density(), 7. / 25.);
+        let rect = CsMat::new((2, 4), vec![0, 1, 1], vec![3], vec![1.]);
+        assert_eq!(rect.density(), 1. / 8.);
+        assert_eq!(CsMat::<f64>::zero((3, 3)).density(), 0.);
+        assert_eq!(CsMat::<f64>::zero((0, 0)).density(), 0.);
+        assert_eq!(CsMat::<f64>::empty(CSR, 5).density(), 0.);
     }
 
     #[test]
-    fn onehot_eye() {
+    fn filter_abs() {
+        let mat = mat1().map(|&x| x - 4.5);
+        let mut last_nnz = mat.nnz();
+        for &threshold in &[0., 0.5, 1., 1.5, 2.5, 3.5, 4.] {
+            let filtered = mat.filter_abs(threshold);
+            assert!(filtered.check_structure().is_ok());
+            assert!(filtered.nnz() <= last_nnz);
+            assert!(filtered.data().iter().all(|x| x.abs() >= threshold));
+            for (&val, (row, col)) in mat.iter() {
+                let expected = if val.abs() >= threshold {
+                    Some(&val)
+                } else {
+                    None
+                };
+                assert_eq!(filtered.get(row, col), expected);
+            }
+            last_nnz = filtered.nnz();
+        }
+        assert_eq!(mat.filter_abs(0.).nnz(), mat.nnz());
+        // the values of mat are -1.5, -0.5, -2.5, 0.5, 0.5, 3.5 and 2.5
+        assert_eq!(mat.filter_abs(1.).nnz(), 4);
+        assert_eq!(mat.filter_abs(4.).nnz(), 0);
+    }
+
+    #[test]
+    fn clamp() {
+        for mat in [mat1(), mat1_csc()].iter() {
+            // the values 2, 3 and 8 are clamped to the bounds
+            let clamped = mat.clamp(3.5, 7.);
+            assert!(clamped.check_structure().is_ok());
+            assert_eq!(clamped.storage(), mat.storage());
+            assert_eq!(clamped.nnz(), mat.nnz());
+            for (&val, (row, col)) in mat.iter() {
+                let expected = val.max(3.5).min(7.);
+                assert_eq!(clamped.get(row, col), Some(&expected));
+            }
+        }
+        // a value clamped to zero is removed
         let mat = CsMat::new(
-            (2, 2),
+            (2, 3),
             vec![0, 2, 4],
-            vec![0, 1, 0, 1],
-            vec![2.0, 0.0, 0.0, 2.0],
+            vec![0, 2, 1, 2],
+            vec![-1., 2., -3., 0.5],
         );
-
-        let onehot = mat.to_inner_onehot();
-
-        assert!(onehot.is_csr());
-        assert_eq!(CsMat::eye(2), onehot);
+        let clamped = mat.clamp(0., 1.);
+        assert_eq!(
+            clamped,
+            CsMat::new((2, 3), vec![0, 1, 2], vec![2, 2], vec![1., 0.5])
+        );
+        assert!(clamped.check_structure().is_ok());
+        // integer values
+        let mat = CsMat::new((1, 3), vec![0, 3], vec![0, 1, 2], vec![-5, 2, 9]);
+        assert_eq!(mat.clamp(-2, 2).data(), &[-2, 2, 2]);
     }
 
     #[test]
-    fn onehot_sparse_csc() {
-        let mat = CsMat::new_csc((2, 3), vec![0, 0, 1, 1], vec![1], vec![2.0]);
-
-        let onehot = mat.to_inner_onehot();
+    #[should_panic(expected = "Empty clamping range")]
+    fn clamp_fail_empty_range() {
+        let _ = mat1().clamp(2., 1.);
+    }
 
-        let expected =
-            CsMat::new_csc((2, 3), vec![0, 0, 1, 1], vec![1], vec![1.0]);
+    #[test]
+    fn map_with_coords() {
+        for mat in [mat1(), mat1_csc()].iter() {
+            // remove the diagonal
+            let off_diag = mat.map_with_coords(
+                |row, col, &x| if row == col { 0. } else { x },
+            );
+            assert!(off_diag.check_structure().is_ok());
+            assert_eq!(off_diag.storage(), mat.storage());
+            assert_eq!(off_diag, mat.prune(|row, col, _| row != col));
+            assert!(off_diag.diag().iter().all(|&x| x == 0.));
+            // the coordinates match the location of the values
+            let coords =
+                mat.map_with_coords(|row, col, _| (10 * row + col) as f64);
+            for (&val, (row, col)) in coords.iter() {
+                assert_eq!(val, (10 * row + col) as f64);
+            }
+        }
+    }
 
-        assert!(onehot.is_csc());
-        assert_eq!(expected, onehot);
+    #[test]
+    fn fold_outer() {
+        let mat = mat1();
+        let maxima = mat.fold_outer(None, |acc: Option<f64>, _, &x| {
+            Some(acc.map_or(x, |m| m.max(x)))
+        });
+        let mut expected = vec![None; mat.rows()];
+        for (&val, (row, _)) in mat.iter() {
+            expected[row] =
+                Some(expected[row].map_or(val, |m: f64| m.max(val)));
+        }
+        assert_eq!(maxima, expected);
+        assert_eq!(
+            maxima,
+            vec![Some(4.), Some(5.), Some(5.), Some(8.), Some(7.)]
+        );
+        // the column maxima of mat1_csc, which has an empty column
+        let maxima = mat1_csc()
+            .fold_outer(std::f64::NEG_INFINITY, |acc, _, &x| acc.max(x));
+        assert_eq!(maxima, vec![std::f64::NEG_INFINITY, 8., 5., 7., 5.]);
+        // the sums and the counts of non-zeros are folds
+        assert_eq!(mat.fold_outer(0., |acc, _, &x| acc + x), mat.sum_outer());
+        assert_eq!(
+            mat.fold_outer(0, |acc, _, _| acc + 1),
+            mat.outer_nnz_counts()
+        );
+        // the inner indices are given in storage order
+        let inds = mat.fold_outer(Vec::new(), |mut acc, ind, _| {
+            acc.push(ind);
+            acc
+        });
+        assert_eq!(
+            inds,
+            vec![vec![2, 3], vec![3, 4], vec![2], vec![1], vec![3]]
+        );
     }
 
     #[test]
-    fn onehot_ignores_nan() {
+    fn row_max_min_argmax() {
+        // | 0  3 -1  0 |
+        // | 0  0  0  0 |
+        // | 2  0  0  2 |
+        // |-4  0 -5  0 |
         let mat = CsMat::new(
-            (2, 2),
-            vec![0, 2, 3],
-            vec![0, 1, 1],
-            vec![2.0, std::f64::NAN, 2.0],
+            (4, 4),
+            vec![0, 2, 2, 4, 6],
+            vec![1, 2, 0, 3, 0, 2],
+            vec![3., -1., 2., 2., -4., -5.],
         );
-
-        let onehot = mat.to_inner_onehot();
-
-        assert!(onehot.is_csr());
-        assert_eq!(CsMat::eye(2), onehot);
+        for mat in [mat.clone(), mat.to_csc()].iter() {
+            assert_eq!(
+                mat.row_max(),
+                vec![Some(3.), None, Some(2.), Some(-4.)]
+            );
+            assert_eq!(
+                mat.row_min(),
+                vec![Some(-1.), None, Some(2.), Some(-5.)]
+            );
+            // the first column is returned on ties
+            assert_eq!(mat.row_argmax(), vec![Some(1), None, Some(0), Some(0)]);
+        }
+        let empty = CsMat::<f64>::zero((2, 3));
+        assert_eq!(empty.row_max(), vec![None, None]);
+        assert_eq!(empty.row_argmax(), vec![None, None]);
     }
 
     #[test]
-    fn mul_assign() {
-        let mut m1 = crate::TriMat::new((6, 9));
-        m1.add_triplet(1, 1, 8_i32);
-        m1.add_triplet(1, 2, 7);
-        m1.add_triplet(0, 1, 6);
-        m1.add_triplet(0, 8, 5);
-        m1.add_triplet(4, 2, 4);
-        let mut m1 = m1.to_csr();
-
-        m1 *= 2;
-        for (&v, (j, i)) in m1.iter() {
-            match (j, i) {
-                (1, 1) => assert_eq!(v, 16),
-                (1, 2) => assert_eq!(v, 14),
-                (0, 1) => assert_eq!(v, 12),
-                (0, 8) => assert_eq!(v, 10),
-                (4, 2) => assert_eq!(v, 8),
-                _ => panic!(),
+    fn add_row_broadcast() {
+        let v = vec![1., 0., -3., -7., 0.5];
+        for mat in [mat1(), mat1_csc()].iter() {
+            let res = mat.add_row_broadcast(&v);
+            assert!(res.check_structure().is_ok());
+            assert_eq!(res.storage(), mat.storage());
+            let mut expected = mat.to_dense();
+            for mut row in expected.outer_iter_mut() {
+                row += &ndarray::arr1(&v);
+            }
+            assert_eq!(res.to_dense(), expected);
+            // the sums cancelling out are not stored, eg at (4, 3)
+            assert!(res.data().iter().all(|&x| x != 0.));
+            assert_eq!(res.get(4, 3), None);
+            assert_eq!(
+                res.nnz(),
+                expected.iter().filter(|&&x| x != 0.).count()
+            );
+            // the product with a probe vector is A x + (v . x) ones
+            let x = vec![2., -1., 0.5, 3., 1.];
+            let vx: f64 = v.iter().zip(x.iter()).map(|(a, b)| a * b).sum();
+            let ax = mat * &x[..];
+            let res_x = &res * &x[..];
+            for (&r, &a) in res_x.iter().zip(ax.iter()) {
+                assert!((r - (a + vx)).abs() < 1e-12);
             }
         }
+        // a zero vector keeps the matrix
+        assert_eq!(mat1().add_row_broadcast(&[0.; 5]), mat1());
     }
 
     #[test]
-    fn div_assign() {
-        let mut m1 = crate::TriMat::new((6, 9));
-        m1.add_triplet(1, 1, 8_i32);
-        m1.add_triplet(1, 2, 7);
-        m1.add_triplet(0, 1, 6);
-        m1.add_triplet(0, 8, 5);
-        m1.add_triplet(4, 2, 4);
-        let mut m1 = m1.to_csr();
+    #[should_panic(expected = "Dimension mismatch")]
+    fn add_row_broadcast_fail_dim_mismatch() {
+        let _ = mat1().add_row_broadcast(&[1.; 4]);
+    }
 
-        m1 /= 2;
-        for (&v, (j, i)) in m1.iter() {
-            match (j, i) {
-                (1, 1) => assert_eq!(v, 4),
-                (1, 2) => assert_eq!(v, 3),
-                (0, 1) => assert_eq!(v, 3),
-                (0, 8) => assert_eq!(v, 2),
-                (4, 2) => assert_eq!(v, 2),
-                _ => panic!(),
+    #[test]
+    fn add_scalar_diag() {
+        let eye = CsMat::<f64>::eye(4);
+        let shifted = eye.add_scalar_diag(2.);
+        assert_eq!(shifted.diag(), vec![3.; 4]);
+        assert_eq!(shifted.nnz(), 4);
+
+        for mat in [mat1(), mat1_csc()].iter() {
+            let shifted = mat.add_scalar_diag(-1.5);
+            assert!(shifted.check_structure().is_ok());
+            assert_eq!(shifted.storage(), mat.storage());
+            let mut expected = mat.to_dense();
+            for i in 0..5 {
+                expected[[i, i]] -= 1.5;
             }
+            assert_eq!(shifted.to_dense(), expected);
+            assert_eq!(shifted.nnz(), mat.nnz() + 5 - 1);
+            let same = mat.add_scalar_diag(0.);
+            assert_eq!(same.indptr(), mat.indptr());
+            assert_eq!(same.indices(), mat.indices());
         }
+        // the cancelling diagonal sums are not stored
+        let shifted = mat1().add_scalar_diag(-5.);
+        assert_eq!(shifted.get(2, 2), None);
+        // but the explicit zeros off the diagonal are kept
+        let mat =
+            CsMat::new((2, 2), vec![0, 2, 3], vec![0, 1, 1], vec![1., 0., 2.]);
+        let shifted = mat.add_scalar_diag(-1.);
+        assert_eq!(shifted.indptr(), &[0, 1, 2]);
+        assert_eq!(shifted.indices(), &[1, 1]);
+        assert_eq!(shifted.data(), &[0., 1.]);
     }
 
     #[test]
-    fn issue_99() {
-        let a = crate::TriMat::<i32>::new((10, 1)).to_csc();
-        let b = crate::TriMat::<i32>::new((1, 9)).to_csr();
-        let _c = &a * &b;
+    #[should_panic(expected = "Non square matrix")]
+    fn add_scalar_diag_fail_non_square() {
+        let mat = CsMat::<f64>::zero((2, 3));
+        let _ = mat.add_scalar_diag(1.);
+    }
+
+    #[test]
+    #[should_panic(expected = "Out of bounds index")]
+    fn modify_fail_product_checked() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        let mut mat = mat1();
+        // an out of bounds index is rejected, but stays in the matrix
+        let res = catch_unwind(AssertUnwindSafe(|| {
+            mat.modify(|_, indices, _| indices[6] = 100);
+        }));
+        assert!(res.is_err());
+        let _ = &mat * &[1.; 5][..];
     }
 }
 
@@ -3364,3 +6991,195 @@ mod approx_impls {
         }
     }
 }
+
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use super::*;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer};
+
+    /// Mirror of the fields of `CsMatBase`, used to deserialize the raw
+    /// components before checking their structure.
+    #[derive(Deserialize)]
+    #[serde(rename = "CsMatBase")]
+    struct CsMatBaseShadow<IptrStorage, IndStorage, DataStorage> {
+        storage: CompressedStorage,
+        nrows: usize,
+        ncols: usize,
+        indptr: IptrStorage,
+        indices: IndStorage,
+        data: DataStorage,
+    }
+
+    impl<'de, N, I, Iptr, IptrStorage, IndStorage, DataStorage> Deserialize<'de>
+        for CsMatBase<N, I, IptrStorage, IndStorage, DataStorage, Iptr>
+    where
+        I: SpIndex,
+        Iptr: SpIndex,
+        IptrStorage: Deref<Target = [Iptr]> + Deserialize<'de>,
+        IndStorage: Deref<Target = [I]> + Deserialize<'de>,
+        DataStorage: Deref<Target = [N]> + Deserialize<'de>,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let shadow = CsMatBaseShadow::deserialize(deserializer)?;
+            let mat = CsMatBase {
+                storage: shadow.storage,
+                nrows: shadow.nrows,
+                ncols: shadow.ncols,
+                indptr: shadow.indptr,
+                indices: shadow.indices,
+                data: shadow.data,
+            };
+            mat.check_compressed_structure().map_err(D::Error::custom)?;
+            Ok(mat)
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use crate::sparse::CompressedStorage::CSR;
+        use crate::sparse::CsMat;
+        use crate::test_data::{mat1, mat1_csc};
+
+        #[test]
+        fn serde_round_trip() {
+            for mat in [mat1(), mat1_csc()].iter() {
+                let serialized = bincode::serialize(mat).unwrap();
+                let deserialized: CsMat<f64> =
+                    bincode::deserialize(&serialized).unwrap();
+                assert_eq!(&deserialized, mat);
+            }
+        }
+
+        #[test]
+        fn serde_corrupted_fails() {
+            // non monotonic indptr
+            let bad_indptr = CsMat::new_trusted(
+                CSR,
+                (3, 3),
+                vec![0, 2, 1, 3],
+                vec![0, 1, 2],
+                vec![1., 2., 3.],
+            );
+            let serialized = bincode::serialize(&bad_indptr).unwrap();
+            assert!(bincode::deserialize::<CsMat<f64>>(&serialized).is_err());
+
+            // out of bounds inner index
+            let bad_indices = CsMat::new_trusted(
+                CSR,
+                (3, 3),
+                vec![0, 1, 2, 3],
+                vec![0, 3, 2],
+                vec![1., 2., 3.],
+            );
+            let serialized = bincode::serialize(&bad_indices).unwrap();
+            assert!(bincode::deserialize::<CsMat<f64>>(&serialized).is_err());
+        }
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+mod nalgebra_impls {
+    use super::*;
+    use nalgebra::{DMatrix, DVector, Scalar};
+
+    impl<N, I, Iptr> CsMatI<N, I, Iptr>
+    where
+        I: SpIndex,
+        Iptr: SpIndex,
+    {
+        /// Create a CSC matrix from a dense `nalgebra` matrix, dropping
+        /// its zero elements.
+        ///
+        /// The CSC storage matches the column major layout of `DMatrix`.
+        pub fn from_nalgebra(m: &DMatrix<N>) -> CsMatI<N, I, Iptr>
+        where
+            N: Scalar + Zero,
+        {
+            let (nrows, ncols) = m.shape();
+            let mut indptr = Vec::with_capacity(ncols + 1);
+            let mut indices = Vec::new();
+            let mut data = Vec::new();
+            indptr.push(Iptr::zero());
+            for col in m.column_iter() {
+                for (row, val) in col.iter().enumerate() {
+                    if !val.is_zero() {
+                        indices.push(I::from_usize(row));
+                        data.push(val.clone());
+                    }
+                }
+                indptr.push(Iptr::from_usize(indices.len()));
+            }
+            CsMatI::new_trusted(CSC, (nrows, ncols), indptr, indices, data)
+        }
+    }
+
+    impl<N, I, Iptr, IpStorage, IStorage, DStorage>
+        CsMatBase<N, I, IpStorage, IStorage, DStorage, Iptr>
+    where
+        I: SpIndex,
+        Iptr: SpIndex,
+        IpStorage: Deref<Target = [Iptr]>,
+        IStorage: Deref<Target = [I]>,
+        DStorage: Deref<Target = [N]>,
+    {
+        /// Convert the matrix to a dense `nalgebra` matrix, whatever
+        /// its storage.
+        pub fn to_nalgebra(&self) -> DMatrix<N>
+        where
+            N: Scalar + Zero,
+        {
+            let mut res = DMatrix::zeros(self.rows(), self.cols());
+            for (val, (row, col)) in self.iter() {
+                res[(row.index(), col.index())] = val.clone();
+            }
+            res
+        }
+    }
+
+    impl<'a, 'b, N, I, Iptr, IpS, IS, DS> Mul<&'b DVector<N>>
+        for &'a CsMatBase<N, I, IpS, IS, DS, Iptr>
+    where
+        N: 'a + Scalar + Copy + Num,
+        I: 'a + SpIndex,
+        Iptr: 'a + SpIndex,
+        IpS: 'a + Deref<Target = [Iptr]>,
+        IS: 'a + Deref<Target = [I]>,
+        DS: 'a + Deref<Target = [N]>,
+    {
+        type Output = DVector<N>;
+
+        fn mul(self, rhs: &'b DVector<N>) -> DVector<N> {
+            DVector::from_vec(self * rhs.as_slice())
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use crate::sparse::CsMat;
+        use nalgebra::{DMatrix, DVector};
+
+        #[test]
+        fn nalgebra_round_trip() {
+            let dense = DMatrix::from_row_slice(
+                3,
+                4,
+                &[0., 1., 0., 2., 0., 0., 0., 0., 3., 0., -4., 0.],
+            );
+            let mat: CsMat<f64> = CsMat::from_nalgebra(&dense);
+            assert!(mat.is_csc());
+            assert_eq!(mat.shape(), (3, 4));
+            assert_eq!(mat.nnz(), 4);
+            assert_eq!(mat.get(2, 2), Some(&-4.));
+            assert_eq!(mat.to_nalgebra(), dense);
+            assert_eq!(mat.to_csr().to_nalgebra(), dense);
+
+            let x = DVector::from_vec(vec![1., 2., 3., 4.]);
+            assert_eq!(&mat * &x, &dense * &x);
+            assert_eq!(&mat.to_csr() * &x, &dense * &x);
+        }
+    }
+}
@@ -0,0 +1,349 @@
+//! Graph algorithms on sparse adjacency matrices
+//!
+//! A square sparse matrix `A` is seen as the adjacency matrix of a weighted
+//! graph, where a stored element `A[i, j]` is an edge of weight `A[i, j]`
+//! from node `i` to node `j`. Undirected graphs are represented by
+//! symmetric matrices.
+
+use crate::indexing::SpIndex;
+use crate::sparse::prelude::*;
+use num_traits::Num;
+
+/// Degrees of the nodes of a graph, ignoring self-loops. The weighted
+/// degrees are the row sums, the unweighted degrees the number of non-zeros
+/// in each row.
+fn degrees<N, I, Iptr>(adj: CsMatViewI<N, I, Iptr>, weighted: bool) -> Vec<N>
+where
+    N: Num + Copy,
+    I: SpIndex,
+    Iptr: SpIndex,
+{
+    let mut degrees = vec![N::zero(); adj.rows()];
+    for (&val, (row, col)) in adj.iter() {
+        if row != col {
+            let degree = &mut degrees[row.index()];
+            *degree = *degree + if weighted { val } else { N::one() };
+        }
+    }
+    degrees
+}
+
+/// Compute the degree matrix of a graph given by its adjacency matrix `A`,
+/// ie the diagonal matrix `D` such that `D[i, i]` is the degree of node `i`.
+///
+/// If `weighted` is true, the degree of a node is the sum of the weights of
+/// its edges, ie the row sum of `A`. Otherwise it is its number of edges,
+/// ie the number of non-zeros in the row of `A`. In both cases self-loops
+/// are ignored, as in `laplacian`, so that `laplacian(A)` is `D - A` for a
+/// graph without self-loops. The diagonal of nodes without edges is
+/// not stored.
+///
+/// The result has the same storage as `adj`.
+///
+/// # Panics
+///
+/// If `adj` is not square.
+///
+/// # Example
+///
+/// ```rust
+/// use sprs::{graph, CsMat};
+/// // the edges 0 - 1 of weight 2 and 1 - 2 of weight 3
+/// let adj = CsMat::new((3, 3), vec![0, 1, 3, 4], vec![1, 0, 2, 1],
+///                      vec![2, 2, 3, 3]);
+/// let degrees = graph::degree_matrix(adj.view(), true);
+/// assert_eq!(degrees, CsMat::new((3, 3), vec![0, 1, 2, 3], vec![0, 1, 2],
+///                                vec![2, 5, 3]));
+/// let degrees = graph::degree_matrix(adj.view(), false);
+/// assert_eq!(degrees, CsMat::new((3, 3), vec![0, 1, 2, 3], vec![0, 1, 2],
+///                                vec![1, 2, 1]));
+/// ```
+pub fn degree_matrix<N, I, Iptr>(
+    adj: CsMatViewI<N, I, Iptr>,
+    weighted: bool,
+) -> CsMatI<N, I, Iptr>
+where
+    N: Num + Copy,
+    I: SpIndex,
+    Iptr: SpIndex,
+{
+    let n = adj.rows();
+    if adj.cols() != n {
+        panic!("Non square matrix passed to degree_matrix");
+    }
+    let mut indptr = Vec::with_capacity(n + 1);
+    let mut indices = Vec::with_capacity(n);
+    let mut data = Vec::with_capacity(n);
+    indptr.push(Iptr::zero());
+    for (node, degree) in degrees(adj, weighted).into_iter().enumerate() {
+        if !degree.is_zero() {
+            indices.push(I::from_usize(node));
+            data.push(degree);
+        }
+        indptr.push(Iptr::from_usize(indices.len()));
+    }
+    CsMatI::new_trusted(adj.storage(), (n, n), indptr, indices, data)
+}
+
+/// Compute the Laplacian matrix `L = D - A` of a graph given by its
+/// adjacency matrix `A`, where `D` is the diagonal matrix of the weighted
+/// degrees of the nodes, ie `D[i, i]` is the sum of the weights of the edges
+/// of node `i`.
+///
+/// Self-loops, ie the diagonal elements of `A`, are ignored: they count
+/// neither in the degrees nor in `A`, so that `x^T L x` is the sum of
+/// `A[i, j] (x[i] - x[j])^2 / 2` over the edges. The degrees are computed
+/// as the row sums of `A`, which is meant to be symmetric (this is not
+/// checked). The diagonal of nodes without edges is not stored.
+///
+/// The result has the same storage as `adj`.
+///
+/// # Panics
+///
+/// If `adj` is not square.
+///
+/// # Example
+///
+/// ```rust
+/// use sprs::{graph, CsMat};
+/// // the path graph 0 - 1 - 2
+/// let adj = CsMat::new((3, 3), vec![0, 1, 3, 4], vec![1, 0, 2, 1],
+///                      vec![1, 1, 1, 1]);
+/// let lap = graph::laplacian(adj.view());
+/// let expected = CsMat::new((3, 3), vec![0, 2, 5, 7],
+///                           vec![0, 1, 0, 1, 2, 1, 2],
+///                           vec![1, -1, -1, 2, -1, -1, 1]);
+/// assert_eq!(lap, expected);
+/// ```
+pub fn laplacian<N, I, Iptr>(adj: CsMatViewI<N, I, Iptr>) -> CsMatI<N, I, Iptr>
+where
+    N: Num + Copy,
+    I: SpIndex,
+    Iptr: SpIndex,
+{
+    let n = adj.rows();
+    if adj.cols() != n {
+        panic!("Non square matrix passed to laplacian");
+    }
+    let degrees = degrees(adj.view(), true);
+
+    let mut indptr = Vec::with_capacity(n + 1);
+    let mut indices = Vec::with_capacity(adj.nnz() + n);
+    let mut data = Vec::with_capacity(adj.nnz() + n);
+    indptr.push(Iptr::zero());
+    for (outer, vec) in adj.outer_iterator().enumerate() {
+        let degree = degrees[outer];
+        let mut diag_done = degree.is_zero();
+        for (inner, &val) in vec.iter() {
+            if !diag_done && inner >= outer {
+                indices.push(I::from_usize(outer));
+                data.push(degree);
+                diag_done = true;
+            }
+            if inner != outer {
+                indices.push(I::from_usize(inner));
+                data.push(N::zero() - val);
+            }
+        }
+        if !diag_done {
+            indices.push(I::from_usize(outer));
+            data.push(degree);
+        }
+        indptr.push(Iptr::from_usize(indices.len()));
+    }
+    CsMatI::new_trusted(adj.storage(), (n, n), indptr, indices, data)
+}
+
+/// Find the root of a node in a union-find forest, halving the path
+fn find_root(parents: &mut [usize], mut node: usize) -> usize {
+    while parents[node] != node {
+        parents[node] = parents[parents[node]];
+        node = parents[node];
+    }
+    node
+}
+
+/// Compute the connected components of a graph given by its adjacency
+/// matrix.
+///
+/// Returns a vector holding the component label of each node. The labels
+/// are numbered from `0` in the order of appearance of the components,
+/// so that the number of components is one plus the largest label.
+///
+/// Only the sparsity structure of `adj` is used, the values of its stored
+/// elements are ignored. The edges are considered undirected, so the weakly
+/// connected components are computed for an unsymmetric structure.
+///
+/// # Panics
+///
+/// If `adj` is not square.
+///
+/// # Example
+///
+/// ```rust
+/// use sprs::{graph, CsMat};
+/// // the edges 0 - 2 and 1 - 3
+/// let adj = CsMat::new((4, 4), vec![0, 1, 2, 2, 2], vec![2, 3],
+///                      vec![1, 1]);
+/// assert_eq!(graph::connected_components(adj.view()), vec![0, 1, 0, 1]);
+/// ```
+pub fn connected_components<N, I, Iptr>(
+    adj: CsMatViewI<N, I, Iptr>,
+) -> Vec<usize>
+where
+    I: SpIndex,
+    Iptr: SpIndex,
+{
+    let n = adj.rows();
+    if adj.cols() != n {
+        panic!("Non square matrix passed to connected_components");
+    }
+    let mut parents: Vec<usize> = (0..n).collect();
+    for (_, (row, col)) in adj.iter() {
+        let root_row = find_root(&mut parents, row.index());
+        let root_col = find_root(&mut parents, col.index());
+        if root_row != root_col {
+            parents[root_row.max(root_col)] = root_row.min(root_col);
+        }
+    }
+    // the root of a component is its smallest node, which is reached
+    // before any other node of the component
+    let mut labels = vec![0; n];
+    let mut nb_components = 0;
+    for node in 0..n {
+        let root = find_root(&mut parents, node);
+        if root == node {
+            labels[node] = nb_components;
+            nb_components += 1;
+        } else {
+            labels[node] = labels[root];
+        }
+    }
+    labels
+}
+
+#[cfg(test)]
+mod test {
+    use super::{connected_components, degree_matrix, laplacian};
+    use crate::sparse::CsMat;
+    use crate::test_data::laplacian_1d;
+
+    fn path_graph(n: usize) -> CsMat<f64> {
+        let mut adj = crate::sparse::TriMat::new((n, n));
+        for i in 1..n {
+            adj.add_triplet(i - 1, i, 1.);
+            adj.add_triplet(i, i - 1, 1.);
+        }
+        adj.to_csr()
+    }
+
+    #[test]
+    fn laplacian_path_graph() {
+        let n = 6;
+        let adj = path_graph(n);
+        let lap = laplacian(adj.view());
+        // the laplacian of the path graph is the 1D laplacian, except for
+        // its end nodes which have a single neighbor
+        let mut expected = laplacian_1d(n);
+        *expected.get_mut(0, 0).unwrap() = 1.;
+        *expected.get_mut(n - 1, n - 1).unwrap() = 1.;
+        assert_eq!(lap, expected);
+        assert!(lap.check_structure().is_ok());
+        // the constant vector is in the kernel of the laplacian
+        let ones = vec![1.; n];
+        assert_eq!(&lap * &ones[..], vec![0.; n]);
+
+        let lap_csc = laplacian(adj.to_csc().view());
+        assert!(lap_csc.is_csc());
+        assert!(lap_csc.logically_eq(&expected));
+    }
+
+    #[test]
+    fn laplacian_weights_and_self_loops() {
+        // weighted triangle 0 - 1 - 2 - 0 with a self-loop on node 1,
+        // and an isolated node 3
+        let adj = CsMat::new(
+            (4, 4),
+            vec![0, 2, 5, 7, 7],
+            vec![1, 2, 0, 1, 2, 0, 1],
+            vec![2., 3., 2., 10., 0.5, 3., 0.5],
+        );
+        let lap = laplacian(adj.view());
+        let expected = ndarray::arr2(&[
+            [5., -2., -3., 0.],
+            [-2., 2.5, -0.5, 0.],
+            [-3., -0.5, 3.5, 0.],
+            [0., 0., 0., 0.],
+        ]);
+        assert_eq!(lap.to_dense(), expected);
+        assert!(lap.check_structure().is_ok());
+        assert_eq!(lap.outer_view(3).unwrap().nnz(), 0);
+    }
+
+    #[test]
+    fn connected_components_two_cliques() {
+        // the cliques {0, 2, 4} and {1, 3}
+        let mut adj = crate::sparse::TriMat::new((5, 5));
+        for &(i, j) in &[(0, 2), (0, 4), (2, 4), (1, 3)] {
+            adj.add_triplet(i, j, 1u8);
+            adj.add_triplet(j, i, 1u8);
+        }
+        let adj: CsMat<u8> = adj.to_csr();
+        let labels = connected_components(adj.view());
+        assert_eq!(labels, vec![0, 1, 0, 1, 0]);
+        assert_eq!(connected_components(adj.to_csc().view()), labels);
+    }
+
+    #[test]
+    fn connected_components_directed_and_isolated() {
+        // the directed edges 3 -> 0 and 2 -> 3 are enough to connect
+        // 0, 2 and 3, while 1 is isolated and 4 only has a self-loop
+        let adj = CsMat::new(
+            (5, 5),
+            vec![0, 0, 0, 1, 2, 3],
+            vec![3, 0, 4],
+            vec![1., 1., 1.],
+        );
+        assert_eq!(connected_components(adj.view()), vec![0, 1, 0, 0, 2]);
+        let empty = CsMat::<f64>::zero((0, 0));
+        assert!(connected_components(empty.view()).is_empty());
+    }
+
+    #[test]
+    fn degree_matrix_weighted_unweighted() {
+        // weighted triangle 0 - 1 - 2 - 0 with a self-loop on node 1,
+        // and an isolated node 3
+        let adj = CsMat::new(
+            (4, 4),
+            vec![0, 2, 5, 7, 7],
+            vec![1, 2, 0, 1, 2, 0, 1],
+            vec![2., 3., 2., 10., 0.5, 3., 0.5],
+        );
+        let weighted = degree_matrix(adj.view(), true);
+        assert_eq!(
+            weighted,
+            CsMat::new(
+                (4, 4),
+                vec![0, 1, 2, 3, 3],
+                vec![0, 1, 2],
+                vec![5., 2.5, 3.5]
+            )
+        );
+        let unweighted = degree_matrix(adj.view(), false);
+        assert_eq!(
+            unweighted,
+            CsMat::new((4, 4), vec![0, 1, 2, 3, 3], vec![0, 1, 2], vec![2.; 3])
+        );
+        assert!(degree_matrix(adj.to_csc().view(), true).is_csc());
+
+        // without self-loops, the laplacian is D - A
+        let adj = CsMat::new(
+            (3, 3),
+            vec![0, 2, 4, 6],
+            vec![1, 2, 0, 2, 0, 1],
+            vec![2., 3., 2., 0.5, 3., 0.5],
+        );
+        let d = degree_matrix(adj.view(), true);
+        assert_eq!(laplacian(adj.view()), &d - &adj);
+    }
+}
@@ -3,6 +3,10 @@ use crate::sparse::prelude::*;
 
 /// Compute the Kronecker product between two matrices
 ///
+/// The product of a `(m, n)` matrix `a` and a `(p, q)` matrix `b` is the
+/// `(m * p, n * q)` block matrix where the block `(i, j)` is
+/// `a[[i, j]] * b`. Its number of non-zeros is `a.nnz() * b.nnz()`.
+///
 /// The storage order of the product will be based on the first matrix.
 /// This method will perform a clone if the two matrices
 /// have different storage order
@@ -31,7 +35,7 @@ pub fn kronecker_product<
         let shape = (a_shape.0 * b_shape.0, a_shape.1 * b_shape.1);
         let mut values = Vec::with_capacity(nnz);
         let mut indices = Vec::with_capacity(nnz);
-        let mut indptr = Vec::with_capacity(shape.1 + 1);
+        let mut indptr = Vec::with_capacity(shape.0 + 1);
 
         let mut element_count = Iptr::zero();
         indptr.push(element_count);
@@ -110,3 +114,34 @@ fn test_kronecker_product() {
     let c = kronecker_product(a.view(), b.view());
     check(c.view());
 }
+
+#[test]
+fn kronecker_product_dense_reference() {
+    let a = crate::test_data::mat3();
+    let b = CsMat::new_csc(
+        (2, 3),
+        vec![0, 1, 1, 3],
+        vec![1, 0, 1],
+        vec![2., -1., 0.5],
+    );
+    let a_dense = a.to_dense();
+    let b_dense = b.to_dense();
+    let (p, q) = b.shape();
+    let mut expected = ndarray::Array2::zeros((a.rows() * p, a.cols() * q));
+    for ((i, j), &a_ij) in a_dense.indexed_iter() {
+        for ((k, l), &b_kl) in b_dense.indexed_iter() {
+            expected[[i * p + k, j * q + l]] = a_ij * b_kl;
+        }
+    }
+
+    let c = kronecker_product(a.view(), b.view());
+    assert!(c.is_csr());
+    assert_eq!(c.shape(), (a.rows() * p, a.cols() * q));
+    assert_eq!(c.nnz(), a.nnz() * b.nnz());
+    assert_eq!(c.to_dense(), expected);
+
+    let c = kronecker_product(a.to_csc().view(), b.view());
+    assert!(c.is_csc());
+    assert_eq!(c.nnz(), a.nnz() * b.nnz());
+    assert_eq!(c.to_dense(), expected);
+}
@@ -1,15 +1,30 @@
 ///! Sparse linear algebra
 ///!
 ///! This module contains solvers for sparse linear systems. Currently
-///! there are solver for sparse triangular systems and symmetric systems.
+///! there are solver for sparse triangular systems and symmetric systems,
+///! as well as a conjugate gradient solver for symmetric positive definite
+///! systems, which can be preconditioned by an incomplete Cholesky
+///! factorization, and an LU factorization for general square systems.
+///! The dominant eigenpair of a matrix can be estimated by power iteration.
+use crate::indexing::SpIndex;
+use crate::sparse::prod;
+use crate::sparse::CsMatViewI;
 use num_traits::Num;
 use std::iter::IntoIterator;
 
+pub mod cg;
 pub mod etree;
+pub mod ichol;
+pub mod lu;
 pub mod ordering;
+pub mod power;
 pub mod trisolve;
 
+pub use self::cg::{cg, pcg};
+pub use self::ichol::ichol0;
+pub use self::lu::lu;
 pub use self::ordering::reverse_cuthill_mckee;
+pub use self::power::power_iteration;
 
 /// Diagonal solve
 pub fn diag_solve<'a, N, I1, I2>(diag: I1, x: I2)
@@ -22,3 +37,27 @@ where
         *xv = *xv / *dv;
     }
 }
+
+/// Dot product of two dense vectors
+pub(crate) fn dot(x: &[f64], y: &[f64]) -> f64 {
+    x.iter().zip(y.iter()).map(|(&a, &b)| a * b).sum()
+}
+
+/// Compute `res = mat * x` for a matrix in either storage
+pub(crate) fn mat_vec<I, Iptr>(
+    mat: CsMatViewI<f64, I, Iptr>,
+    x: &[f64],
+    res: &mut [f64],
+) where
+    I: SpIndex,
+    Iptr: SpIndex,
+{
+    for r in res.iter_mut() {
+        *r = 0.;
+    }
+    if mat.is_csr() {
+        prod::mul_acc_mat_vec_csr(mat, x, res);
+    } else {
+        prod::mul_acc_mat_vec_csc(mat, x, res);
+    }
+}
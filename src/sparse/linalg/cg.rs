@@ -0,0 +1,226 @@
+//! Conjugate gradient solver for symmetric positive definite systems
+
+use crate::errors::SprsError;
+use crate::indexing::SpIndex;
+use crate::sparse::linalg::{dot, mat_vec};
+use crate::sparse::CsMatViewI;
+
+/// Solve the system `A x = b` using the conjugate gradient method, where
+/// `A` is a symmetric positive definite matrix.
+///
+/// The iterations start from `x = 0` and stop as soon as the euclidean norm
+/// of the residual `b - A x` is below `tol * ||b||`. On success, the
+/// solution is returned along with the number of iterations performed.
+///
+/// The matrix is only accessed through matrix-vector products, and can be
+/// stored either in CSR or CSC storage. Its symmetry is not checked.
+///
+/// Returns `SprsError::NotConverged` with the final residual norm if the
+/// tolerance could not be reached in `max_iter` iterations.
+///
+/// # Panics
+///
+/// - if `a` is not square
+/// - if the length of `b` does not match the dimension of `a`
+///
+/// # Example
+///
+/// ```rust
+/// use sprs::linalg::cg::cg;
+/// use sprs::CsMat;
+/// // | 4 1 | |x0|   |1|
+/// // | 1 3 | |x1| = |2|
+/// let a = CsMat::new((2, 2), vec![0, 2, 4], vec![0, 1, 0, 1],
+///                    vec![4., 1., 1., 3.]);
+/// let (x, iters) = cg(a.view(), &[1., 2.], 1e-10, 10).unwrap();
+/// assert!((x[0] - 1. / 11.).abs() < 1e-10);
+/// assert!((x[1] - 7. / 11.).abs() < 1e-10);
+/// assert!(iters <= 2);
+/// ```
+pub fn cg<I, Iptr>(
+    a: CsMatViewI<f64, I, Iptr>,
+    b: &[f64],
+    tol: f64,
+    max_iter: usize,
+) -> Result<(Vec<f64>, usize), SprsError>
+where
+    I: SpIndex,
+    Iptr: SpIndex,
+{
+    pcg(a, b, tol, max_iter, |_| {})
+}
+
+/// Solve the system `A x = b` using the preconditioned conjugate gradient
+/// method, where `A` is a symmetric positive definite matrix.
+///
+/// The preconditioner is given as a closure applying `M^-1` in place
+/// to a vector, where `M` is a symmetric positive definite approximation
+/// of `A`. For instance, given an incomplete Cholesky factor `L` computed
+/// by `linalg::ichol0`, the preconditioner `(L L^T)^-1` is applied by
+/// two triangular solves (see the example below).
+///
+/// Convergence, return values and panics are the same as for `cg`.
+///
+/// # Example
+///
+/// ```rust
+/// use sprs::linalg::cg::pcg;
+/// use sprs::linalg::ichol0;
+/// use sprs::linalg::trisolve::{lsolve_csr_dense_rhs, usolve_csc_dense_rhs};
+/// use sprs::CsMat;
+/// let a = CsMat::new((3, 3), vec![0, 2, 5, 7], vec![0, 1, 0, 1, 2, 1, 2],
+///                    vec![4., -1., -1., 4., -1., -1., 4.]);
+/// let l = ichol0(a.view()).unwrap();
+/// let (x, _) = pcg(a.view(), &[3., 2., 3.], 1e-10, 10, |r| {
+///     lsolve_csr_dense_rhs(l.view(), r).unwrap();
+///     usolve_csc_dense_rhs(l.transpose_view(), r).unwrap();
+/// })
+/// .unwrap();
+/// for xi in x {
+///     assert!((xi - 1.).abs() < 1e-10);
+/// }
+/// ```
+pub fn pcg<I, Iptr, F>(
+    a: CsMatViewI<f64, I, Iptr>,
+    b: &[f64],
+    tol: f64,
+    max_iter: usize,
+    mut precond: F,
+) -> Result<(Vec<f64>, usize), SprsError>
+where
+    I: SpIndex,
+    Iptr: SpIndex,
+    F: FnMut(&mut [f64]),
+{
+    let n = a.rows();
+    if a.cols() != n {
+        panic!("Non square matrix passed to solver");
+    }
+    if b.len() != n {
+        panic!("Dimension mismatch");
+    }
+    let threshold = tol * dot(b, b).sqrt();
+    let mut x = vec![0.; n];
+    let mut r = b.to_vec();
+    let mut rr = dot(&r, &r);
+    if rr.sqrt() <= threshold {
+        return Ok((x, 0));
+    }
+    let mut z = r.clone();
+    precond(&mut z);
+    let mut p = z.clone();
+    let mut ap = vec![0.; n];
+    let mut rz = dot(&r, &z);
+    for iter in 1..=max_iter {
+        mat_vec(a.view(), &p, &mut ap);
+        let alpha = rz / dot(&p, &ap);
+        for i in 0..n {
+            x[i] += alpha * p[i];
+            r[i] -= alpha * ap[i];
+        }
+        rr = dot(&r, &r);
+        if rr.sqrt() <= threshold {
+            return Ok((x, iter));
+        }
+        z.copy_from_slice(&r);
+        precond(&mut z);
+        let rz_new = dot(&r, &z);
+        let beta = rz_new / rz;
+        for (pi, &zi) in p.iter_mut().zip(z.iter()) {
+            *pi = zi + beta * *pi;
+        }
+        rz = rz_new;
+    }
+    Err(SprsError::NotConverged {
+        residual: rr.sqrt(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{cg, pcg};
+    use crate::errors::SprsError;
+    use crate::sparse::linalg::ichol0;
+    use crate::sparse::linalg::trisolve;
+    use crate::test_data::{laplacian_1d, laplacian_2d};
+
+    #[test]
+    fn cg_laplacian() {
+        // the solution of L x = e_0 + e_{n-1} is the constant vector 1
+        let n = 20;
+        let a = laplacian_1d(n);
+        let mut b = vec![0.; n];
+        b[0] = 1.;
+        b[n - 1] = 1.;
+        let (x, iters) = cg(a.view(), &b, 1e-12, 100).unwrap();
+        // in exact arithmetic, cg converges in at most n iterations
+        assert!(iters <= n);
+        for &xi in &x {
+            assert!((xi - 1.).abs() < 1e-10);
+        }
+
+        let (x_csc, _) = cg(a.to_csc().view(), &b, 1e-12, 100).unwrap();
+        for (&xi, &yi) in x.iter().zip(x_csc.iter()) {
+            assert!((xi - yi).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn cg_zero_rhs() {
+        let a = laplacian_1d(5);
+        let (x, iters) = cg(a.view(), &[0.; 5], 1e-10, 10).unwrap();
+        assert_eq!(iters, 0);
+        assert_eq!(x, vec![0.; 5]);
+    }
+
+    #[test]
+    fn cg_not_converged() {
+        let a = laplacian_1d(50);
+        let b = vec![1.; 50];
+        match cg(a.view(), &b, 1e-12, 3) {
+            Err(SprsError::NotConverged { residual }) => {
+                assert!(residual > 0.);
+            }
+            res => panic!("unexpected result {:?}", res),
+        }
+    }
+
+    #[test]
+    fn cg_no_iteration() {
+        // without any iteration, the residual is the norm of b
+        let a = laplacian_1d(5);
+        let b = vec![3., 0., 4., 0., 0.];
+        match cg(a.view(), &b, 1e-10, 0) {
+            Err(SprsError::NotConverged { residual }) => {
+                assert_eq!(residual, 5.);
+            }
+            res => panic!("unexpected result {:?}", res),
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Dimension mismatch")]
+    fn cg_fail_dim_mismatch() {
+        let a = laplacian_1d(5);
+        let _ = cg(a.view(), &[1.; 4], 1e-10, 10);
+    }
+
+    #[test]
+    fn pcg_ichol0_reduces_iterations() {
+        let a = laplacian_2d(12);
+        let n = a.rows();
+        let b: Vec<f64> = (0..n).map(|i| (i % 7) as f64 - 3.).collect();
+        let (x, iters) = cg(a.view(), &b, 1e-10, 500).unwrap();
+
+        let l = ichol0(a.view()).unwrap();
+        let (x_pcg, iters_pcg) = pcg(a.view(), &b, 1e-10, 500, |r| {
+            trisolve::lsolve_csr_dense_rhs(l.view(), r).unwrap();
+            trisolve::usolve_csc_dense_rhs(l.transpose_view(), r).unwrap();
+        })
+        .unwrap();
+        assert!(iters_pcg < iters);
+        for (&xi, &yi) in x.iter().zip(x_pcg.iter()) {
+            assert!((xi - yi).abs() < 1e-8);
+        }
+    }
+}
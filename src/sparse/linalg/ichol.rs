@@ -0,0 +1,177 @@
+//! Incomplete Cholesky factorization
+
+use crate::errors::SprsError;
+use crate::indexing::SpIndex;
+use crate::sparse::{CsMatI, CsMatViewI};
+
+/// Dot product of two sparse vectors given by their sorted indices
+/// and values
+fn sorted_dot<I: SpIndex>(
+    inds1: &[I],
+    vals1: &[f64],
+    inds2: &[I],
+    vals2: &[f64],
+) -> f64 {
+    let mut res = 0.;
+    let (mut k1, mut k2) = (0, 0);
+    while k1 < inds1.len() && k2 < inds2.len() {
+        let (i1, i2) = (inds1[k1].index(), inds2[k2].index());
+        if i1 < i2 {
+            k1 += 1;
+        } else if i2 < i1 {
+            k2 += 1;
+        } else {
+            res += vals1[k1] * vals2[k2];
+            k1 += 1;
+            k2 += 1;
+        }
+    }
+    res
+}
+
+/// Compute the zero fill-in incomplete Cholesky factorization, IC(0),
+/// of a symmetric positive definite matrix.
+///
+/// The returned factor `L` is a lower triangular matrix in CSR storage,
+/// with the same sparsity pattern as the lower triangular part of `A`,
+/// such that `L L^T` approximates `A`. Only the lower triangular part of
+/// `A` is read, its upper triangular part is assumed to be symmetric.
+///
+/// `L` is typically used as a preconditioner for `linalg::cg::pcg`,
+/// the product `(L L^T)^-1 r` being computed with
+/// `trisolve::lsolve_csr_dense_rhs` on `L` followed by
+/// `trisolve::usolve_csc_dense_rhs` on `L.transpose_view()`.
+///
+/// Returns `SprsError::SingularMatrix` if a diagonal element of `A` is
+/// missing, or if the factorization meets a non-positive pivot, which
+/// can happen even for positive definite matrices.
+///
+/// # Panics
+///
+/// If the matrix is not square.
+pub fn ichol0<I, Iptr>(
+    a: CsMatViewI<f64, I, Iptr>,
+) -> Result<CsMatI<f64, I, Iptr>, SprsError>
+where
+    I: SpIndex,
+    Iptr: SpIndex,
+{
+    let n = a.rows();
+    if a.cols() != n {
+        panic!("Non square matrix passed to incomplete Cholesky");
+    }
+    let a = a.to_csr();
+    let mut indptr: Vec<Iptr> = Vec::with_capacity(n + 1);
+    let mut indices: Vec<I> = Vec::new();
+    let mut data: Vec<f64> = Vec::new();
+    indptr.push(Iptr::zero());
+
+    // L[i, j] = (A[i, j] - sum_{k < j} L[i, k] L[j, k]) / L[j, j]
+    // L[i, i] = sqrt(A[i, i] - sum_{k < i} L[i, k]^2)
+    for (i, row) in a.outer_iterator().enumerate() {
+        let row_start = indices.len();
+        let mut has_diag = false;
+        for (j, &a_ij) in row.iter() {
+            if j > i {
+                break;
+            }
+            let (start_j, stop_j) =
+                (indptr[j].index(), indptr.get(j + 1).map(|x| x.index()));
+            // the row j of L is complete if j < i, and ends with its
+            // diagonal element
+            let (row_j_inds, row_j_vals) = match stop_j {
+                Some(stop_j) => {
+                    (&indices[start_j..stop_j - 1], &data[start_j..stop_j - 1])
+                }
+                None => (&indices[start_j..], &data[start_j..]),
+            };
+            let s = sorted_dot(
+                &indices[row_start..],
+                &data[row_start..],
+                row_j_inds,
+                row_j_vals,
+            );
+            let val = if j < i {
+                let l_jj = data[indptr[j + 1].index() - 1];
+                (a_ij - s) / l_jj
+            } else {
+                has_diag = true;
+                let pivot = a_ij - s;
+                if pivot <= 0. {
+                    return Err(SprsError::SingularMatrix);
+                }
+                pivot.sqrt()
+            };
+            indices.push(I::from_usize(j));
+            data.push(val);
+        }
+        if !has_diag {
+            return Err(SprsError::SingularMatrix);
+        }
+        indptr.push(Iptr::from_usize(indices.len()));
+    }
+    Ok(CsMatI::new_trusted(
+        crate::CSR,
+        (n, n),
+        indptr,
+        indices,
+        data,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::ichol0;
+    use crate::errors::SprsError;
+    use crate::sparse::CsMat;
+    use crate::test_data::{laplacian_1d, laplacian_2d};
+    use ndarray::arr2;
+
+    #[test]
+    fn ichol0_tridiagonal_is_exact() {
+        // there is no fill-in for a tridiagonal matrix, IC(0) is the
+        // exact Cholesky factorization
+        let a = laplacian_1d(6);
+        let l = ichol0(a.view()).unwrap();
+        let llt = l.to_dense().dot(&l.transpose_view().to_dense());
+        for (&x, &y) in llt.iter().zip(a.to_dense().iter()) {
+            assert!((x - y).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn ichol0_keeps_pattern() {
+        let a = laplacian_2d(4);
+        let l = ichol0(a.view()).unwrap();
+        let mut nnz_lower = 0;
+        for (_, (row, col)) in a.iter() {
+            if col <= row {
+                nnz_lower += 1;
+                assert!(l.get(row, col).is_some());
+            }
+        }
+        assert_eq!(l.nnz(), nnz_lower);
+        // L L^T matches A on the pattern of A
+        let llt = l.to_dense().dot(&l.transpose_view().to_dense());
+        for (&v, (row, col)) in a.iter() {
+            assert!((llt[[row, col]] - v).abs() < 1e-12);
+        }
+        // the csc input gives the same factor
+        assert_eq!(ichol0(a.to_csc().view()).unwrap(), l);
+    }
+
+    #[test]
+    fn ichol0_not_positive() {
+        let a = CsMat::csr_from_dense(
+            arr2(&[[1., 2., 0.], [2., 1., 0.], [0., 0., 1.]]).view(),
+            0.,
+        );
+        assert_eq!(ichol0(a.view()), Err(SprsError::SingularMatrix));
+
+        let a = CsMat::csr_from_dense(
+            arr2(&[[1., 0., 0.], [0., 0., 1.], [0., 1., 1.]]).view(),
+            0.,
+        );
+        assert_eq!(ichol0(a.view()), Err(SprsError::SingularMatrix));
+    }
+}
@@ -0,0 +1,304 @@
+//! Sparse LU factorization with partial pivoting
+
+use crate::errors::SprsError;
+use crate::indexing::SpIndex;
+use crate::sparse::{CsMatI, CsMatViewI};
+
+/// The factors `(L, U, perm)` of an LU factorization, see `lu`
+pub type LuFactors<I, Iptr> =
+    (CsMatI<f64, I, Iptr>, CsMatI<f64, I, Iptr>, Vec<usize>);
+
+/// Compute the LU factorization of a square matrix, `P A = L U`, using a
+/// left-looking algorithm with partial pivoting.
+///
+/// Returns the factors `(L, U, perm)`, where:
+///
+/// - `L` is a unit lower triangular matrix in CSC storage, with its unit
+///   diagonal explicitly stored,
+/// - `U` is an upper triangular matrix in CSC storage,
+/// - `perm` describes the row permutation `P`: the row `k` of `P A`
+///   is the row `perm[k]` of `A`.
+///
+/// The factors can be used with the triangular solvers in
+/// `linalg::trisolve`: to solve `A x = b`, compute `y[k] = b[perm[k]]`,
+/// then solve `L z = y` and `U x = z`.
+///
+/// No fill-reducing ordering is performed, so the factors of a large
+/// matrix can be much denser than the matrix itself. Pre-ordering the
+/// columns of `A` can help reduce fill-in.
+///
+/// Returns `SprsError::SingularMatrix` if no non-zero pivot can be
+/// found for a column.
+///
+/// # Panics
+///
+/// If the matrix is not square.
+///
+/// # Example
+///
+/// ```rust
+/// use sprs::linalg::lu::lu;
+/// use sprs::linalg::trisolve::{lsolve_csc_dense_rhs, usolve_csc_dense_rhs};
+/// use sprs::CsMat;
+/// // | 0 2 |     |4|
+/// // | 1 1 | x = |3|
+/// let a = CsMat::new((2, 2), vec![0, 1, 3], vec![1, 0, 1],
+///                    vec![2., 1., 1.]);
+/// let b = [4., 3.];
+/// let (l, u, perm) = lu(a.view()).unwrap();
+/// let mut x: Vec<f64> = perm.iter().map(|&i| b[i]).collect();
+/// lsolve_csc_dense_rhs(l.view(), &mut x).unwrap();
+/// usolve_csc_dense_rhs(u.view(), &mut x).unwrap();
+/// assert_eq!(x, vec![1., 2.]);
+/// ```
+pub fn lu<I, Iptr>(
+    a: CsMatViewI<f64, I, Iptr>,
+) -> Result<LuFactors<I, Iptr>, SprsError>
+where
+    I: SpIndex,
+    Iptr: SpIndex,
+{
+    let n = a.rows();
+    if a.cols() != n {
+        panic!("Non square matrix passed to LU factorization");
+    }
+    let a = a.to_csc();
+
+    // columns of L, indexed by the original row indices during the
+    // factorization, excluding the unit diagonal
+    let mut l_cols: Vec<Vec<(usize, f64)>> = Vec::with_capacity(n);
+    let mut u_indptr = Vec::with_capacity(n + 1);
+    let mut u_indices = Vec::new();
+    let mut u_data = Vec::new();
+    u_indptr.push(Iptr::zero());
+
+    let mut perm = Vec::with_capacity(n);
+    let mut pinv: Vec<Option<usize>> = vec![None; n];
+
+    // dense workspace, with the list of its possibly non-zero entries in
+    // depth first postorder, ie reverse topological order
+    let mut x = vec![0.; n];
+    let mut marked = vec![false; n];
+    let mut pattern = Vec::new();
+    // depth first search stack of (row, index of the next child)
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+    let mut u_col = Vec::new();
+
+    for j in 0..n {
+        // The non-zeros of the solution of the triangular system are the
+        // rows reachable from the non-zeros of the column in the graph of
+        // L, where a pivoted row links to the rows of its column of L
+        // (Gilbert-Peierls). They are collected in depth first postorder.
+        for (row, &val) in a.outer_view(j).expect("in bounds").iter() {
+            x[row] = val;
+            if marked[row] {
+                continue;
+            }
+            marked[row] = true;
+            stack.push((row, 0));
+            while let Some(top) = stack.len().checked_sub(1) {
+                let (node, child) = stack[top];
+                let children: &[(usize, f64)] = match pinv[node] {
+                    Some(k) => &l_cols[k],
+                    None => &[],
+                };
+                if let Some(&(next, _)) = children.get(child) {
+                    stack[top].1 += 1;
+                    if !marked[next] {
+                        marked[next] = true;
+                        stack.push((next, 0));
+                    }
+                } else {
+                    stack.pop();
+                    pattern.push(node);
+                }
+            }
+        }
+        // solve against the reached columns of L, in topological order
+        for &row in pattern.iter().rev() {
+            let k = match pinv[row] {
+                Some(k) => k,
+                None => continue,
+            };
+            let u_kj = x[row];
+            if u_kj == 0. {
+                continue;
+            }
+            u_col.push((k, u_kj));
+            for &(l_row, l_val) in &l_cols[k] {
+                x[l_row] -= l_val * u_kj;
+            }
+        }
+        u_col.sort_by_key(|&(k, _)| k);
+        for &(k, u_kj) in &u_col {
+            u_indices.push(I::from_usize(k));
+            u_data.push(u_kj);
+        }
+        u_col.clear();
+        // partial pivoting on the rows not yet pivoted
+        let mut pivot = None;
+        let mut pivot_abs = 0.;
+        for &row in &pattern {
+            if pinv[row].is_none() && x[row].abs() > pivot_abs {
+                pivot_abs = x[row].abs();
+                pivot = Some(row);
+            }
+        }
+        let pivot = pivot.ok_or(SprsError::SingularMatrix)?;
+        let pivot_val = x[pivot];
+        pinv[pivot] = Some(j);
+        perm.push(pivot);
+        u_indices.push(I::from_usize(j));
+        u_data.push(pivot_val);
+        u_indptr.push(Iptr::from_usize(u_indices.len()));
+
+        let mut l_col = Vec::new();
+        for &row in &pattern {
+            if pinv[row].is_none() && x[row] != 0. {
+                l_col.push((row, x[row] / pivot_val));
+            }
+            x[row] = 0.;
+            marked[row] = false;
+        }
+        pattern.clear();
+        l_cols.push(l_col);
+    }
+
+    let mut l_indptr = Vec::with_capacity(n + 1);
+    let mut l_indices = Vec::new();
+    let mut l_data = Vec::new();
+    l_indptr.push(Iptr::zero());
+    for (j, l_col) in l_cols.iter_mut().enumerate() {
+        for entry in l_col.iter_mut() {
+            entry.0 = pinv[entry.0].expect("all rows are pivoted");
+        }
+        l_col.sort_by_key(|&(row, _)| row);
+        l_indices.push(I::from_usize(j));
+        l_data.push(1.);
+        for &(row, val) in l_col.iter() {
+            l_indices.push(I::from_usize(row));
+            l_data.push(val);
+        }
+        l_indptr.push(Iptr::from_usize(l_indices.len()));
+    }
+
+    let l = CsMatI::new_csc((n, n), l_indptr, l_indices, l_data);
+    let u = CsMatI::new_csc((n, n), u_indptr, u_indices, u_data);
+    Ok((l, u, perm))
+}
+
+#[cfg(test)]
+mod test {
+    use super::lu;
+    use crate::errors::SprsError;
+    use crate::sparse::linalg::trisolve;
+    use crate::sparse::CsMat;
+    use crate::test_data::laplacian_1d;
+    use ndarray::{arr2, Array2};
+
+    fn check_factors(a: &CsMat<f64>) {
+        let (l, u, perm) = lu(a.view()).unwrap();
+        let n = a.rows();
+        let dense = a.to_dense();
+        let mut pa = Array2::zeros((n, n));
+        for (k, &row) in perm.iter().enumerate() {
+            pa.row_mut(k).assign(&dense.row(row));
+        }
+        let lu_prod = l.to_dense().dot(&u.to_dense());
+        for (&x, &y) in pa.iter().zip(lu_prod.iter()) {
+            assert!((x - y).abs() < 1e-12);
+        }
+        // the factors are triangular
+        for (&v, (row, col)) in l.iter() {
+            assert!(row >= col);
+            if row == col {
+                assert_eq!(v, 1.);
+            }
+        }
+        for (_, (row, col)) in u.iter() {
+            assert!(row <= col);
+        }
+    }
+
+    #[test]
+    fn lu_needs_pivoting() {
+        // a zero leading entry requires a row exchange
+        let a = CsMat::csr_from_dense(
+            arr2(&[
+                [0., 2., 0., 1.],
+                [3., 0., 1., 0.],
+                [1., 1., 4., 0.],
+                [0., 5., 0., 2.],
+            ])
+            .view(),
+            0.,
+        );
+        check_factors(&a);
+        check_factors(&a.to_csc());
+    }
+
+    #[test]
+    fn lu_solve_unsymmetric() {
+        let a = CsMat::csr_from_dense(
+            arr2(&[
+                [2., 0., 0., 1., 0.],
+                [0., 0., 3., 0., 1.],
+                [1., 4., 0., 0., 0.],
+                [0., 0., 1., 2., 0.],
+                [0., 1., 0., 0., 5.],
+            ])
+            .view(),
+            0.,
+        );
+        check_factors(&a);
+        let x_true = vec![1., -2., 3., 0.5, -1.];
+        let b = (&a * &ndarray::arr1(&x_true)).to_vec();
+        let (l, u, perm) = lu(a.view()).unwrap();
+        let mut x: Vec<f64> = perm.iter().map(|&i| b[i]).collect();
+        trisolve::lsolve_csc_dense_rhs(l.view(), &mut x).unwrap();
+        trisolve::usolve_csc_dense_rhs(u.view(), &mut x).unwrap();
+        for (&xi, &yi) in x.iter().zip(x_true.iter()) {
+            assert!((xi - yi).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn lu_large_banded() {
+        // the triangular solves only visit the reachable columns of L, so
+        // this would take minutes if each column walked all the previous
+        // ones
+        let n = 100_000;
+        let a = laplacian_1d(n);
+        let (l, u, perm) = lu(a.view()).unwrap();
+        // no pivoting is needed, and the factors are bidiagonal
+        assert!(perm.iter().enumerate().all(|(k, &row)| k == row));
+        assert_eq!(l.nnz(), 2 * n - 1);
+        assert_eq!(u.nnz(), 2 * n - 1);
+        // the solution of L x = e_0 + e_{n-1} is the constant vector 1
+        let mut x = vec![0.; n];
+        x[0] = 1.;
+        x[n - 1] = 1.;
+        trisolve::lsolve_csc_dense_rhs(l.view(), &mut x).unwrap();
+        trisolve::usolve_csc_dense_rhs(u.view(), &mut x).unwrap();
+        for &xi in &x {
+            assert!((xi - 1.).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn lu_singular() {
+        let a = CsMat::csr_from_dense(
+            arr2(&[[1., 2., 0.], [2., 4., 0.], [0., 0., 1.]]).view(),
+            0.,
+        );
+        assert_eq!(lu(a.view()), Err(SprsError::SingularMatrix));
+    }
+
+    #[test]
+    #[should_panic]
+    fn lu_fail_non_square() {
+        let a =
+            CsMat::<f64>::new((2, 3), vec![0, 1, 2], vec![0, 1], vec![1., 1.]);
+        let _ = lu(a.view());
+    }
+}
@@ -543,6 +543,29 @@ where
 ///     mat, start::PseudoPeripheral::new(), order::Reversed::new()
 /// )
 /// ```
+///
+/// The nonzeros of the matrix are treated as the edges of a graph, whose
+/// structure should thus be symmetric. The computed permutation can be
+/// applied with
+/// [`permute_symmetric`](../../struct.CsMatBase.html#method.permute_symmetric):
+///
+/// ```rust
+/// use sprs::linalg::reverse_cuthill_mckee;
+/// use sprs::CsMat;
+/// // a path graph 0 - 2 - 1 - 3 with a bandwidth of 2
+/// let mat = CsMat::new(
+///     (4, 4),
+///     vec![0, 2, 5, 8, 10],
+///     vec![0, 2, 1, 2, 3, 0, 1, 2, 1, 3],
+///     vec![1.; 10],
+/// );
+/// let ordering = reverse_cuthill_mckee(mat.view());
+/// let reordered = mat.permute_symmetric(ordering.perm.view());
+/// // the reordered matrix is tridiagonal
+/// for (_, (row, col)) in reordered.iter() {
+///     assert!(row <= col + 1 && col <= row + 1);
+/// }
+/// ```
 pub fn reverse_cuthill_mckee<N, I, Iptr>(
     mat: CsMatViewI<N, I, Iptr>,
 ) -> Ordering<I>
@@ -639,6 +662,25 @@ mod test {
         assert_eq!(&ordering.perm.vec(), &correct_perm.vec());
     }
 
+    #[test]
+    fn reverse_cuthill_mckee_reduces_bandwidth() {
+        let lap_mat = unconnected_graph_lap();
+        let ordering = reverse_cuthill_mckee(lap_mat.view());
+        let reordered = lap_mat.permute_symmetric(ordering.perm.view());
+        assert_eq!(reordered.nnz(), lap_mat.nnz());
+        assert!(reordered.bandwidth() < lap_mat.bandwidth());
+
+        // a tridiagonal matrix with scrambled vertices gets its
+        // bandwidth back
+        let scramble = Permutation::new(vec![3, 7, 0, 5, 1, 6, 2, 4]);
+        let tridiag = crate::test_data::laplacian_1d(8);
+        let scrambled = tridiag.permute_symmetric(scramble.view());
+        assert!(scrambled.bandwidth() > 1);
+        let ordering = reverse_cuthill_mckee(scrambled.view());
+        let reordered = scrambled.permute_symmetric(ordering.perm.view());
+        assert_eq!(reordered.bandwidth(), 1);
+    }
+
     #[test]
     fn reverse_cuthill_mckee_eye() {
         let mat = CsMat::<f64>::eye(3);
@@ -0,0 +1,140 @@
+//! Power iteration for the dominant eigenpair of a matrix
+
+use crate::errors::SprsError;
+use crate::indexing::SpIndex;
+use crate::sparse::linalg::{dot, mat_vec};
+use crate::sparse::CsMatViewI;
+
+/// Estimate the eigenvalue of largest magnitude of a square matrix, and an
+/// associated eigenvector, using the power iteration method.
+///
+/// Starting from the normalized vector of ones, the iterate is repeatedly
+/// multiplied by `A` and normalized. The eigenvalue is estimated by the
+/// Rayleigh quotient `x^T A x`, and the iterations stop once the residual
+/// `||A x - lambda x||` is below `tol * |lambda|`.
+///
+/// On success, returns the eigenvalue, the normalized eigenvector and the
+/// number of iterations performed.
+///
+/// The convergence rate depends on the ratio between the two largest
+/// eigenvalue magnitudes: when several eigenvalues share the largest
+/// magnitude (eg `1` and `-1`) the method does not converge.
+/// If the starting vector is orthogonal to the dominant eigenvector,
+/// another eigenpair can be found instead.
+///
+/// Returns `SprsError::NotConverged` with the last residual norm if the
+/// tolerance is not reached in `max_iter` iterations.
+///
+/// # Panics
+///
+/// If the matrix is not square.
+///
+/// # Example
+///
+/// ```rust
+/// use sprs::linalg::power_iteration;
+/// use sprs::CsMat;
+/// // | 2 1 |
+/// // | 1 2 | has eigenvalues 3 and 1
+/// let a = CsMat::new((2, 2), vec![0, 2, 4], vec![0, 1, 0, 1],
+///                    vec![2., 1., 1., 2.]);
+/// let (lambda, x, _) = power_iteration(a.view(), 1e-10, 100).unwrap();
+/// assert!((lambda - 3.).abs() < 1e-8);
+/// assert!((x[0] - x[1]).abs() < 1e-8);
+/// ```
+pub fn power_iteration<I, Iptr>(
+    a: CsMatViewI<f64, I, Iptr>,
+    tol: f64,
+    max_iter: usize,
+) -> Result<(f64, Vec<f64>, usize), SprsError>
+where
+    I: SpIndex,
+    Iptr: SpIndex,
+{
+    let n = a.rows();
+    if a.cols() != n {
+        panic!("Non square matrix passed to power iteration");
+    }
+    let mut x = vec![1. / (n as f64).sqrt(); n];
+    let mut ax = vec![0.; n];
+    let mut residual = std::f64::INFINITY;
+    for iter in 1..=max_iter {
+        mat_vec(a.view(), &x, &mut ax);
+        let lambda = dot(&x, &ax);
+        residual = ax
+            .iter()
+            .zip(x.iter())
+            .map(|(&y, &xi)| (y - lambda * xi) * (y - lambda * xi))
+            .sum::<f64>()
+            .sqrt();
+        if residual <= tol * lambda.abs() {
+            return Ok((lambda, x, iter));
+        }
+        let norm = dot(&ax, &ax).sqrt();
+        if norm == 0. {
+            // x is in the kernel of A, and all eigenvalues may be zero
+            return Ok((0., x, iter));
+        }
+        for (xi, &y) in x.iter_mut().zip(ax.iter()) {
+            *xi = y / norm;
+        }
+    }
+    Err(SprsError::NotConverged { residual })
+}
+
+#[cfg(test)]
+mod test {
+    use super::power_iteration;
+    use crate::errors::SprsError;
+    use crate::sparse::CsMat;
+    use crate::test_data::laplacian_1d;
+
+    #[test]
+    fn power_iteration_laplacian() {
+        // the eigenvalues of the 1D laplacian of size n are
+        // 2 - 2 cos(k pi / (n + 1)), for k in 1..=n. For an odd n, the
+        // dominant eigenvector is not orthogonal to the starting vector.
+        let n = 9;
+        let a = laplacian_1d(n);
+        let (lambda, x, iters) = power_iteration(a.view(), 1e-6, 5000).unwrap();
+        let expected =
+            2. - 2. * (n as f64 * std::f64::consts::PI / (n as f64 + 1.)).cos();
+        assert!(iters > 1);
+        assert!((lambda - expected).abs() < 1e-8);
+        // x is a normalized eigenvector
+        let ax = &a * &ndarray::arr1(&x);
+        let norm: f64 = x.iter().map(|v| v * v).sum::<f64>().sqrt();
+        assert!((norm - 1.).abs() < 1e-12);
+        for (&y, &xi) in ax.iter().zip(x.iter()) {
+            assert!((y - lambda * xi).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn power_iteration_negative_dominant() {
+        // | 1  0  0 |
+        // | 0 -4  0 |
+        // | 0  0  2 |
+        let a = CsMat::new(
+            (3, 3),
+            vec![0, 1, 2, 3],
+            vec![0, 1, 2],
+            vec![1., -4., 2.],
+        );
+        let (lambda, x, _) = power_iteration(a.view(), 1e-12, 200).unwrap();
+        assert!((lambda + 4.).abs() < 1e-10);
+        assert!((x[1].abs() - 1.).abs() < 1e-10);
+    }
+
+    #[test]
+    fn power_iteration_not_converged() {
+        // the eigenvalues 1 and -1 have the same magnitude
+        let a = CsMat::new((2, 2), vec![0, 1, 2], vec![0, 1], vec![1., -1.]);
+        match power_iteration(a.view(), 1e-10, 50) {
+            Err(SprsError::NotConverged { residual }) => {
+                assert!(residual > 0.)
+            }
+            res => panic!("unexpected result {:?}", res),
+        }
+    }
+}
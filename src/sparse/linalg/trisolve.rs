@@ -33,6 +33,9 @@ fn check_solver_dimensions<N, I, Iptr, V: ?Sized>(
 ///
 /// This solve does not assume the input matrix to actually be
 /// triangular, instead it ignores the upper triangular part.
+///
+/// Returns `SprsError::SingularMatrix` if a diagonal element is zero
+/// or missing.
 pub fn lsolve_csr_dense_rhs<N, I, Iptr, V: ?Sized>(
     lower_tri_mat: CsMatViewI<N, I, Iptr>,
     rhs: &mut V,
@@ -149,9 +152,9 @@ where
 ///
 /// The solve results are written into the provided values.
 ///
-/// This method does not require the matrix to actually be lower triangular,
+/// This method does not require the matrix to actually be upper triangular,
 /// but is most efficient if the last element of each column
-/// is the diagonal element (thus actual sorted lower triangular matrices work
+/// is the diagonal element (thus actual sorted upper triangular matrices work
 /// best). Otherwise, logarithmic search for the diagonal element
 /// has to be performed for each column.
 pub fn usolve_csc_dense_rhs<N, I, Iptr, V: ?Sized>(
@@ -200,13 +203,16 @@ where
     Ok(())
 }
 
-/// Solve a sparse lower triangular matrix system, with a csr matrix
+/// Solve a sparse upper triangular matrix system, with a csr matrix
 /// and a dense vector as inputs
 ///
 /// The solve results are written into the provided values.
 ///
 /// This solve does not assume the input matrix to actually be
-/// triangular, instead it ignores the upper triangular part.
+/// triangular, instead it ignores the lower triangular part.
+///
+/// Returns `SprsError::SingularMatrix` if a diagonal element is zero
+/// or missing.
 pub fn usolve_csr_dense_rhs<N, I, Iptr, V: ?Sized>(
     upper_tri_mat: CsMatViewI<N, I, Iptr>,
     rhs: &mut V,
@@ -337,7 +343,6 @@ where
     // solve for the non-zero values into dense workspace
     rhs.scatter(x_workspace);
     for &ind in dstack.iter_right().map(stack::extract_stack_val) {
-        println!("ind: {}", ind);
         let col = lower_tri_mat.outer_view(ind).expect("ind not in bounds");
         lspsolve_csc_process_col(col, ind, x_workspace)?;
     }
@@ -347,6 +352,7 @@ where
 #[cfg(test)]
 mod test {
 
+    use crate::errors::SprsError;
     use crate::sparse::{CsMat, CsVec};
     use crate::stack::{self, DStack};
     use std::collections::HashSet;
@@ -423,6 +429,76 @@ mod test {
         assert_eq!(x, vec![3, 1, 1]);
     }
 
+    #[test]
+    fn lsolve_csr_known_system() {
+        // |2       | |1 |   |2 |
+        // |1 4     | |-1|   |-3|
+        // |  3 1   | |2 | = |-1|
+        // |1   2 .5| |4 |   |7 |
+        let l = CsMat::new(
+            (4, 4),
+            vec![0, 1, 3, 5, 8],
+            vec![0, 0, 1, 1, 2, 0, 2, 3],
+            vec![2., 1., 4., 3., 1., 1., 2., 0.5],
+        );
+        let x_true = vec![1., -1., 2., 4.];
+        let mut x = (&l * &ndarray::arr1(&x_true)).to_vec();
+        assert_eq!(x, vec![2., -3., -1., 7.]);
+
+        super::lsolve_csr_dense_rhs(l.view(), &mut x).unwrap();
+        assert_eq!(x, x_true);
+
+        // the transpose is upper triangular, solve the same way
+        let u = l.transpose_view().to_csr();
+        let mut x = (&u * &ndarray::arr1(&x_true)).to_vec();
+        super::usolve_csr_dense_rhs(u.view(), &mut x).unwrap();
+        assert_eq!(x, x_true);
+    }
+
+    #[test]
+    fn lsolve_csr_zero_pivot() {
+        // |1    |
+        // |1 0  |
+        // |0 1 1|
+        let l = CsMat::new(
+            (3, 3),
+            vec![0, 1, 3, 5],
+            vec![0, 0, 1, 1, 2],
+            vec![1., 1., 0., 1., 1.],
+        );
+        let mut x = vec![1., 2., 3.];
+        assert_eq!(
+            super::lsolve_csr_dense_rhs(l.view(), &mut x),
+            Err(SprsError::SingularMatrix)
+        );
+    }
+
+    #[test]
+    fn usolve_csr_missing_pivot() {
+        // |1 1  |
+        // |    1|
+        // |    1|
+        let u = CsMat::new(
+            (3, 3),
+            vec![0, 2, 3, 4],
+            vec![0, 1, 2, 2],
+            vec![1., 1., 1., 1.],
+        );
+        let mut x = vec![1., 2., 3.];
+        assert_eq!(
+            super::usolve_csr_dense_rhs(u.view(), &mut x),
+            Err(SprsError::SingularMatrix)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Dimension mismatch")]
+    fn lsolve_csr_fail_dim_mismatch() {
+        let l = CsMat::<f64>::eye(3);
+        let mut x = vec![1., 2.];
+        let _ = super::lsolve_csr_dense_rhs(l.view(), &mut x);
+    }
+
     #[test]
     fn lspsolve_csc() {
         // |1        | | |   | |
@@ -3,9 +3,12 @@ use crate::sparse::compressed::SpMatView;
 ///! Sparse matrix product
 use crate::sparse::prelude::*;
 use crate::sparse::vec::DenseVector;
+use crate::sparse::CompressedStorage::CSR;
 use crate::Ix2;
 use ndarray::{ArrayView, ArrayViewMut, Axis};
 use num_traits::Num;
+#[cfg(feature = "multi_thread")]
+use rayon::prelude::*;
 use std::iter::Sum;
 
 /// Compute the dot product of two sparse vectors, using binary search to find matching indices.
@@ -47,6 +50,16 @@ where
 
 /// Multiply a sparse CSC matrix with a dense vector and accumulate the result
 /// into another dense vector
+///
+/// The inner loop does not perform bounds checking: the indices of each
+/// column are checked against the number of rows beforehand. This check
+/// does not rely on the invariants established when constructing the
+/// matrix, as a custom `Deref` storage is not trusted to always return the
+/// slices that were validated.
+///
+/// # Panics
+///
+/// If a stored index is out of bounds
 pub fn mul_acc_mat_vec_csc<N, I, Iptr, V>(
     mat: CsMatViewI<N, I, Iptr>,
     in_vec: V,
@@ -67,15 +80,34 @@ pub fn mul_acc_mat_vec_csc<N, I, Iptr, V>(
 
     for (col_ind, vec) in mat.outer_iterator().enumerate() {
         let multiplier = in_vec.index(col_ind);
+        let rows = res_vec.len();
+        assert!(
+            vec.indices().iter().all(|i| i.index_unchecked() < rows),
+            "Out of bounds index"
+        );
         for (row_ind, &value) in vec.iter() {
-            // TODO: unsafe access to value? needs bench
-            res_vec[row_ind] = res_vec[row_ind] + *multiplier * value;
+            // Safety: row_ind < res_vec.len(), all the indices of vec have
+            // been checked above
+            let tv = unsafe { res_vec.get_unchecked_mut(row_ind) };
+            *tv = *tv + *multiplier * value;
         }
     }
 }
 
 /// Multiply a sparse CSR matrix with a dense vector and accumulate the result
 /// into another dense vector
+///
+/// The inner loop does not perform bounds checking: the indices of each row
+/// are checked against the dimension of `in_vec` beforehand. This check
+/// does not rely on the invariants established when constructing the
+/// matrix, as a custom `Deref` storage is not trusted to always return the
+/// slices that were validated. Custom `DenseVector` implementations that do
+/// not override `DenseVector::index_unchecked` fall back to checked
+/// accesses.
+///
+/// # Panics
+///
+/// If a stored index is out of bounds
 pub fn mul_acc_mat_vec_csr<N, I, Iptr, V>(
     mat: CsMatViewI<N, I, Iptr>,
     in_vec: V,
@@ -98,13 +130,253 @@ pub fn mul_acc_mat_vec_csr<N, I, Iptr, V>(
         // mat.row() == res_vec.len() and now the row_ind is within the
         // range of [0, mat.row). So it should be safe.
         let tv = res_vec.get_mut(row_ind).unwrap();
+        let cols = in_vec.dim();
+        assert!(
+            vec.indices().iter().all(|i| i.index_unchecked() < cols),
+            "Out of bounds index"
+        );
         for (col_ind, &value) in vec.iter() {
-            // TODO: unsafe access to value? needs bench
-            *tv = *tv + *in_vec.index(col_ind) * value;
+            // Safety: col_ind < in_vec.dim(), all the indices of vec have
+            // been checked above
+            let x = unsafe { in_vec.index_unchecked(col_ind) };
+            *tv = *tv + *x * value;
+        }
+    }
+}
+
+/// Multiply a sparse CSR matrix with a dense vector and accumulate the result
+/// into another dense vector, distributing the rows over the rayon thread
+/// pool.
+///
+/// Each row of a CSR matrix contributes to a single element of `res_vec`,
+/// so the rows can be processed in parallel without any synchronization.
+/// This is not the case for a CSC matrix, which is therefore not supported.
+///
+/// # Panics
+///
+/// - on dimension mismatch
+/// - if `mat` is not a CSR matrix
+#[cfg(feature = "multi_thread")]
+pub fn mul_acc_mat_vec_csr_par<N, I, Iptr, V>(
+    mat: CsMatViewI<N, I, Iptr>,
+    in_vec: V,
+    res_vec: &mut [N],
+) where
+    N: Num + Copy + Send + Sync,
+    I: SpIndex + Sync,
+    Iptr: SpIndex + Sync,
+    V: DenseVector<N> + Sync,
+{
+    if mat.cols() != in_vec.dim() || mat.rows() != res_vec.len() {
+        panic!("Dimension mismatch");
+    }
+    if !mat.is_csr() {
+        panic!("Storage mismatch");
+    }
+
+    // process rows by batches, a single row is usually too little work
+    // to amortize the cost of a task
+    res_vec
+        .par_iter_mut()
+        .enumerate()
+        .with_min_len(1024)
+        .for_each(|(row_ind, tv)| {
+            // row_ind is in [0, mat.rows()) as checked above
+            let vec = mat.outer_view(row_ind).unwrap();
+            for (col_ind, &value) in vec.iter() {
+                *tv = *tv + *in_vec.index(col_ind) * value;
+            }
+        });
+}
+
+/// Compute `y = alpha * A * x + beta * y`, where `A` is a sparse matrix
+/// and `x` and `y` are dense vectors, in a single pass over `A`.
+///
+/// If `beta` is zero, `y` is overwritten without being read, so that its
+/// previous content does not matter (even if it contains NaNs). If `beta`
+/// is one, the product is simply accumulated into `y`.
+///
+/// Both CSR and CSC matrices are supported.
+///
+/// # Panics
+///
+/// On dimension mismatch.
+///
+/// # Example
+///
+/// ```rust
+/// use sprs::{prod, CsMat};
+/// let a = CsMat::new((2, 2), vec![0, 1, 2], vec![1, 0], vec![1., 2.]);
+/// let mut y = vec![1., 1.];
+/// prod::gaxpy(2., a.view(), &[3., 4.][..], -1., &mut y);
+/// assert_eq!(y, vec![7., 11.]);
+/// ```
+pub fn gaxpy<N, I, Iptr, V>(
+    alpha: N,
+    a: CsMatViewI<N, I, Iptr>,
+    x: V,
+    beta: N,
+    y: &mut [N],
+) where
+    N: Num + Copy,
+    I: SpIndex,
+    Iptr: SpIndex,
+    V: DenseVector<N>,
+{
+    if a.cols() != x.dim() || a.rows() != y.len() {
+        panic!("Dimension mismatch");
+    }
+    if beta.is_zero() {
+        for yi in y.iter_mut() {
+            *yi = N::zero();
+        }
+    } else if !beta.is_one() {
+        for yi in y.iter_mut() {
+            *yi = beta * *yi;
+        }
+    }
+    if a.is_csr() {
+        for (row_ind, vec) in a.outer_iterator().enumerate() {
+            let mut sum = N::zero();
+            for (col_ind, &value) in vec.iter() {
+                sum = sum + *x.index(col_ind) * value;
+            }
+            y[row_ind] = y[row_ind] + alpha * sum;
+        }
+    } else {
+        for (col_ind, vec) in a.outer_iterator().enumerate() {
+            let multiplier = alpha * *x.index(col_ind);
+            for (row_ind, &value) in vec.iter() {
+                y[row_ind] = y[row_ind] + multiplier * value;
+            }
         }
     }
 }
 
+/// Compute the Gram matrix `A^T A` of a sparse matrix
+///
+/// The result is a symmetric `(a.cols(), a.cols())` matrix in CSR storage.
+/// It is computed as a sparse product of `A^T` and `A`, both in CSR storage,
+/// so that only the structurally non-zero products are evaluated. As the
+/// transpose of a CSC matrix is a CSR matrix, this only requires converting
+/// `a` to the other storage, never transposing it explicitly.
+///
+/// The entries `(i, j)` and `(j, i)` are accumulated in the same order,
+/// so the result is exactly symmetric.
+pub fn gram<N, I, Iptr>(a: CsMatViewI<N, I, Iptr>) -> CsMatI<N, I, Iptr>
+where
+    N: Num + Copy + Default + std::ops::AddAssign + Send + Sync,
+    I: SpIndex,
+    Iptr: SpIndex,
+{
+    let other = a.to_other_storage();
+    let (a_csr, a_csc) = if a.is_csr() {
+        (a, other.view())
+    } else {
+        (other.view(), a)
+    };
+    crate::smmp::mul_csr_csr(a_csc.transpose_view(), a_csr)
+}
+
+/// Sum of the dot products of the matching outer vectors of two matrices
+/// with the same shape and storage, ie `sum_ij A[i, j] * B[i, j]`
+fn sum_outer_dots<N, I, Iptr>(
+    a: CsMatViewI<N, I, Iptr>,
+    b: CsMatViewI<N, I, Iptr>,
+) -> N
+where
+    N: Num + Copy + Sum,
+    I: SpIndex,
+    Iptr: SpIndex,
+{
+    a.outer_iterator()
+        .zip(b.outer_iterator())
+        .map(|(a_vec, b_vec)| a_vec.dot(&b_vec))
+        .sum()
+}
+
+/// Compute the trace of the product of two matrices, `tr(A B)`, without
+/// computing the product.
+///
+/// The trace is the sum over `i` of the dot products of the row `i` of
+/// `A` and the column `i` of `B`, which only involves the products of
+/// the non-zeros of `A` with the matching non-zeros of `B^T`. This is
+/// cheapest for a CSR `A` and a CSC `B`; for other storages one of the
+/// matrices is converted.
+///
+/// # Panics
+///
+/// On dimension mismatch, ie if `a.cols() != b.rows()` or
+/// `a.rows() != b.cols()`.
+///
+/// # Example
+///
+/// ```rust
+/// use sprs::{prod, CsMat};
+/// // | 1 2 |   | 0 1 |   | 2 1 |
+/// // | 0 3 | x | 1 0 | = | 3 0 |
+/// let a = CsMat::new((2, 2), vec![0, 2, 3], vec![0, 1, 1], vec![1, 2, 3]);
+/// let b = CsMat::new((2, 2), vec![0, 1, 2], vec![1, 0], vec![1, 1]);
+/// assert_eq!(prod::trace_of_product(a.view(), b.view()), 2);
+/// ```
+pub fn trace_of_product<N, I, Iptr>(
+    a: CsMatViewI<N, I, Iptr>,
+    b: CsMatViewI<N, I, Iptr>,
+) -> N
+where
+    N: Num + Copy + Default + Sum,
+    I: SpIndex,
+    Iptr: SpIndex,
+{
+    if a.cols() != b.rows() || a.rows() != b.cols() {
+        panic!("Dimension mismatch");
+    }
+    let b_t = b.transpose_view();
+    if a.storage() == b_t.storage() {
+        sum_outer_dots(a, b_t)
+    } else {
+        let b_t = b_t.to_other_storage();
+        sum_outer_dots(a, b_t.view())
+    }
+}
+
+/// Compute the Frobenius inner product of two matrices,
+/// `sum_ij A[i, j] * B[i, j]`, which is also `tr(A^T B)`.
+///
+/// The sparsity patterns of the matching outer vectors are intersected,
+/// so only the locations that are non-zero in both matrices contribute.
+///
+/// # Panics
+///
+/// - on shape mismatch
+/// - on storage mismatch
+///
+/// # Example
+///
+/// ```rust
+/// use sprs::{prod, CsMat};
+/// let a = CsMat::new((2, 2), vec![0, 2, 3], vec![0, 1, 1], vec![1, 2, 3]);
+/// let b = CsMat::new((2, 2), vec![0, 1, 2], vec![1, 1], vec![4, 5]);
+/// assert_eq!(prod::frobenius_inner(a.view(), b.view()), 2 * 4 + 3 * 5);
+/// ```
+pub fn frobenius_inner<N, I, Iptr>(
+    a: CsMatViewI<N, I, Iptr>,
+    b: CsMatViewI<N, I, Iptr>,
+) -> N
+where
+    N: Num + Copy + Sum,
+    I: SpIndex,
+    Iptr: SpIndex,
+{
+    if a.shape() != b.shape() {
+        panic!("Dimension mismatch");
+    }
+    if a.storage() != b.storage() {
+        panic!("Storage mismatch");
+    }
+    sum_outer_dots(a, b)
+}
+
 /// Allocate the appropriate workspace for a CSR-CSR product
 pub fn workspace_csr<N, I, Iptr, Mat1, Mat2>(_: &Mat1, rhs: &Mat2) -> Vec<N>
 where
@@ -157,6 +429,40 @@ where
     res
 }
 
+/// Outer product of two sparse vectors, `u * v^T`, as a CSR matrix
+///
+/// The result has shape `(u.dim(), v.dim())`. Its non-empty rows
+/// correspond to the non-zero locations of `u`, and the row `i` is equal
+/// to `u[i] * v`.
+pub fn outer_product<N, I>(
+    u: CsVecViewI<N, I>,
+    v: CsVecViewI<N, I>,
+) -> CsMatI<N, I>
+where
+    N: Copy + Num,
+    I: SpIndex,
+{
+    let nnz = u.nnz() * v.nnz();
+    let mut indptr = Vec::with_capacity(u.dim() + 1);
+    let mut indices = Vec::with_capacity(nnz);
+    let mut data = Vec::with_capacity(nnz);
+    indptr.push(I::zero());
+    for (row_ind, &u_val) in u.iter() {
+        while indptr.len() <= row_ind {
+            indptr.push(I::from_usize(indices.len()));
+        }
+        indices.extend_from_slice(v.indices());
+        data.extend(v.data().iter().map(|&v_val| u_val * v_val));
+        indptr.push(I::from_usize(indices.len()));
+    }
+    while indptr.len() <= u.dim() {
+        indptr.push(I::from_usize(indices.len()));
+    }
+    // Correctness: the row indices of u are sorted and in bounds, and each
+    // row shares the sorted, in bounds, indices of v.
+    CsMatI::new_trusted(CSR, (u.dim(), v.dim()), indptr, indices, data)
+}
+
 /// CSR-dense rowmaj multiplication
 ///
 /// Performs better if rhs has a decent number of colums.
@@ -199,6 +505,43 @@ pub fn csr_mulacc_dense_rowmaj<'a, N1, N2, NOut, I, Iptr>(
     }
 }
 
+/// Multiply a sparse matrix with a dense matrix stored as a row-major
+/// slice, returning the row-major dense result.
+///
+/// `rhs` holds a `(lhs.cols(), rhs_cols)` matrix, and the result is a
+/// `(lhs.rows(), rhs_cols)` matrix. The product is computed in a single pass
+/// over `lhs`, each non-zero of `lhs` scaling a whole row of `rhs`, using
+/// [`csr_mulacc_dense_rowmaj`](fn.csr_mulacc_dense_rowmaj.html) or
+/// [`csc_mulacc_dense_rowmaj`](fn.csc_mulacc_dense_rowmaj.html)
+/// depending on the storage of `lhs`.
+///
+/// # Panics
+///
+/// If `rhs.len() != lhs.cols() * rhs_cols`.
+pub fn csr_mul_dense<N, I, Iptr>(
+    lhs: CsMatViewI<N, I, Iptr>,
+    rhs: &[N],
+    rhs_cols: usize,
+) -> Vec<N>
+where
+    N: Num + Copy,
+    I: SpIndex,
+    Iptr: SpIndex,
+{
+    if rhs.len() != lhs.cols() * rhs_cols {
+        panic!("Dimension mismatch");
+    }
+    let rhs = ArrayView::from_shape((lhs.cols(), rhs_cols), rhs)
+        .expect("the shape was checked");
+    let mut out = ndarray::Array2::zeros((lhs.rows(), rhs_cols));
+    if lhs.is_csr() {
+        csr_mulacc_dense_rowmaj(lhs, rhs, out.view_mut());
+    } else {
+        csc_mulacc_dense_rowmaj(lhs, rhs, out.view_mut());
+    }
+    out.into_raw_vec()
+}
+
 /// CSC-dense rowmaj multiplication
 ///
 /// Performs better if rhs has a decent number of colums.
@@ -322,7 +665,7 @@ pub fn csr_mulacc_dense_colmaj<'a, N1, N2, NOut, I, Iptr>(
 mod test {
     use super::*;
     use crate::sparse::csmat::CompressedStorage::{CSC, CSR};
-    use crate::sparse::{CsMat, CsMatView, CsVec};
+    use crate::sparse::{CsMat, CsMatI, CsMatView, CsVec};
     use crate::test_data::{
         mat1, mat1_csc, mat1_csc_matprod_mat4, mat1_matprod_mat2,
         mat1_self_matprod, mat2, mat4, mat5, mat_dense1, mat_dense1_colmaj,
@@ -331,6 +674,31 @@ mod test {
     use ndarray::linalg::Dot;
     use ndarray::{arr2, s, Array, Array2, Dimension, ShapeBuilder};
 
+    #[test]
+    fn mul_mat_vec_complex() {
+        use num_complex::Complex64;
+        let c = Complex64::new;
+        // | 1+i  0  |   | 2 |   | 2+2i  |
+        // | 2   -i  | x | i | = | 5     |
+        // | 0   3+i |           | -1+3i |
+        let mat = CsMat::new(
+            (3, 2),
+            vec![0, 1, 3, 4],
+            vec![0, 0, 1, 1],
+            vec![c(1., 1.), c(2., 0.), c(0., -1.), c(3., 1.)],
+        );
+        let x = vec![c(2., 0.), c(0., 1.)];
+        let expected = vec![c(2., 2.), c(5., 0.), c(-1., 3.)];
+
+        let mut res = vec![c(0., 0.); 3];
+        mul_acc_mat_vec_csr(mat.view(), &x[..], &mut res);
+        assert_eq!(res, expected);
+
+        let mut res = vec![c(0., 0.); 3];
+        mul_acc_mat_vec_csc(mat.to_csc().view(), &x[..], &mut res);
+        assert_eq!(res, expected);
+    }
+
     #[test]
     fn test_csvec_dot_by_binary_search() {
         let vec1 = CsVecI::new(8, vec![0, 2, 4, 6], vec![1.; 4]);
@@ -396,6 +764,87 @@ mod test {
             .all(|(x, y)| (*x - *y).abs() < epsilon));
     }
 
+    #[test]
+    fn mul_mat_slice() {
+        // mat1 times [1, 2, 3, 4, 5]
+        let x: &[f64] = &[1., 2., 3., 4., 5.];
+        let expected_output = vec![25., 33., 15., 16., 28.];
+
+        let a = mat1();
+        let res = &a * x;
+        assert_eq!(expected_output, res);
+
+        let a_csc = mat1_csc();
+        let res = &a_csc * x;
+        assert_eq!(expected_output, res);
+
+        let y = vec![1., 0., 0., 0., 0.];
+        assert_eq!(&a * &y[..], vec![0.; 5]);
+    }
+
+    #[test]
+    fn mul_mat_slice_u32_indices() {
+        let a = mat1();
+        let a_u32: CsMatI<f64, u32> = CsMatI::new(
+            a.shape(),
+            a.indptr().iter().map(|&i| i as u32).collect(),
+            a.indices().iter().map(|&i| i as u32).collect(),
+            a.data().to_vec(),
+        );
+        assert_eq!(a_u32.nnz(), a.nnz());
+        assert_eq!(a_u32.get(4, 3), Some(&7.));
+        let x: &[f64] = &[1., 2., 3., 4., 5.];
+        assert_eq!(&a_u32 * x, vec![25., 33., 15., 16., 28.]);
+        assert_eq!(&a_u32.to_csc() * x, vec![25., 33., 15., 16., 28.]);
+
+        let mut res = vec![0.; 5];
+        mul_acc_mat_vec_csr(a_u32.view(), x, &mut res);
+        assert_eq!(res, vec![25., 33., 15., 16., 28.]);
+    }
+
+    #[test]
+    #[cfg(feature = "multi_thread")]
+    fn mul_mat_vec_par() {
+        // block diagonal matrix made of copies of mat1
+        let eye = CsMat::<f64>::eye(200);
+        let a = crate::kronecker_product(eye.view(), mat1().view());
+        let x: Vec<f64> = (0..a.cols()).map(|i| (i % 7) as f64 - 3.).collect();
+        let mut serial = vec![0.; a.rows()];
+        mul_acc_mat_vec_csr(a.view(), &x[..], &mut serial);
+        let mut par = vec![0.; a.rows()];
+        mul_acc_mat_vec_csr_par(a.view(), &x[..], &mut par);
+        assert_eq!(serial, par);
+
+        // the result is accumulated
+        mul_acc_mat_vec_csr_par(a.view(), &x[..], &mut par);
+        let doubled: Vec<f64> = serial.iter().map(|&y| 2. * y).collect();
+        assert_eq!(par, doubled);
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(feature = "multi_thread")]
+    fn mul_mat_vec_par_csc() {
+        let a = mat1_csc();
+        let x = [1.; 5];
+        let mut res = [0.; 5];
+        mul_acc_mat_vec_csr_par(a.view(), &x[..], &mut res);
+    }
+
+    #[test]
+    #[should_panic]
+    fn mul_mat_slice_dim_mismatch() {
+        let x: &[f64] = &[1., 2., 3.];
+        let _ = &mat1() * x;
+    }
+
+    #[test]
+    #[should_panic]
+    fn mul_csc_slice_dim_mismatch() {
+        let x: &[f64] = &[1., 2., 3.];
+        let _ = &mat1_csc() * x;
+    }
+
     #[test]
     fn mul_csr_csr() {
         let a = mat1();
@@ -431,6 +880,35 @@ mod test {
         assert_eq!(expected_output, res);
     }
 
+    #[test]
+    fn mul_all_storages() {
+        // | 1 . 2 |   | . 6 . |   |  . 24  . |
+        // | . 3 . | x | 7 . 8 | = | 21  . 24 |
+        // | 4 . 5 |   | . 9 . |   |  . 69  . |
+        let a = CsMat::new(
+            (3, 3),
+            vec![0, 2, 3, 5],
+            vec![0, 2, 1, 0, 2],
+            vec![1., 2., 3., 4., 5.],
+        );
+        let b = CsMat::new(
+            (3, 3),
+            vec![0, 1, 3, 4],
+            vec![1, 0, 2, 1],
+            vec![6., 7., 8., 9.],
+        );
+        let expected = arr2(&[[0., 24., 0.], [21., 0., 24.], [0., 69., 0.]]);
+        let (a_csc, b_csc) = (a.to_csc(), b.to_csc());
+
+        for lhs in &[&a, &a_csc] {
+            for rhs in &[&b, &b_csc] {
+                let res = *lhs * *rhs;
+                assert_eq!(res.storage(), lhs.storage());
+                assert_eq!(res.to_dense(), expected);
+            }
+        }
+    }
+
     #[test]
     fn mul_csr_csvec() {
         let a = mat1();
@@ -473,6 +951,36 @@ mod test {
         assert_eq!(expected_output, res);
     }
 
+    #[test]
+    fn outer_product() {
+        let u = CsVec::new(5, vec![0, 3], vec![2., -1.]);
+        let v = CsVec::new(4, vec![1, 2], vec![3., 4.]);
+        let res = super::outer_product(u.view(), v.view());
+        assert!(res.is_csr());
+        assert_eq!(res.shape(), (5, 4));
+        assert_eq!(res.nnz(), 4);
+        let expected = arr2(&[
+            [0., 6., 8., 0.],
+            [0., 0., 0., 0.],
+            [0., 0., 0., 0.],
+            [0., -3., -4., 0.],
+            [0., 0., 0., 0.],
+        ]);
+        assert_eq!(res.to_dense(), expected);
+
+        let mut u_dense = Array::zeros((5, 1));
+        u.scatter(u_dense.as_slice_mut().unwrap());
+        let mut v_dense = Array::zeros((1, 4));
+        v.scatter(v_dense.as_slice_mut().unwrap());
+        assert_eq!(res.to_dense(), u_dense.dot(&v_dense));
+
+        let empty = CsVec::<f64>::empty(4);
+        let res = super::outer_product(u.view(), empty.view());
+        assert_eq!(res.shape(), (5, 4));
+        assert_eq!(res.nnz(), 0);
+        assert_eq!(res.indptr(), &[0; 6]);
+    }
+
     #[test]
     fn mul_csr_dense_rowmaj() {
         let a: Array2<f64> = Array::eye(3);
@@ -656,4 +1164,146 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn gram_dense_reference() {
+        for a in &[mat1(), mat1_csc(), crate::test_data::mat3(), mat5()] {
+            let g = gram(a.view());
+            assert!(g.is_csr());
+            assert_eq!(g.shape(), (a.cols(), a.cols()));
+            let dense = a.to_dense();
+            let expected = dense.t().dot(&dense);
+            for (&x, &y) in g.to_dense().iter().zip(expected.iter()) {
+                assert!((x - y).abs() < 1e-10);
+            }
+            assert_eq!(g.transpose_view().to_csr(), g);
+            assert!(crate::is_symmetric(&g));
+        }
+    }
+
+    #[test]
+    fn csr_mul_dense_slice() {
+        let a = mat1();
+        // 5x3 row-major block
+        let b: Vec<f64> = (0..15).map(|x| x as f64 - 4.).collect();
+        let res = csr_mul_dense(a.view(), &b, 3);
+        assert_eq!(res.len(), 15);
+        for col in 0..3 {
+            let b_col: Vec<f64> = (0..5).map(|row| b[row * 3 + col]).collect();
+            let mut expected = vec![0.; 5];
+            mul_acc_mat_vec_csr(a.view(), &b_col[..], &mut expected);
+            for row in 0..5 {
+                assert_eq!(res[row * 3 + col], expected[row]);
+            }
+        }
+        assert_eq!(csr_mul_dense(mat1_csc().view(), &b, 3), res);
+    }
+
+    #[test]
+    #[should_panic(expected = "Dimension mismatch")]
+    fn csr_mul_dense_slice_fail_dim_mismatch() {
+        let _ = csr_mul_dense(mat1().view(), &[1.; 12], 3);
+    }
+
+    #[test]
+    fn gaxpy() {
+        let a = mat1();
+        let x = vec![1., -2., 3., 0.5, 2.];
+        let y0 = vec![4., 0., -1., 2., 1.];
+        let ax = &a * &x[..];
+        let expected: Vec<f64> = ax
+            .iter()
+            .zip(y0.iter())
+            .map(|(&axi, &yi)| 2. * axi - yi)
+            .collect();
+        for mat in &[a.view(), mat1_csc().view()] {
+            let mut y = y0.clone();
+            super::gaxpy(2., mat.view(), &x[..], -1., &mut y);
+            assert_eq!(y, expected);
+        }
+
+        // beta = 0 ignores the content of y
+        let mut y = vec![std::f64::NAN; 5];
+        super::gaxpy(1., a.view(), &x[..], 0., &mut y);
+        assert_eq!(y, ax);
+        // beta = 1 accumulates
+        let mut y = y0.clone();
+        super::gaxpy(1., a.view(), &x[..], 1., &mut y);
+        let acc: Vec<f64> =
+            ax.iter().zip(y0.iter()).map(|(&u, &v)| u + v).collect();
+        assert_eq!(y, acc);
+    }
+
+    #[test]
+    #[should_panic(expected = "Dimension mismatch")]
+    fn gaxpy_fail_dim_mismatch() {
+        let mut y = vec![0.; 4];
+        super::gaxpy(1., mat1().view(), &[1.; 5][..], 0., &mut y);
+    }
+
+    #[test]
+    fn trace_of_product() {
+        let mats = [mat1(), mat1_csc(), mat2(), mat2().to_csc()];
+        for a in mats.iter() {
+            for b in mats.iter() {
+                let expected = (a * b).trace();
+                let trace = super::trace_of_product(a.view(), b.view());
+                assert!((trace - expected).abs() < 1e-10);
+            }
+        }
+        // rectangular matrices
+        let a = mat5();
+        let b = a.transpose_view().map(|&x| x - 2.).to_csr();
+        let expected = a.to_dense().dot(&b.to_dense()).diag().sum();
+        let trace = super::trace_of_product(a.view(), b.view());
+        assert!((trace - expected).abs() < 1e-10);
+        let trace = super::trace_of_product(b.view(), a.view());
+        assert!((trace - expected).abs() < 1e-10);
+    }
+
+    #[test]
+    #[should_panic(expected = "Dimension mismatch")]
+    fn trace_of_product_fail_dim_mismatch() {
+        let a = CsMat::<f64>::zero((2, 3));
+        let _ = super::trace_of_product(a.view(), a.view());
+    }
+
+    #[test]
+    fn frobenius_inner() {
+        let pairs = [(mat1(), mat2()), (mat1(), mat1()), (mat2(), mat1())];
+        for (a, b) in pairs.iter() {
+            let expected: f64 = a
+                .to_dense()
+                .iter()
+                .zip(b.to_dense().iter())
+                .map(|(x, y)| x * y)
+                .sum();
+            let inner = super::frobenius_inner(a.view(), b.view());
+            assert!((inner - expected).abs() < 1e-10);
+            let inner_csc =
+                super::frobenius_inner(a.to_csc().view(), b.to_csc().view());
+            assert!((inner_csc - expected).abs() < 1e-10);
+            // this is the trace of A^T B
+            let trace = super::trace_of_product(a.transpose_view(), b.view());
+            assert!((trace - expected).abs() < 1e-10);
+        }
+        // the squared frobenius norm
+        let a = mat1();
+        let norm = a.norm_frobenius();
+        let inner = super::frobenius_inner(a.view(), a.view());
+        assert!((inner - norm * norm).abs() < 1e-10);
+    }
+
+    #[test]
+    #[should_panic(expected = "Storage mismatch")]
+    fn frobenius_inner_fail_storage_mismatch() {
+        let _ = super::frobenius_inner(mat1().view(), mat1_csc().view());
+    }
+
+    #[test]
+    #[should_panic(expected = "Dimension mismatch")]
+    fn frobenius_inner_fail_dim_mismatch() {
+        let a = CsMat::<f64>::zero((2, 3));
+        let _ = super::frobenius_inner(a.view(), a.transpose_view());
+    }
 }
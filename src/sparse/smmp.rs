@@ -256,6 +256,31 @@ where
     mul_csr_csr_with_workspace(lhs, rhs, &mut seens, &mut tmps)
 }
 
+/// Compute the sparse matrix product of two CSC matrices using the SMMP
+/// routines, producing a CSC matrix.
+///
+/// The CSC structure of `lhs * rhs` is the CSR structure of
+/// `rhs^T * lhs^T`, which lets us use the CSR routines on transposed views
+/// of the operands without copying or converting their storage.
+///
+/// # Panics
+///
+/// - if `lhs` or `rhs` is not CSC.
+/// - if `lhs.cols() != rhs.rows()`.
+pub fn mul_csc_csc<N, I, Iptr>(
+    lhs: CsMatViewI<N, I, Iptr>,
+    rhs: CsMatViewI<N, I, Iptr>,
+) -> CsMatI<N, I, Iptr>
+where
+    N: Num + Copy + std::ops::AddAssign + Send + Sync,
+    I: SpIndex,
+    Iptr: SpIndex,
+{
+    assert!(lhs.is_csc() && rhs.is_csc(), "Storage mismatch");
+    assert_eq!(lhs.cols(), rhs.rows());
+    mul_csr_csr(rhs.transpose_view(), lhs.transpose_view()).transpose_into()
+}
+
 /// Compute a sparse matrix product using the SMMP routines, using temporary
 /// storage that was already allocated
 ///
@@ -294,8 +319,9 @@ where
     assert!(tmps.iter().all(|x| x.len() == workspace_len));
     let indptr_len = l_rows + 1;
     let mut res_indices = Vec::new();
-    let nb_threads = seens.len();
-    assert!(nb_threads > 0);
+    assert!(!seens.is_empty());
+    // there cannot be more chunks than rows
+    let nb_threads = seens.len().min(l_rows.max(1));
     let chunk_size = lhs.indptr().len() / nb_threads;
     let mut lhs_indptr_chunks = Vec::with_capacity(nb_threads);
     let mut res_indptr_chunks = Vec::with_capacity(nb_threads);
@@ -447,7 +473,9 @@ where
 
 #[cfg(test)]
 mod test {
+    use crate::sparse::CsMat;
     use crate::test_data;
+    use ndarray::arr2;
 
     #[test]
     fn symbolic_and_numeric() {
@@ -509,6 +537,71 @@ mod test {
         assert_eq!(exp, res);
     }
 
+    #[test]
+    fn mul_csr_csr_dense_reference() {
+        // The sparsity patterns of the operands don't line up: a row of
+        // the lhs never shares its non-zero locations with a row of the rhs.
+        // | 1 . 2 |   | . 6 . |   |  . 24  . |
+        // | . 3 . | x | 7 . 8 | = | 21  . 24 |
+        // | 4 . 5 |   | . 9 . |   |  . 69  . |
+        let a = CsMat::new(
+            (3, 3),
+            vec![0, 2, 3, 5],
+            vec![0, 2, 1, 0, 2],
+            vec![1., 2., 3., 4., 5.],
+        );
+        let b = CsMat::new(
+            (3, 3),
+            vec![0, 1, 3, 4],
+            vec![1, 0, 2, 1],
+            vec![6., 7., 8., 9.],
+        );
+        let expected = arr2(&[[0., 24., 0.], [21., 0., 24.], [0., 69., 0.]]);
+        let res = super::mul_csr_csr(a.view(), b.view());
+        assert_eq!(res.to_dense(), expected);
+        assert_eq!(res.to_dense(), a.to_dense().dot(&b.to_dense()));
+        assert_eq!(res.nnz(), 4);
+    }
+
+    #[test]
+    fn mul_csc_csc_dense_reference() {
+        // Same operands as in mul_csr_csr_dense_reference, in CSC storage
+        // | 1 . 2 |   | . 6 . |   |  . 24  . |
+        // | . 3 . | x | 7 . 8 | = | 21  . 24 |
+        // | 4 . 5 |   | . 9 . |   |  . 69  . |
+        let a = CsMat::new_csc(
+            (3, 3),
+            vec![0, 2, 3, 5],
+            vec![0, 2, 1, 0, 2],
+            vec![1., 4., 3., 2., 5.],
+        );
+        let b = CsMat::new_csc(
+            (3, 3),
+            vec![0, 1, 3, 4],
+            vec![1, 0, 2, 1],
+            vec![7., 6., 9., 8.],
+        );
+        let expected = arr2(&[[0., 24., 0.], [21., 0., 24.], [0., 69., 0.]]);
+        let res = super::mul_csc_csc(a.view(), b.view());
+        assert!(res.is_csc());
+        assert_eq!(res.to_dense(), expected);
+        let res_csr = super::mul_csr_csr(a.to_csr().view(), b.to_csr().view());
+        assert_eq!(res, res_csr.to_csc());
+
+        // (a * b)^T = b^T * a^T
+        let a_t = a.transpose_view().to_csc();
+        let b_t = b.transpose_view().to_csc();
+        let res_t = super::mul_csc_csc(b_t.view(), a_t.view());
+        assert_eq!(res_t.to_dense(), expected.t());
+    }
+
+    #[test]
+    #[should_panic]
+    fn mul_csc_csc_fail_storage() {
+        let a = test_data::mat1();
+        let _ = super::mul_csc_csc(a.view(), a.view());
+    }
+
     #[test]
     #[cfg(feature = "multi_thread")]
     fn mul_csr_csr_multithreaded() {
@@ -520,4 +613,64 @@ mod test {
         let res = super::mul_csr_csr(a.view(), a.view());
         assert_eq!(exp, res);
     }
+
+    #[test]
+    #[cfg(feature = "multi_thread")]
+    fn mul_csr_csr_multithreaded_matches_serial() {
+        use super::ThreadingStrategy::Fixed;
+        let a = test_data::laplacian_2d(30);
+        let b = &a + &CsMat::eye(a.rows());
+        super::set_thread_threading_strategy(Fixed(1));
+        let serial = super::mul_csr_csr(a.view(), b.view());
+        let serial_small = super::mul_csr_csr(
+            test_data::mat1().view(),
+            test_data::mat1().view(),
+        );
+        for &nb_threads in &[2, 3, 7, 16] {
+            super::set_thread_threading_strategy(Fixed(nb_threads));
+            let res = super::mul_csr_csr(a.view(), b.view());
+            // the rows are assembled in order, so the storage is identical
+            assert_eq!(res.indptr(), serial.indptr());
+            assert_eq!(res.indices(), serial.indices());
+            assert_eq!(res.data(), serial.data());
+            // more threads than rows
+            let res = super::mul_csr_csr(
+                test_data::mat1().view(),
+                test_data::mat1().view(),
+            );
+            assert_eq!(res, serial_small);
+        }
+        super::set_thread_threading_strategy(
+            super::ThreadingStrategy::Automatic,
+        );
+    }
+
+    #[test]
+    fn mul_csr_csr_complex() {
+        use num_complex::Complex64;
+        let c = Complex64::new;
+        // | i  1 |   | i   0  |   | 1  1-i  |
+        // | 0  2 | x | 2  1-i | = | 4  2-2i |
+        let a = CsMat::new(
+            (2, 2),
+            vec![0, 2, 3],
+            vec![0, 1, 1],
+            vec![c(0., 1.), c(1., 0.), c(2., 0.)],
+        );
+        let b = CsMat::new(
+            (2, 2),
+            vec![0, 1, 3],
+            vec![0, 0, 1],
+            vec![c(0., 1.), c(2., 0.), c(1., -1.)],
+        );
+        let exp = CsMat::new(
+            (2, 2),
+            vec![0, 2, 4],
+            vec![0, 1, 0, 1],
+            vec![c(1., 0.), c(1., -1.), c(4., 0.), c(2., -2.)],
+        );
+        let res = super::mul_csr_csr(a.view(), b.view());
+        assert_eq!(exp, res);
+        assert_eq!(exp, &a * &b);
+    }
 }
@@ -1,9 +1,17 @@
 /// Functions dealing with symmetric sparse matrices
 use std::ops::Deref;
 
+use num_traits::Float;
+
 use crate::indexing::SpIndex;
 use crate::sparse::prelude::*;
 
+/// Check whether a matrix is exactly symmetric.
+///
+/// Returns `false` if the matrix is not square, or if a stored element
+/// `A[i, j]` does not have an equal stored mirror element `A[j, i]`.
+/// In particular, a stored element whose mirror is not stored makes
+/// the matrix non symmetric, even if it is an explicit zero.
 pub fn is_symmetric<N, I, Iptr, IpStorage, IStorage, DStorage>(
     mat: &CsMatBase<N, I, IpStorage, IStorage, DStorage, Iptr>,
 ) -> bool
@@ -33,11 +41,49 @@ where
     true
 }
 
+/// Check whether a matrix is symmetric up to an absolute tolerance, ie
+/// whether `|A[i, j] - A[j, i]| <= tol` for all `i` and `j`.
+///
+/// Contrary to `is_symmetric`, a stored element whose mirror is not stored
+/// is compared to zero.
+///
+/// Returns `false` if the matrix is not square.
+pub fn is_symmetric_approx<N, I, Iptr, IpStorage, IStorage, DStorage>(
+    mat: &CsMatBase<N, I, IpStorage, IStorage, DStorage, Iptr>,
+    tol: N,
+) -> bool
+where
+    N: Float,
+    I: SpIndex,
+    Iptr: SpIndex,
+    IpStorage: Deref<Target = [Iptr]>,
+    IStorage: Deref<Target = [I]>,
+    DStorage: Deref<Target = [N]>,
+{
+    if mat.rows() != mat.cols() {
+        return false;
+    }
+    for (outer_ind, vec) in mat.outer_iterator().enumerate() {
+        for (inner_ind, &value) in vec.iter() {
+            let transposed_val = mat
+                .get_outer_inner(inner_ind, outer_ind)
+                .cloned()
+                .unwrap_or_else(N::zero);
+            let diff = (value - transposed_val).abs();
+            if diff.is_nan() || diff > tol {
+                return false;
+            }
+        }
+    }
+    true
+}
+
 #[cfg(test)]
 mod test {
-    use super::is_symmetric;
+    use super::{is_symmetric, is_symmetric_approx};
     use crate::sparse::csmat::CompressedStorage::CSR;
-    use crate::sparse::CsMatView;
+    use crate::sparse::{CsMat, CsMatView};
+    use crate::test_data::{laplacian_2d, mat1};
 
     #[test]
     fn is_symmetric_simple() {
@@ -58,5 +104,56 @@ mod test {
         assert!(is_symmetric(&a));
     }
 
+    #[test]
+    fn is_symmetric_fixtures() {
+        let a = laplacian_2d(4);
+        assert!(is_symmetric(&a));
+        assert!(is_symmetric(&a.to_csc()));
+        assert!(a.is_symmetric());
+        assert!(is_symmetric_approx(&a, 0.));
+        assert!(!is_symmetric(&mat1()));
+        assert!(!is_symmetric_approx(&mat1(), 1.));
+        // non square
+        let b = CsMat::new((2, 3), vec![0, 1, 1], vec![0], vec![1.]);
+        assert!(!is_symmetric(&b));
+        assert!(!b.is_symmetric_approx(1.));
+    }
+
+    #[test]
+    fn is_symmetric_missing_mirror() {
+        // | 1 2 |
+        // | 0 1 |, with the mirror of the 2 missing
+        let a =
+            CsMat::new((2, 2), vec![0, 2, 3], vec![0, 1, 1], vec![1., 2., 1.]);
+        assert!(!is_symmetric(&a));
+        assert!(!a.is_symmetric_approx(1e-10));
+        assert!(a.is_symmetric_approx(2.));
+        // an explicit zero without mirror
+        let a =
+            CsMat::new((2, 2), vec![0, 2, 3], vec![0, 1, 1], vec![1., 0., 1.]);
+        assert!(!is_symmetric(&a));
+        assert!(a.is_symmetric_approx(0.));
+    }
+
+    #[test]
+    fn is_symmetric_approx_tolerance() {
+        let a = CsMat::new(
+            (2, 2),
+            vec![0, 2, 4],
+            vec![0, 1, 0, 1],
+            vec![1., 2., 2. + 1e-12, 1.],
+        );
+        assert!(!a.is_symmetric());
+        assert!(a.is_symmetric_approx(1e-10));
+        assert!(!a.is_symmetric_approx(1e-14));
+        let a = CsMat::new(
+            (2, 2),
+            vec![0, 2, 4],
+            vec![0, 1, 0, 1],
+            vec![1., std::f64::NAN, std::f64::NAN, 1.],
+        );
+        assert!(!a.is_symmetric_approx(1.));
+    }
+
     // TODO: symmetry test on A^T*A products
 }
@@ -153,9 +153,17 @@ impl<N, I: SpIndex> TriMatBase<Vec<I>, Vec<N>> {
     }
 
     /// Append a non-zero triplet to this matrix.
+    ///
+    /// Triplets can be added in any order, and several triplets can
+    /// share the same location, in which case they will be summed
+    /// when converting to a compressed matrix.
+    ///
+    /// # Panics
+    ///
+    /// - if the row or column index is out of bounds.
     pub fn add_triplet(&mut self, row: usize, col: usize, val: N) {
-        assert!(row < self.rows);
-        assert!(col < self.cols);
+        assert!(row < self.rows, "row index should be within shape");
+        assert!(col < self.cols, "col index should be within shape");
         self.row_inds.push(I::from_usize(row));
         self.col_inds.push(I::from_usize(col));
         self.data.push(val);
@@ -176,6 +184,34 @@ impl<N, I: SpIndex> TriMatBase<Vec<I>, Vec<N>> {
     }
 }
 
+/// Append `(row, col, value)` triplets to a triplet matrix, as with
+/// repeated calls to `add_triplet`.
+///
+/// This makes it easy to assemble a matrix in chunks before compressing it.
+///
+/// # Panics
+///
+/// - if a row or column index is out of bounds.
+///
+/// # Example
+///
+/// ```rust
+/// use sprs::TriMat;
+/// let mut tri = TriMat::new((2, 2));
+/// tri.extend(vec![(0, 0, 1.), (1, 1, 2.)]);
+/// tri.extend(vec![(0, 0, 3.)]);
+/// assert_eq!(tri.to_csr().get(0, 0), Some(&4.));
+/// ```
+impl<N, I: SpIndex> Extend<(usize, usize, N)> for TriMatBase<Vec<I>, Vec<N>> {
+    fn extend<T: IntoIterator<Item = (usize, usize, N)>>(&mut self, iter: T) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for (row, col, val) in iter {
+            self.add_triplet(row, col, val);
+        }
+    }
+}
+
 /// # Common methods shared by all variants of triplet matrices
 impl<N, I: SpIndex, IStorage, DStorage> TriMatBase<IStorage, DStorage>
 where
@@ -254,6 +290,9 @@ where
     }
 
     /// Create a CSC matrix from this triplet matrix
+    ///
+    /// Triplets sharing the same location are summed together, and the
+    /// indices of the resulting matrix are sorted.
     pub fn to_csc(&self) -> CsMatI<N, I>
     where
         N: Clone + Num,
@@ -262,6 +301,9 @@ where
     }
 
     /// Create a CSR matrix from this triplet matrix
+    ///
+    /// Triplets sharing the same location are summed together, and the
+    /// indices of the resulting matrix are sorted.
     pub fn to_csr(&self) -> CsMatI<N, I>
     where
         N: Clone + Num,
@@ -358,6 +400,36 @@ mod test {
         assert_eq!(csc, expected);
     }
 
+    #[test]
+    fn triplet_reserve() {
+        let mut triplet_mat = TriMat::new((3, 3));
+        triplet_mat.reserve(2);
+        assert_eq!(triplet_mat.nnz(), 0);
+        assert_eq!(triplet_mat.shape(), (3, 3));
+        triplet_mat.add_triplet(2, 0, 1.);
+        triplet_mat.add_triplet(0, 2, 2.);
+        triplet_mat.reserve_exact(4);
+        triplet_mat.add_triplet(1, 1, 3.);
+        assert_eq!(triplet_mat.nnz(), 3);
+        assert_eq!(triplet_mat.row_inds(), &[2, 0, 1]);
+        assert_eq!(triplet_mat.col_inds(), &[0, 2, 1]);
+        assert_eq!(triplet_mat.data(), &[1., 2., 3.]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn triplet_add_row_out_of_bounds() {
+        let mut triplet_mat = TriMat::new((3, 4));
+        triplet_mat.add_triplet(3, 0, 1.);
+    }
+
+    #[test]
+    #[should_panic]
+    fn triplet_add_col_out_of_bounds() {
+        let mut triplet_mat = TriMat::new((3, 4));
+        triplet_mat.add_triplet(0, 4, 1.);
+    }
+
     #[test]
     fn triplet_unordered() {
         let mut triplet_mat = TriMat::with_capacity((4, 4), 6);
@@ -420,6 +492,34 @@ mod test {
         assert_eq!(csr, expected.to_csr());
     }
 
+    #[test]
+    fn triplet_assembly() {
+        // assembly of the stiffness matrix of 1D linear finite elements,
+        // each element contributes to a 2x2 block overlapping its neighbours
+        let nb_elems = 4;
+        let mut triplet_mat = TriMat::new((nb_elems + 1, nb_elems + 1));
+        let mut dense = ndarray::Array2::<f64>::zeros((5, 5));
+        let elem = [[1., -1.], [-1., 1.]];
+        // add elements in reverse order to also test sorting
+        for e in (0..nb_elems).rev() {
+            for (i, elem_row) in elem.iter().enumerate() {
+                for (j, &val) in elem_row.iter().enumerate() {
+                    triplet_mat.add_triplet(e + i, e + j, val);
+                    dense[[e + i, e + j]] += val;
+                }
+            }
+        }
+        assert_eq!(triplet_mat.nnz(), 16);
+
+        let csr = triplet_mat.to_csr();
+        assert_eq!(csr.nnz(), 13);
+        assert_eq!(csr.to_dense(), dense);
+        assert_eq!(csr.indptr(), &[0, 2, 5, 8, 11, 13]);
+        assert_eq!(csr.indices(), &[0, 1, 0, 1, 2, 1, 2, 3, 2, 3, 4, 3, 4]);
+        let csc = triplet_mat.to_csc();
+        assert_eq!(csc, csr.to_csc());
+    }
+
     #[test]
     fn triplet_from_vecs() {
         // |1 2    |
@@ -635,4 +735,35 @@ mod test {
         assert_eq!(m.indices(), &[1, 0]);
         assert_eq!(m.data(), &[1, 2]);
     }
+
+    #[test]
+    fn extend_in_chunks() {
+        let triplets: Vec<(usize, usize, f64)> = (0..60)
+            .map(|k| ((k * 7) % 10, (k * 3) % 8, k as f64))
+            .collect();
+        let mut tri = TriMat::new((10, 8));
+        for chunk in triplets.chunks(7) {
+            tri.extend(chunk.iter().cloned());
+        }
+        assert_eq!(tri.nnz(), 60);
+        let (rows, cols, data) = triplets.iter().fold(
+            (vec![], vec![], vec![]),
+            |(mut rows, mut cols, mut data), &(r, c, v)| {
+                rows.push(r);
+                cols.push(c);
+                data.push(v);
+                (rows, cols, data)
+            },
+        );
+        let expected = TriMat::from_triplets((10, 8), rows, cols, data);
+        assert_eq!(tri.to_csr(), expected.to_csr());
+        assert_eq!(tri.to_csc(), expected.to_csc());
+    }
+
+    #[test]
+    #[should_panic]
+    fn extend_fail_out_of_bounds() {
+        let mut tri = TriMat::new((2, 2));
+        tri.extend(vec![(0, 0, 1.), (2, 0, 1.)]);
+    }
 }
@@ -355,6 +355,18 @@ pub trait DenseVector<N> {
     ///
     /// If the index is out of bounds
     fn index(&self, idx: usize) -> &N;
+
+    /// Random access to an element in the vector, without bounds checking.
+    ///
+    /// The default implementation falls back to the checked `index`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `idx < self.dim()`.
+    #[inline]
+    unsafe fn index_unchecked(&self, idx: usize) -> &N {
+        self.index(idx)
+    }
 }
 
 impl<'a, N: 'a> DenseVector<N> for &'a [N] {
@@ -366,6 +378,11 @@ impl<'a, N: 'a> DenseVector<N> for &'a [N] {
     fn index(&self, idx: usize) -> &N {
         &self[idx]
     }
+
+    #[inline]
+    unsafe fn index_unchecked(&self, idx: usize) -> &N {
+        self.get_unchecked(idx)
+    }
 }
 
 impl<N> DenseVector<N> for Vec<N> {
@@ -377,6 +394,11 @@ impl<N> DenseVector<N> for Vec<N> {
     fn index(&self, idx: usize) -> &N {
         &self[idx]
     }
+
+    #[inline]
+    unsafe fn index_unchecked(&self, idx: usize) -> &N {
+        self.get_unchecked(idx)
+    }
 }
 
 impl<'a, N: 'a> DenseVector<N> for &'a Vec<N> {
@@ -388,6 +410,11 @@ impl<'a, N: 'a> DenseVector<N> for &'a Vec<N> {
     fn index(&self, idx: usize) -> &N {
         &self[idx]
     }
+
+    #[inline]
+    unsafe fn index_unchecked(&self, idx: usize) -> &N {
+        self.get_unchecked(idx)
+    }
 }
 
 impl<N, S> DenseVector<N> for ArrayBase<S, Ix1>
@@ -402,6 +429,11 @@ where
     fn index(&self, idx: usize) -> &N {
         &self[[idx]]
     }
+
+    #[inline]
+    unsafe fn index_unchecked(&self, idx: usize) -> &N {
+        self.uget(idx)
+    }
 }
 
 /// An iterator over the non zeros of either of two vector iterators, ordered,
@@ -507,6 +539,11 @@ impl<N, I: SpIndex> CsVecBase<Vec<I>, Vec<N>> {
     where
         N: Copy,
     {
+        if indices.len() != data.len() {
+            return Err(SprsError::IllegalArguments(
+                "indices and data lengths differ",
+            ));
+        }
         let mut buf = Vec::with_capacity(indices.len());
         utils::sort_indices_data_slices(
             &mut indices[..],
@@ -648,9 +685,16 @@ where
     }
 
     /// Check the sparse structure, namely that:
+    /// - indices and data have the same length
     /// - indices is sorted
     /// - indices are lower than dims()
     pub fn check_structure(&self) -> Result<(), SprsError> {
+        if self.indices.len() != self.data.len() {
+            return Err(SprsError::IllegalArguments(
+                "indices and data lengths differ",
+            ));
+        }
+
         // Make sure indices can be converted to usize
         for i in self.indices.iter() {
             i.index();
@@ -852,6 +896,34 @@ where
             .sum()
     }
 
+    /// Compute `y += alpha * self`, where `y` is a dense vector, only
+    /// touching the elements of `y` at the non-zero locations of `self`.
+    ///
+    /// This is the scatter step of iterative solvers.
+    ///
+    /// # Panics
+    ///
+    /// If the dimension of the vectors do not match.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sprs::CsVec;
+    /// let x = CsVec::new(4, vec![1, 3], vec![1., 2.]);
+    /// let mut y = vec![1.; 4];
+    /// x.axpy_dense(2., &mut y);
+    /// assert_eq!(y, vec![1., 3., 1., 5.]);
+    /// ```
+    pub fn axpy_dense(&self, alpha: N, y: &mut [N])
+    where
+        N: Num + Copy,
+    {
+        assert_eq!(self.dim(), y.len());
+        for (ind, &val) in self.iter() {
+            y[ind] = y[ind] + alpha * val;
+        }
+    }
+
     /// Compute the squared L2-norm.
     pub fn squared_l2_norm(&self) -> N
     where
@@ -1562,6 +1634,61 @@ mod test {
         assert_eq!(view1, view2);
     }
 
+    #[test]
+    fn new_sorts_indices() {
+        let v = CsVec::new(5, vec![3, 0, 1], vec![3., 0.5, 1.]);
+        assert_eq!(v.indices(), &[0, 1, 3]);
+        assert_eq!(v.data(), &[0.5, 1., 3.]);
+        let elems: Vec<_> = v.iter().collect();
+        assert_eq!(elems, vec![(0, &0.5), (1, &1.), (3, &3.)]);
+    }
+
+    #[test]
+    fn try_new_fails_on_bad_structure() {
+        use crate::errors::SprsError;
+        let res = CsVec::try_new(5, vec![0, 1, 5], vec![1., 2., 3.]);
+        assert_eq!(
+            res.unwrap_err(),
+            SprsError::IllegalArguments("Out of bounds index")
+        );
+        let res = CsVec::try_new(5, vec![0, 2, 2], vec![1., 2., 3.]);
+        assert_eq!(res.unwrap_err(), SprsError::NonSortedIndices);
+        let res = CsVec::try_new(5, vec![0, 2], vec![1., 2., 3.]);
+        assert_eq!(
+            res.unwrap_err(),
+            SprsError::IllegalArguments("indices and data lengths differ")
+        );
+    }
+
+    #[test]
+    fn new_view_checks_structure() {
+        use crate::errors::SprsError;
+        use crate::sparse::CsVecView;
+        let ok = CsVecView::new_view(5, &[0, 2, 4], &[1., 2., 3.]);
+        assert!(ok.is_ok());
+        let unsorted = CsVecView::new_view(5, &[2, 0, 4], &[1., 2., 3.]);
+        assert_eq!(unsorted.unwrap_err(), SprsError::NonSortedIndices);
+        let oob = CsVecView::new_view(4, &[0, 2, 4], &[1., 2., 3.]);
+        assert!(oob.is_err());
+        let lengths = CsVecView::new_view(5, &[0, 2, 4], &[1., 2.]);
+        assert!(lengths.is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_panics_on_out_of_bounds_index() {
+        let _ = CsVec::new(3, vec![0, 3], vec![1, 2]);
+    }
+
+    #[test]
+    fn empty_vec() {
+        let v: CsVec<f64> = CsVec::empty(7);
+        assert_eq!(v.dim(), 7);
+        assert_eq!(v.nnz(), 0);
+        assert!(v.check_structure().is_ok());
+        assert_eq!(v.iter().next(), None);
+    }
+
     #[test]
     fn test_nnz_zip_iter() {
         let vec1 = test_vec1();
@@ -1615,6 +1742,22 @@ mod test {
         assert_eq!(16., vec1.dot_dense(ndarray_vec.view()));
     }
 
+    #[test]
+    fn dot_product_supports() {
+        let vec1 = CsVec::new(6, vec![0, 2, 5], vec![1., 2., 3.]);
+        let overlapping = CsVec::new(6, vec![1, 2, 3, 5], vec![4., 5., 6., 7.]);
+        let disjoint = CsVec::new(6, vec![1, 3, 4], vec![4., 5., 6.]);
+        let empty = CsVec::<f64>::empty(6);
+
+        assert_eq!(31., vec1.dot(&overlapping));
+        assert_eq!(31., overlapping.dot(&vec1));
+        assert_eq!(0., vec1.dot(&disjoint));
+        assert_eq!(14., vec1.dot(&vec1));
+        assert_eq!(0., vec1.dot(&empty));
+        assert_eq!(0., empty.dot(&vec1));
+        assert_eq!(0., empty.dot(&empty));
+    }
+
     #[test]
     #[should_panic]
     fn dot_product_panics() {
@@ -1813,6 +1956,28 @@ mod test {
         (a, b, expected_sum)
     }
 
+    #[test]
+    fn addition_drops_cancelled_values() {
+        let a = CsVec::new(6, vec![0, 2, 3, 5], vec![1., 2., 3., 4.]);
+        let b = CsVec::new(6, vec![1, 2, 5], vec![1., -2., 1.]);
+        let sum = &a + &b;
+        let expected = CsVec::new(6, vec![0, 1, 3, 5], vec![1., 1., 3., 5.]);
+        assert_eq!(sum, expected);
+        assert!(sum.indices().windows(2).all(|x| x[0] < x[1]));
+
+        let diff = &a - &a;
+        assert_eq!(diff.nnz(), 0);
+        assert_eq!(diff.dim(), 6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn addition_dim_mismatch() {
+        let a = CsVec::new(6, vec![0, 2], vec![1., 2.]);
+        let b = CsVec::new(5, vec![1, 2], vec![1., -2.]);
+        let _ = &a + &b;
+    }
+
     #[test]
     fn negates_vectors() {
         let vector = CsVec::new(4, vec![0, 3], vec![2., -3.]);
@@ -1850,6 +2015,27 @@ mod test {
         assert_eq!(vector, CsVec::new(4, vec![1, 2, 3], vec![2_i32, 6, 8]));
     }
 
+    #[test]
+    fn axpy_dense() {
+        let x = CsVec::new(6, vec![0, 2, 5], vec![1., -2., 0.5]);
+        let mut y = vec![0.; 6];
+        for _ in 0..4 {
+            x.axpy_dense(1.5, &mut y);
+        }
+        assert_eq!(y, vec![6., 0., -12., 0., 0., 3.]);
+        x.axpy_dense(-6., &mut y);
+        assert_eq!(y, vec![0.; 6]);
+        CsVec::empty(6).axpy_dense(2., &mut y);
+        assert_eq!(y, vec![0.; 6]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn axpy_dense_fail_dim_mismatch() {
+        let x = CsVec::new(6, vec![0, 2, 5], vec![1., -2., 0.5]);
+        x.axpy_dense(1., &mut [0.; 5]);
+    }
+
     #[test]
     fn div_assign() {
         let mut vector = CsVec::new(4, vec![1, 2, 3], vec![1_i32, 3, 4]);
@@ -1,6 +1,6 @@
 //! Some matrices used in tests
 
-use crate::sparse::CsMat;
+use crate::sparse::{CsMat, TriMat};
 use ndarray::{arr2, Array, Ix2, ShapeBuilder};
 
 pub fn mat1() -> CsMat<f64> {
@@ -122,3 +122,36 @@ pub fn mat_dense2() -> Array<f64, Ix2> {
     ]);
     m.to_owned()
 }
+
+/// The 1D Laplacian `tridiag(-1, 2, -1)` of size n, in CSR storage
+pub fn laplacian_1d(n: usize) -> CsMat<f64> {
+    let mut tri = TriMat::new((n, n));
+    for i in 0..n {
+        tri.add_triplet(i, i, 2.);
+        if i > 0 {
+            tri.add_triplet(i, i - 1, -1.);
+            tri.add_triplet(i - 1, i, -1.);
+        }
+    }
+    tri.to_csr()
+}
+
+/// The 5-point 2D Laplacian on a n x n grid, in CSR storage
+pub fn laplacian_2d(n: usize) -> CsMat<f64> {
+    let mut tri = TriMat::new((n * n, n * n));
+    for i in 0..n {
+        for j in 0..n {
+            let ind = i * n + j;
+            tri.add_triplet(ind, ind, 4.);
+            if i > 0 {
+                tri.add_triplet(ind, ind - n, -1.);
+                tri.add_triplet(ind - n, ind, -1.);
+            }
+            if j > 0 {
+                tri.add_triplet(ind, ind - 1, -1.);
+                tri.add_triplet(ind - 1, ind, -1.);
+            }
+        }
+    }
+    tri.to_csr()
+}
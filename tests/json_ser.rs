@@ -0,0 +1,32 @@
+#![cfg(feature = "serde")]
+
+use sprs::CsMat;
+
+#[test]
+fn json_round_trip() {
+    let mat =
+        CsMat::new((3, 3), vec![0, 2, 2, 3], vec![0, 2, 1], vec![1., -2., 3.]);
+    for mat in [mat.clone(), mat.to_csc()].iter() {
+        let serialized = serde_json::to_string(mat).unwrap();
+        let deserialized: CsMat<f64> =
+            serde_json::from_str(&serialized).unwrap();
+        assert_eq!(&deserialized, mat);
+    }
+    // a view is serialized as the owned matrix
+    let serialized = serde_json::to_string(&mat.view()).unwrap();
+    assert_eq!(serialized, serde_json::to_string(&mat).unwrap());
+    let deserialized: CsMat<f64> = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, mat);
+}
+
+#[test]
+fn json_corrupted_fails() {
+    // non monotonic indptr
+    let json = r#"{"storage":"CSR","nrows":3,"ncols":3,"indptr":[0,2,1,3],
+                   "indices":[0,2,1],"data":[1.0,-2.0,3.0]}"#;
+    assert!(serde_json::from_str::<CsMat<f64>>(json).is_err());
+    // out of bounds inner index
+    let json = r#"{"storage":"CSR","nrows":3,"ncols":3,"indptr":[0,2,2,3],
+                   "indices":[0,3,1],"data":[1.0,-2.0,3.0]}"#;
+    assert!(serde_json::from_str::<CsMat<f64>>(json).is_err());
+}